@@ -0,0 +1,157 @@
+//! Command-line interface for inspecting vim plugin metadata without writing any code.
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use std::error::Error;
+use std::fmt::Write;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use vim_plugin_metadata::{ValueRedaction, VimModule, VimNode, VimParser};
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Parses a plugin dir or a single module file and prints its metadata.
+    Parse {
+        path: PathBuf,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+        /// Truncates variable and flag values longer than this many chars.
+        #[arg(long, value_name = "CHARS")]
+        max_value_len: Option<usize>,
+        /// Redacts the values of variables and flags whose names match this regex. Can be
+        /// repeated.
+        #[arg(long, value_name = "REGEX")]
+        redact_names: Vec<String>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum Format {
+    Json,
+    Yaml,
+    /// An outline of each module's definitions.
+    Text,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let Command::Parse {
+        path,
+        format,
+        max_value_len,
+        redact_names,
+    } = cli.command;
+    let mut redaction = ValueRedaction::new();
+    if let Some(max_value_len) = max_value_len {
+        redaction = redaction.truncate(max_value_len);
+    }
+    for pattern in &redact_names {
+        redaction = redaction.redact_names_matching(pattern)?;
+    }
+    let parser = VimParser::builder().value_redaction(redaction).build()?;
+    if path.is_dir() {
+        let plugin = parser.parse_plugin_dir(path.as_path())?;
+        for (module_path, warning) in plugin.warnings() {
+            let module_path = module_path.unwrap_or(&path);
+            eprintln!("warning: {}:{warning}", module_path.display());
+        }
+        print_formatted(&plugin, format, || modules_text(&plugin.content))
+    } else {
+        let module = parser.parse_module_file(&path)?;
+        for warning in &module.warnings {
+            eprintln!("warning: {}:{warning}", path.display());
+        }
+        print_formatted(&module, format, || modules_text([&module]))
+    }
+}
+
+fn print_formatted<T: Serialize>(
+    value: &T,
+    format: Format,
+    text: impl FnOnce() -> String,
+) -> Result<(), Box<dyn Error>> {
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(io::stdout(), value)?;
+            println!();
+        }
+        Format::Yaml => serde_yaml::to_writer(io::stdout(), value)?,
+        Format::Text => print!("{}", text()),
+    }
+    Ok(())
+}
+
+/// Renders modules as an outline of their paths and the definitions in each.
+fn modules_text<'a, I: IntoIterator<Item = &'a VimModule>>(modules: I) -> String {
+    let mut text = String::new();
+    for module in modules {
+        let path = module
+            .path
+            .as_ref()
+            .map_or("<unknown>".into(), |path| path.to_string_lossy());
+        writeln!(text, "{path}").unwrap();
+        if let Some(doc) = module.doc.as_deref().and_then(|doc| doc.lines().next()) {
+            writeln!(text, "  \"\" {doc}").unwrap();
+        }
+        for node in &module.nodes {
+            if let Some(summary) = node_summary(node) {
+                writeln!(text, "  {:?} {summary}", node.kind()).unwrap();
+            }
+        }
+    }
+    text
+}
+
+/// A one-line description of a definition, or None for standalone doc comments.
+fn node_summary(node: &VimNode) -> Option<String> {
+    match node {
+        VimNode::StandaloneDocComment { .. } => None,
+        VimNode::Autocmd {
+            events, patterns, ..
+        } => Some(format!("{} {}", events.join(","), patterns.join(","))),
+        VimNode::Mapping { mode, lhs, .. } if mode.is_empty() => Some(lhs.clone()),
+        VimNode::Mapping { mode, lhs, .. } => Some(format!("{mode} {lhs}")),
+        VimNode::SetOption { item, .. } => Some(item.clone()),
+        node => node.signature().or_else(|| node.name().map(str::to_string)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn modules_text_outline() {
+        let module = VimModule::new(vec![
+            VimNode::doc_comment("Ignored."),
+            VimNode::function("foo#Bar").args(["path", "..."]).into(),
+            VimNode::command("Foo").into(),
+            VimNode::variable("g:foo_enabled", "1").into(),
+        ])
+        .with_path("plugin/foo.vim")
+        .with_doc("Frobs things.\nMore details.");
+        assert_eq!(
+            modules_text([&module]),
+            "plugin/foo.vim\n  \"\" Frobs things.\n  Function foo#Bar({path}, [...])\n  \
+             Command :Foo\n  Variable g:foo_enabled\n"
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! Crawls a directory of plugin repos, parsing each in parallel and writing its metadata as JSON
+//! to an output dir.
+//!
+//! Usage: `cargo run --example crawl -- <repos dir> <output dir> [threads]`
+//!
+//! Results are written one plugin at a time, so an interrupted crawl can be rerun to pick up
+//! where it left off. Plugins whose output is still fresh (see [VimPlugin::is_stale]) are skipped
+//! without reparsing.
+
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use vim_plugin_metadata::{detect_plugin_roots, Error, VimParser, VimPlugin};
+
+#[derive(Default)]
+struct Counts {
+    parsed: AtomicUsize,
+    skipped: AtomicUsize,
+    failed: AtomicUsize,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (repos_dir, output_dir) = match &args[..] {
+        [repos_dir, output_dir] | [repos_dir, output_dir, _] => {
+            (PathBuf::from(repos_dir), PathBuf::from(output_dir))
+        }
+        _ => {
+            eprintln!("Usage: crawl <repos dir> <output dir> [threads]");
+            return ExitCode::FAILURE;
+        }
+    };
+    let threads = match args.get(2).map(|threads| threads.parse()) {
+        Some(Ok(threads)) => threads,
+        Some(Err(err)) => {
+            eprintln!("Invalid thread count: {err}");
+            return ExitCode::FAILURE;
+        }
+        None => thread::available_parallelism().map_or(1, usize::from),
+    };
+    if let Err(err) = fs::create_dir_all(&output_dir) {
+        eprintln!("Failed to create {}: {err}", output_dir.display());
+        return ExitCode::FAILURE;
+    }
+    let parser = match VimParser::builder().record_parse_info(true).build() {
+        Ok(parser) => parser,
+        Err(err) => {
+            eprintln!("Failed to create parser: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let roots = Mutex::new(detect_plugin_roots(&repos_dir).into_iter());
+    let counts = Counts::default();
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| loop {
+                let Some(root) = roots.lock().unwrap().next() else {
+                    break;
+                };
+                let counter = match crawl_plugin(&parser, &root, &output_dir) {
+                    Ok(true) => &counts.parsed,
+                    Ok(false) => &counts.skipped,
+                    Err(err) => {
+                        eprintln!("{}: {err}", root.display());
+                        &counts.failed
+                    }
+                };
+                counter.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+    });
+
+    println!(
+        "Parsed {}, skipped {} up to date, {} failed",
+        counts.parsed.into_inner(),
+        counts.skipped.into_inner(),
+        counts.failed.into_inner()
+    );
+    ExitCode::SUCCESS
+}
+
+/// Parses one plugin and writes its metadata, unless the existing output is still fresh.
+/// Returns whether the plugin was parsed.
+fn crawl_plugin(parser: &VimParser, root: &Path, output_dir: &Path) -> Result<bool, Error> {
+    let name = root
+        .file_name()
+        .map_or_else(|| "plugin".into(), |name| name.to_string_lossy());
+    let output_path = output_dir.join(format!("{name}.json"));
+    if let Ok(file) = File::open(&output_path) {
+        // Unreadable output, say from an older format, is just reparsed.
+        let cached = VimPlugin::read_from(BufReader::new(file));
+        if cached.is_ok_and(|plugin| plugin.is_stale().is_ok_and(|stale| !stale)) {
+            return Ok(false);
+        }
+    }
+    let plugin = parser.parse_plugin_dir(root)?;
+    // Write to a temporary file first so an interrupted crawl never leaves partial output.
+    let partial_path = output_path.with_extension("json.partial");
+    let mut writer = BufWriter::new(File::create(&partial_path)?);
+    plugin.write_to(&mut writer)?;
+    writer.flush()?;
+    fs::rename(&partial_path, &output_path)?;
+    Ok(true)
+}
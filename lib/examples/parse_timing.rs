@@ -0,0 +1,87 @@
+//! Times parsing a corpus of vimscript modules, for checking parser performance changes.
+//!
+//! Usage: `cargo run --release --example parse_timing -- [dir] [iterations]`
+//!
+//! Parses every .vim file under `dir`, or a generated corpus of heavily documented modules if no
+//! dir is given, and reports the fastest of several iterations to keep noise down.
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use std::{fs, io};
+use vim_plugin_metadata::VimParser;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let modules = match args.first() {
+        Some(dir) => match read_vim_files(Path::new(dir)) {
+            Ok(modules) => modules,
+            Err(err) => {
+                eprintln!("Failed to read {dir}: {err}");
+                return ExitCode::FAILURE;
+            }
+        },
+        None => generated_corpus(200),
+    };
+    let iterations = match args.get(1).map(|iterations| iterations.parse()) {
+        Some(Ok(iterations)) => iterations,
+        Some(Err(err)) => {
+            eprintln!("Invalid iteration count: {err}");
+            return ExitCode::FAILURE;
+        }
+        None => 10,
+    };
+    let parser = VimParser::new().unwrap();
+    let mut fastest = Duration::MAX;
+    let mut node_count = 0;
+    for _ in 0..iterations {
+        let start = Instant::now();
+        node_count = 0;
+        for code in &modules {
+            node_count += parser.parse_module_str(code).unwrap().nodes.len();
+        }
+        fastest = fastest.min(start.elapsed());
+    }
+    let bytes: usize = modules.iter().map(String::len).sum();
+    println!(
+        "Parsed {} modules ({bytes} bytes, {node_count} nodes) in {fastest:?}, best of {iterations}",
+        modules.len()
+    );
+    ExitCode::SUCCESS
+}
+
+fn read_vim_files(dir: &Path) -> io::Result<Vec<String>> {
+    let mut modules = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            modules.extend(read_vim_files(&path)?);
+        } else if path.extension().is_some_and(|ext| ext == "vim") {
+            modules.push(String::from_utf8_lossy(&fs::read(&path)?).into_owned());
+        }
+    }
+    Ok(modules)
+}
+
+/// Modules full of documented definitions, the case doc handling matters most for.
+fn generated_corpus(module_count: usize) -> Vec<String> {
+    (0..module_count)
+        .map(|m| {
+            let mut code = format!("\"\"\n\" Module {m} of the generated corpus.\n\n");
+            for i in 0..50 {
+                write!(
+                    code,
+                    "\"\"\n\" Does thing {i} with {{path}}.\n\"\n\" Longer explanation of how thing \
+                     {i} works and when to use it.\n\" @public\nfunction! plugin{m}#Thing{i}(path, \
+                     ...) abort\n  return a:path\nendfunction\n\n\"\" Whether thing {i} is \
+                     enabled.\nlet g:plugin{m}_thing{i} = 1\n\n\"\" Runs thing {i}.\ncommand \
+                     Thing{m}x{i} call plugin{m}#Thing{i}('.')\n\n\"\" Shared docs.\nlet \
+                     [s:a{i}, s:b{i}] = [1, 2]\n\n"
+                )
+                .unwrap();
+            }
+            code
+        })
+        .collect()
+}
@@ -0,0 +1,106 @@
+//! Whole-plugin analyses built on the parsed symbols and references, like finding dead code.
+
+use crate::symbols::is_same_script_local;
+use crate::{Symbol, VimModule, VimNode, VimPlugin, Visibility};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Finds script-local functions that nothing else in their module references, in module order.
+///
+/// References count from calls in other functions or at the top level (as `s:Name()` or
+/// `<SID>Name()`), `function('s:Name')` and `funcref()` strings, and `<SID>Name` in command
+/// replacement text, completion functions, mappings and autocmds. A function only calling itself
+/// is still unused. Names built dynamically, like in `execute 'call s:' .. name .. '()'`, can't
+/// be seen, so check before deleting anything.
+pub fn find_unused_script_local_functions(plugin: &VimPlugin) -> Vec<Symbol<'_>> {
+    plugin
+        .functions()
+        .filter(|function| Visibility::of_name(function.name()) == Visibility::ScriptLocal)
+        .filter(|function| !is_referenced(function.module, function.name()))
+        .collect()
+}
+
+/// Whether the module references the script-local function `name` outside its own body.
+fn is_referenced(module: &VimModule, name: &str) -> bool {
+    static SID_REF: OnceLock<Regex> = OnceLock::new();
+    let sid_ref =
+        SID_REF.get_or_init(|| Regex::new(r"(?i:<SID>|\bs:)([A-Za-z_][A-Za-z0-9_]*)").unwrap());
+    let is_same = |other: &str| other == name || is_same_script_local(name, other);
+    let called = module
+        .function_calls
+        .iter()
+        .any(|call| is_same(&call.callee) && !call.caller.as_deref().is_some_and(&is_same));
+    called
+        || module.nodes.iter().flat_map(raw_commands).any(|text| {
+            sid_ref
+                .captures_iter(&text)
+                .any(|captures| is_same_script_local(name, &format!("s:{}", &captures[1])))
+        })
+}
+
+/// Command text in a node that vim only parses when it runs, where functions can be referenced.
+fn raw_commands(node: &VimNode) -> Vec<String> {
+    match node {
+        VimNode::Command { repl, .. } => repl
+            .iter()
+            .cloned()
+            .chain(node.completion_function())
+            .collect(),
+        VimNode::Mapping { rhs, .. } => vec![rhs.clone()],
+        VimNode::Autocmd { command, .. } => vec![command.clone()],
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VimParser;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn find_unused_script_local_functions_references() {
+        let code = r#"
+func! s:Called() abort
+endfunc
+func! s:Recursive(n) abort
+  return a:n ? s:Recursive(a:n - 1) : 0
+endfunc
+func! s:ViaFuncref() abort
+endfunc
+func! <SID>ViaMapping() abort
+endfunc
+func! s:ViaCompletion(...) abort
+endfunc
+func! s:ViaAutocmd() abort
+endfunc
+func! s:Unused() abort
+endfunc
+call s:Called()
+let s:Callback = function('s:ViaFuncref')
+nnoremap <Plug>(foo) :call <SID>ViaMapping()<CR>
+command! -complete=customlist,s:ViaCompletion Foo echo
+autocmd BufEnter * call s:ViaAutocmd()
+"#;
+        let module = VimParser::new()
+            .unwrap()
+            .parse_module_str(code)
+            .unwrap()
+            .with_path("plugin/foo.vim");
+        let plugin = VimPlugin::new(vec![
+            module,
+            // Script-local functions are separate per script, so this doesn't count.
+            VimModule::new(vec![VimNode::command("Unused")
+                .repl("call s:Unused()")
+                .into()])
+            .with_path("plugin/bar.vim"),
+        ]);
+        assert_eq!(
+            find_unused_script_local_functions(&plugin)
+                .iter()
+                .map(|s| s.name())
+                .collect::<Vec<_>>(),
+            vec!["s:Recursive", "s:Unused"]
+        );
+    }
+}
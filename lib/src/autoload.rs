@@ -0,0 +1,224 @@
+use crate::runtime::autoload_path_for;
+use crate::{VimModule, VimPlugin, VimPluginSet};
+
+/// Autoload namespaces that ship with vim or neovim's own runtime files, which plugins can call
+/// into without depending on anything.
+const RUNTIME_NAMESPACES: [&str; 9] = [
+    "dist", "health", "netrw", "provider", "remote", "tar", "vimball", "zip", "gzip",
+];
+
+/// An autoload namespace a module calls into, along with the module in the plugin providing it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AutoloadNamespaceUse<'a> {
+    pub module: &'a VimModule,
+    /// The namespace, like `foo#bar` for `foo#bar#Baz()`.
+    pub namespace: String,
+    /// Functions in the namespace the module calls, in order of first use.
+    pub functions: Vec<&'a str>,
+    /// The module at the namespace's autoload path (like autoload/foo/bar.vim), if the plugin
+    /// has one.
+    pub provider: Option<&'a VimModule>,
+    /// Whether the module only calls into the namespace with errors suppressed (see
+    /// [VimModule::optional_autoload_references]), so it works without the namespace's plugin.
+    pub optional: bool,
+}
+
+impl AutoloadNamespaceUse<'_> {
+    /// The top-level namespace, like `foo` for `foo#bar`, which is usually the plugin name.
+    pub fn root(&self) -> &str {
+        self.namespace.split('#').next().unwrap_or(&self.namespace)
+    }
+}
+
+/// A module in a [VimPluginSet] that references functions in some autoload namespace (see
+/// [VimPluginSet::autoload_callers]).
+#[derive(Clone, Debug, PartialEq)]
+pub struct AutoloadCaller<'a> {
+    pub plugin: &'a str,
+    pub module: &'a VimModule,
+    /// The referenced functions, in order of first use.
+    pub functions: Vec<&'a str>,
+}
+
+impl VimPlugin {
+    /// Lists the autoload namespaces each module calls into and which files provide them, in
+    /// module order.
+    pub fn autoload_namespace_uses(&self) -> Vec<AutoloadNamespaceUse<'_>> {
+        let mut uses = vec![];
+        for module in &self.content {
+            let mut module_uses: Vec<AutoloadNamespaceUse> = vec![];
+            for function in &module.autoload_references {
+                let Some((namespace, _)) = function.rsplit_once('#') else {
+                    continue;
+                };
+                let optional = module.optional_autoload_references.contains(function);
+                match module_uses.iter_mut().find(|u| u.namespace == namespace) {
+                    Some(existing) => {
+                        existing.functions.push(function);
+                        existing.optional &= optional;
+                    }
+                    None => module_uses.push(AutoloadNamespaceUse {
+                        module,
+                        namespace: namespace.to_string(),
+                        functions: vec![function],
+                        provider: self.autoload_provider(function),
+                        optional,
+                    }),
+                }
+            }
+            uses.extend(module_uses);
+        }
+        uses
+    }
+
+    /// Finds autoload namespaces called into that the plugin doesn't provide, excluding ones
+    /// under the given `dependencies` (top-level namespaces like `maktaba`) and vim's runtime.
+    ///
+    /// Optional integrations are included too, so check [AutoloadNamespaceUse::optional] to tell
+    /// them apart from hard requirements.
+    pub fn unresolved_autoload_namespaces(
+        &self,
+        dependencies: &[&str],
+    ) -> Vec<AutoloadNamespaceUse<'_>> {
+        self.autoload_namespace_uses()
+            .into_iter()
+            .filter(|u| {
+                u.provider.is_none()
+                    && !dependencies.contains(&u.root())
+                    && !RUNTIME_NAMESPACES.contains(&u.root())
+            })
+            .collect()
+    }
+
+    fn autoload_provider(&self, function: &str) -> Option<&VimModule> {
+        let path = autoload_path_for(function)?;
+        self.content.iter().find(|m| {
+            m.path
+                .as_deref()
+                .is_some_and(|p| p == path || p.strip_prefix("after").is_ok_and(|p| p == path))
+        })
+    }
+}
+
+impl VimPluginSet {
+    /// Finds every module across the set that references functions in `namespace` (like `foo` or
+    /// `foo#util`) or namespaces under it, in plugin and module order.
+    pub fn autoload_callers(&self, namespace: &str) -> Vec<AutoloadCaller<'_>> {
+        let prefix = format!("{namespace}#");
+        let mut callers = vec![];
+        for (plugin_name, plugin) in self.iter() {
+            for module in &plugin.content {
+                let functions: Vec<&str> = module
+                    .autoload_references
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|f| f.starts_with(&prefix))
+                    .collect();
+                if !functions.is_empty() {
+                    callers.push(AutoloadCaller {
+                        plugin: plugin_name,
+                        module,
+                        functions,
+                    });
+                }
+            }
+        }
+        callers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+
+    #[test]
+    fn unresolved_autoload_namespaces_excludes_provided_and_declared() {
+        let plugin = VimPlugin::new(vec![
+            VimModule {
+                autoload_references: vec![
+                    "foo#util#Trim".into(),
+                    "maktaba#ensure#IsString".into(),
+                    "missing#Thing".into(),
+                    "foo#util#Split".into(),
+                    "dist#ft#FTcheck".into(),
+                    "other#Setup".into(),
+                ],
+                optional_autoload_references: vec!["other#Setup".into()],
+                ..Default::default()
+            }
+            .with_path("plugin/foo.vim"),
+            VimModule::default().with_path("autoload/foo/util.vim"),
+        ]);
+        let uses = plugin.autoload_namespace_uses();
+        assert_eq!(
+            uses.iter()
+                .map(|u| (
+                    u.namespace.as_str(),
+                    u.functions.clone(),
+                    u.provider.and_then(|p| p.path.as_deref())
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "foo#util",
+                    vec!["foo#util#Trim", "foo#util#Split"],
+                    Some(Path::new("autoload/foo/util.vim"))
+                ),
+                ("maktaba#ensure", vec!["maktaba#ensure#IsString"], None),
+                ("missing", vec!["missing#Thing"], None),
+                ("dist#ft", vec!["dist#ft#FTcheck"], None),
+                ("other", vec!["other#Setup"], None),
+            ]
+        );
+        assert_eq!(
+            plugin
+                .unresolved_autoload_namespaces(&["maktaba"])
+                .iter()
+                .map(|u| (u.namespace.as_str(), u.optional))
+                .collect::<Vec<_>>(),
+            vec![("missing", false), ("other", true)]
+        );
+    }
+
+    #[test]
+    fn autoload_callers_across_plugins() {
+        let calling_module = |path: &str, references: &[&str]| VimModule {
+            autoload_references: references.iter().map(|r| r.to_string()).collect(),
+            ..VimModule::default().with_path(path)
+        };
+        let plugins = VimPluginSet::from_iter([
+            (
+                "foo",
+                VimPlugin::new(vec![calling_module("plugin/foo.vim", &["foo#util#Trim"])]),
+            ),
+            (
+                "bar",
+                VimPlugin::new(vec![
+                    calling_module("plugin/bar.vim", &["bar#Init", "foobar#X"]),
+                    calling_module("autoload/bar.vim", &["foo#Run", "foo#util#Split"]),
+                ]),
+            ),
+        ]);
+        assert_eq!(
+            plugins
+                .autoload_callers("foo")
+                .iter()
+                .map(|c| (c.plugin, c.module.path.as_deref(), c.functions.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "foo",
+                    Some(Path::new("plugin/foo.vim")),
+                    vec!["foo#util#Trim"]
+                ),
+                (
+                    "bar",
+                    Some(Path::new("autoload/bar.vim")),
+                    vec!["foo#Run", "foo#util#Split"]
+                ),
+            ]
+        );
+    }
+}
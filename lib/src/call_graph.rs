@@ -0,0 +1,115 @@
+use crate::symbols::is_same_script_local;
+use crate::{FunctionCall, Symbol, VimModule, VimNode, VimPlugin, Visibility};
+
+/// A call from one place in a plugin to a function (see [VimPlugin::call_graph]).
+#[derive(Clone, Debug, PartialEq)]
+pub struct CallEdge<'a> {
+    /// The module the call is made from.
+    pub module: &'a VimModule,
+    /// The calling function, or None for calls at the top level of the module.
+    pub caller: Option<Symbol<'a>>,
+    /// The called function's name as normalized in [FunctionCall::callee].
+    pub callee: &'a str,
+    /// The called function's definition, if the plugin defines it. Script-local functions only
+    /// resolve within the calling module.
+    pub definition: Option<Symbol<'a>>,
+}
+
+impl VimPlugin {
+    /// Lists every call to a user-defined function in the plugin as an edge from the caller to
+    /// the callee's definition, in module order.
+    ///
+    /// Calls to functions the plugin doesn't define, like other plugins' autoload functions, are
+    /// included with no definition.
+    pub fn call_graph(&self) -> Vec<CallEdge<'_>> {
+        let mut edges = vec![];
+        for module in &self.content {
+            for FunctionCall { caller, callee } in &module.function_calls {
+                let caller = caller
+                    .as_deref()
+                    .and_then(|caller| find_in_module(module, caller));
+                let definition = if Visibility::of_name(callee) == Visibility::ScriptLocal {
+                    find_in_module(module, callee)
+                } else {
+                    self.find_function(callee)
+                };
+                edges.push(CallEdge {
+                    module,
+                    caller,
+                    callee,
+                    definition,
+                });
+            }
+        }
+        edges
+    }
+}
+
+/// Finds a function definition in one module by a name in `s:Foo` form, which also matches a
+/// `<SID>Foo` definition.
+fn find_in_module<'a>(module: &'a VimModule, name: &str) -> Option<Symbol<'a>> {
+    module
+        .nodes
+        .iter()
+        .find(|node| match node {
+            VimNode::Function { name: defined, .. } => {
+                defined == name || is_same_script_local(defined, name)
+            }
+            _ => false,
+        })
+        .map(|node| Symbol { module, node })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VimParser;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn call_graph_across_modules() {
+        let parser = VimParser::new().unwrap();
+        let module =
+            |path: &str, code: &str| parser.parse_module_str(code).unwrap().with_path(path);
+        let plugin = VimPlugin::new(vec![
+            module(
+                "plugin/foo.vim",
+                r#"
+func! s:Setup() abort
+  call foo#Run(len(g:foo_args))
+  call <SID>Log('setup')
+  let F = function('s:Log')
+endfunc
+func! s:Log(msg) abort
+  call maktaba#log#Info(a:msg)
+endfunc
+call s:Setup()
+"#,
+            ),
+            module(
+                "autoload/foo.vim",
+                "func! foo#Run(count) abort\n  call s:Log(a:count)\nendfunc\n",
+            ),
+        ]);
+        assert_eq!(
+            plugin
+                .call_graph()
+                .iter()
+                .map(|edge| (
+                    edge.caller.map(|c| c.name()),
+                    edge.callee,
+                    edge.definition
+                        .and_then(|d| d.module.path.as_deref())
+                        .map(|p| p.to_str().unwrap())
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some("s:Setup"), "foo#Run", Some("autoload/foo.vim")),
+                (Some("s:Setup"), "s:Log", Some("plugin/foo.vim")),
+                (Some("s:Log"), "maktaba#log#Info", None),
+                (None, "s:Setup", Some("plugin/foo.vim")),
+                (Some("foo#Run"), "s:Log", None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use crate::docgen::{node_title, strip_directives};
+use crate::{Symbol, VimNode, VimPluginSet};
+
+/// The kind of name [VimPluginSet::completion_candidates] completes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CompletionKind {
+    /// Functions callable from outside their plugin, like `foo#Bar` or `FooBar`.
+    Function,
+    /// User commands, completed without the leading `:`.
+    Command,
+    /// Things users configure, meaning flags and `g:` variables.
+    Setting,
+}
+
+/// A symbol whose name completes a prefix, with what a completion menu would show for it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompletionCandidate<'a> {
+    /// Name of the plugin defining the symbol.
+    pub plugin: &'a str,
+    pub symbol: Symbol<'a>,
+    /// A one-line summary like `foo#Bar({path}, [count])`, as for [crate::HoverInfo].
+    pub signature: String,
+    /// The symbol's doc comment without `@` directives, if it has one.
+    pub doc: Option<String>,
+}
+
+impl VimPluginSet {
+    /// Finds symbols of the given kind across all plugins whose names start with `prefix`,
+    /// sorted by name and then plugin order.
+    ///
+    /// Matching is case-sensitive like vim's own completion, since vim names are.
+    pub fn completion_candidates(
+        &self,
+        prefix: &str,
+        kind: CompletionKind,
+    ) -> Vec<CompletionCandidate<'_>> {
+        let prefix = match kind {
+            CompletionKind::Command => prefix.strip_prefix(':').unwrap_or(prefix),
+            _ => prefix,
+        };
+        let mut candidates: Vec<CompletionCandidate> = self
+            .iter()
+            .flat_map(|(plugin, vim_plugin)| {
+                vim_plugin
+                    .symbols()
+                    .filter(move |symbol| is_kind(symbol.node, kind))
+                    .filter(move |symbol| symbol.name().starts_with(prefix))
+                    .map(move |symbol| CompletionCandidate {
+                        plugin,
+                        symbol,
+                        signature: node_title(symbol.node)
+                            .unwrap_or_else(|| symbol.name().to_string()),
+                        doc: symbol.node.get_doc().map(strip_directives),
+                    })
+            })
+            .collect();
+        // Stable sort keeps plugin order for symbols with the same name.
+        candidates.sort_by_key(|candidate| candidate.symbol.name());
+        candidates
+    }
+}
+
+fn is_kind(node: &VimNode, kind: CompletionKind) -> bool {
+    match (kind, node) {
+        (CompletionKind::Function, VimNode::Function { .. }) => node.is_public(),
+        (CompletionKind::Command, VimNode::Command { .. }) => true,
+        (CompletionKind::Setting, VimNode::Flag { .. }) => true,
+        (CompletionKind::Setting, VimNode::Variable { name, .. }) => name.starts_with("g:"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{VimModule, VimPlugin};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn completion_candidates_by_kind() {
+        let mut plugins = VimPluginSet::new();
+        plugins.add(
+            "foo",
+            VimPlugin::new(vec![VimModule::new(vec![
+                VimNode::function("foo#Run")
+                    .args(["path"])
+                    .doc("Runs foo.\n@public")
+                    .into(),
+                VimNode::function("s:Helper").into(),
+                VimNode::command("FooRun").into(),
+                VimNode::flag("foo_enabled").default_value_token("1").into(),
+                VimNode::variable("g:foo_debug", "0").into(),
+                VimNode::variable("s:foo_state", "{}").into(),
+            ])]),
+        );
+        plugins.add(
+            "foobar",
+            VimPlugin::new(vec![VimModule::new(vec![
+                VimNode::function("foo#Bar").into(),
+                VimNode::command("FooBar").into(),
+            ])]),
+        );
+        let candidates = |prefix: &str, kind| {
+            plugins
+                .completion_candidates(prefix, kind)
+                .into_iter()
+                .map(|c| (c.plugin, c.signature, c.doc))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            candidates("foo#", CompletionKind::Function),
+            vec![
+                ("foobar", "foo#Bar()".to_string(), None),
+                (
+                    "foo",
+                    "foo#Run({path})".to_string(),
+                    Some("Runs foo.".to_string())
+                ),
+            ]
+        );
+        assert_eq!(
+            candidates(":FooR", CompletionKind::Command),
+            vec![("foo", ":FooRun".to_string(), None)]
+        );
+        assert_eq!(
+            candidates("", CompletionKind::Setting),
+            vec![
+                ("foo", "foo_enabled (default: 1)".to_string(), None),
+                ("foo", "g:foo_debug".to_string(), None),
+            ]
+        );
+        assert_eq!(candidates("s:", CompletionKind::Function), vec![]);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{VimNode, VimPlugin, VimPluginSet};
+use std::collections::BTreeMap;
+
+/// Aggregate statistics over all plugins in a [VimPluginSet], for research across an ecosystem
+/// of plugins (see [VimPluginSet::corpus_stats]).
+///
+/// Popularity counts are the number of plugins using something at least once, so one plugin
+/// setting an option in many places doesn't outweigh others.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CorpusStats {
+    pub plugin_count: usize,
+    /// Option names plugins set with `:set`/`:setlocal` (see [VimNode::SetOption]), with how
+    /// many plugins set each, most common first.
+    pub setting_names: Vec<(String, usize)>,
+    /// Autocmd events plugins listen for, with how many plugins use each, most common first.
+    pub autocmd_events: Vec<(String, usize)>,
+    /// The mean across plugins of the fraction of public symbols with docs, or None if no
+    /// plugin defines any public symbols.
+    pub average_doc_coverage: Option<f64>,
+    /// How many plugins define each number of functions, as (function count, plugin count)
+    /// pairs sorted by function count.
+    pub function_count_histogram: Vec<(usize, usize)>,
+}
+
+impl VimPluginSet {
+    /// Computes aggregate statistics across all plugins in the set.
+    pub fn corpus_stats(&self) -> CorpusStats {
+        let mut setting_names: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut autocmd_events: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut function_counts: BTreeMap<usize, usize> = BTreeMap::new();
+        let mut coverages = vec![];
+        for (_, plugin) in self.iter() {
+            let modules = || plugin.content.iter();
+            let nodes = || modules().flat_map(|m| &m.nodes);
+            let mut plugin_settings: Vec<&str> = nodes()
+                .filter_map(|n| match n {
+                    VimNode::SetOption { name, .. } => Some(name.as_str()),
+                    _ => None,
+                })
+                .collect();
+            plugin_settings.sort();
+            plugin_settings.dedup();
+            for name in plugin_settings {
+                *setting_names.entry(name).or_default() += 1;
+            }
+            let mut plugin_events: Vec<&str> = nodes()
+                .filter_map(|n| match n {
+                    VimNode::Autocmd { events, .. } => Some(events),
+                    _ => None,
+                })
+                .flatten()
+                .map(String::as_str)
+                .collect();
+            plugin_events.sort();
+            plugin_events.dedup();
+            for event in plugin_events {
+                *autocmd_events.entry(event).or_default() += 1;
+            }
+            let function_count = nodes()
+                .filter(|n| matches!(n, VimNode::Function { .. }))
+                .count();
+            *function_counts.entry(function_count).or_default() += 1;
+            coverages.extend(plugin.doc_coverage());
+        }
+        CorpusStats {
+            plugin_count: self.len(),
+            setting_names: by_popularity(setting_names),
+            autocmd_events: by_popularity(autocmd_events),
+            average_doc_coverage: (!coverages.is_empty())
+                .then(|| coverages.iter().sum::<f64>() / coverages.len() as f64),
+            function_count_histogram: function_counts.into_iter().collect(),
+        }
+    }
+}
+
+impl VimPlugin {
+    /// The fraction of the plugin's public symbols (anything named except script-local
+    /// functions) that have docs, or None if it has none.
+    pub fn doc_coverage(&self) -> Option<f64> {
+        let public: Vec<&VimNode> = self
+            .symbols()
+            .map(|symbol| symbol.node)
+            .filter(|n| n.name().is_some_and(|name| !name.starts_with("s:")))
+            .collect();
+        let documented = public.iter().filter(|n| n.get_doc().is_some()).count();
+        (!public.is_empty()).then(|| documented as f64 / public.len() as f64)
+    }
+}
+
+/// Sorts counts from most to least common, breaking ties by name.
+fn by_popularity(counts: BTreeMap<&str, usize>) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(name, count)| (name.to_string(), count))
+        .collect();
+    // BTreeMap order is by name, so a stable sort by count keeps ties in name order.
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VimParser;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn corpus_stats_across_plugins() {
+        let parser = VimParser::new().unwrap();
+        let plugin = |code: &str| VimPlugin::new(vec![parser.parse_module_str(code).unwrap()]);
+        let plugins = VimPluginSet::from_iter([
+            (
+                "a",
+                plugin(
+                    "setlocal sw=2 et\nset sw=4\nautocmd BufRead,BufWritePost * call s:X()\n\
+                     \"\" Does A.\nfunc A()\nendfunc\nfunc B()\nendfunc\nfunc s:X()\nendfunc\n",
+                ),
+            ),
+            (
+                "b",
+                plugin("setlocal et\nautocmd BufRead * echo 1\n\"\" Does C.\ncommand C echo\n"),
+            ),
+            ("c", plugin("")),
+        ]);
+        assert_eq!(
+            plugins.corpus_stats(),
+            CorpusStats {
+                plugin_count: 3,
+                setting_names: vec![("et".into(), 2), ("sw".into(), 1)],
+                autocmd_events: vec![("BufRead".into(), 2), ("BufWritePost".into(), 1)],
+                average_doc_coverage: Some(0.75),
+                function_count_histogram: vec![(0, 2), (3, 1)],
+            }
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{Symbol, VimModule, VimNode, VimPlugin, Visibility};
+
+/// How many of a module's public functions, commands and flags have doc comments (see
+/// [VimPlugin::module_doc_coverage]).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModuleDocCoverage<'a> {
+    pub module: &'a VimModule,
+    pub documented: usize,
+    pub total: usize,
+}
+
+impl ModuleDocCoverage<'_> {
+    /// The fraction of the module's API that's documented, or None if it has no public API.
+    pub fn fraction(&self) -> Option<f64> {
+        (self.total > 0).then(|| self.documented as f64 / self.total as f64)
+    }
+}
+
+impl VimPlugin {
+    /// Lists the plugin's public functions, commands and flags without `""` doc comments, in
+    /// module order.
+    ///
+    /// Script-local functions (`s:Foo` or `<SID>Foo`) are internal, so they don't need docs.
+    pub fn undocumented_symbols(&self) -> Vec<Symbol<'_>> {
+        self.symbols()
+            .filter(|symbol| is_api(symbol.node) && symbol.node.get_doc().is_none())
+            .collect()
+    }
+
+    /// Counts documented public functions, commands and flags per module, in module order,
+    /// including modules without any.
+    pub fn module_doc_coverage(&self) -> Vec<ModuleDocCoverage<'_>> {
+        self.content
+            .iter()
+            .map(|module| {
+                let api = || module.nodes.iter().filter(|node| is_api(node));
+                ModuleDocCoverage {
+                    module,
+                    documented: api().filter(|node| node.get_doc().is_some()).count(),
+                    total: api().count(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Whether a node is part of the API users are expected to call or configure.
+fn is_api(node: &VimNode) -> bool {
+    match node {
+        VimNode::Function { name, .. } => Visibility::of_name(name) != Visibility::ScriptLocal,
+        VimNode::Command { .. } | VimNode::Flag { .. } => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn undocumented_symbols_and_module_stats() {
+        let plugin = VimPlugin::new(vec![
+            VimModule::new(vec![
+                VimNode::command("Frob").doc("Frobs.").into(),
+                VimNode::command("Unfrob").into(),
+                VimNode::function("s:Helper").into(),
+                VimNode::function("<SID>Other").into(),
+                VimNode::Variable {
+                    name: "g:foo_loaded".into(),
+                    init_value_token: "1".into(),
+                    doc: None,
+                    span: None,
+                },
+            ])
+            .with_path("plugin/foo.vim"),
+            VimModule::new(vec![VimNode::function("foo#Frob").into()])
+                .with_path("autoload/foo.vim"),
+            VimModule::default().with_path("ftdetect/foo.vim"),
+        ]);
+        assert_eq!(
+            plugin
+                .undocumented_symbols()
+                .iter()
+                .map(|s| s.name())
+                .collect::<Vec<_>>(),
+            vec!["Unfrob", "foo#Frob"]
+        );
+        assert_eq!(
+            plugin
+                .module_doc_coverage()
+                .iter()
+                .map(|c| (c.documented, c.total, c.fraction()))
+                .collect::<Vec<_>>(),
+            vec![(1, 2, Some(0.5)), (0, 1, Some(0.0)), (0, 0, None)]
+        );
+    }
+}
@@ -0,0 +1,653 @@
+use crate::VimNode;
+
+impl VimNode {
+    /// Creates a [VimNode::StandaloneDocComment] with the given doc.
+    pub fn doc_comment<S: Into<String>>(doc: S) -> Self {
+        Self::StandaloneDocComment {
+            doc: doc.into(),
+            span: None,
+        }
+    }
+
+    /// Starts building a [VimNode::Function] with the given name.
+    ///
+    /// ```
+    /// use vim_plugin_metadata::VimNode;
+    ///
+    /// let node: VimNode = VimNode::function("foo#Bar")
+    ///     .args(["arg1", "..."])
+    ///     .modifiers(["abort"])
+    ///     .doc("Does a thing.")
+    ///     .into();
+    /// ```
+    pub fn function<S: Into<String>>(name: S) -> FunctionBuilder {
+        FunctionBuilder {
+            name: name.into(),
+            args: vec![],
+            modifiers: vec![],
+            doc: None,
+            body: None,
+        }
+    }
+
+    /// Starts building a [VimNode::Command] with the given name.
+    pub fn command<S: Into<String>>(name: S) -> CommandBuilder {
+        CommandBuilder {
+            name: name.into(),
+            modifiers: vec![],
+            repl: None,
+            doc: None,
+        }
+    }
+
+    /// Starts building a [VimNode::Variable] with the given name and initial value.
+    pub fn variable<S: Into<String>, V: Into<String>>(
+        name: S,
+        init_value_token: V,
+    ) -> VariableBuilder {
+        VariableBuilder {
+            name: name.into(),
+            init_value_token: init_value_token.into(),
+            doc: None,
+        }
+    }
+
+    /// Starts building a [VimNode::Flag] with the given name.
+    pub fn flag<S: Into<String>>(name: S) -> FlagBuilder {
+        FlagBuilder {
+            name: name.into(),
+            default_value_token: None,
+            doc: None,
+        }
+    }
+
+    /// Starts building a [VimNode::Augroup] with the given name.
+    pub fn augroup<S: Into<String>>(name: S) -> AugroupBuilder {
+        AugroupBuilder {
+            name: name.into(),
+            doc: None,
+        }
+    }
+
+    /// Starts building a [VimNode::Autocmd] running `command` for the given events and patterns.
+    pub fn autocmd<E, P, S, C>(events: E, patterns: P, command: C) -> AutocmdBuilder
+    where
+        E: IntoIterator<Item = S>,
+        P: IntoIterator<Item = S>,
+        S: Into<String>,
+        C: Into<String>,
+    {
+        AutocmdBuilder {
+            group: None,
+            events: into_strings(events),
+            patterns: into_strings(patterns),
+            command: command.into(),
+            doc: None,
+        }
+    }
+
+    /// Starts building a [VimNode::Mapping] from `lhs` to `rhs` in the given mode letters, like
+    /// `n` for `:nmap`.
+    pub fn mapping<M: Into<String>, L: Into<String>, R: Into<String>>(
+        mode: M,
+        lhs: L,
+        rhs: R,
+    ) -> MappingBuilder {
+        MappingBuilder {
+            mode: mode.into(),
+            noremap: false,
+            lhs: lhs.into(),
+            rhs: rhs.into(),
+            arguments: vec![],
+            doc: None,
+        }
+    }
+
+    /// Starts building a [VimNode::Highlight] with the given group name.
+    pub fn highlight<S: Into<String>>(name: S) -> HighlightBuilder {
+        HighlightBuilder {
+            name: name.into(),
+            default: false,
+            link: None,
+            attributes: vec![],
+            doc: None,
+        }
+    }
+
+    /// Starts building a [VimNode::Sign] with the given name.
+    pub fn sign<S: Into<String>>(name: S) -> SignBuilder {
+        SignBuilder {
+            name: name.into(),
+            attributes: vec![],
+            doc: None,
+        }
+    }
+
+    /// Starts building a [VimNode::SetOption] for option `name` as set by `item`, like `wrap` for
+    /// `nowrap`. Defaults to the `both` scope of `:set`.
+    pub fn set_option<N: Into<String>, I: Into<String>>(name: N, item: I) -> SetOptionBuilder {
+        SetOptionBuilder {
+            name: name.into(),
+            item: item.into(),
+            value: None,
+            scope: "both".into(),
+            doc: None,
+        }
+    }
+}
+
+fn into_strings<I: IntoIterator<Item = S>, S: Into<String>>(items: I) -> Vec<String> {
+    items.into_iter().map(Into::into).collect()
+}
+
+/// Builder for [VimNode::Function], created by [VimNode::function].
+#[derive(Clone, Debug)]
+pub struct FunctionBuilder {
+    name: String,
+    args: Vec<String>,
+    modifiers: Vec<String>,
+    doc: Option<String>,
+    body: Option<String>,
+}
+
+impl FunctionBuilder {
+    pub fn args<I: IntoIterator<Item = S>, S: Into<String>>(mut self, args: I) -> Self {
+        self.args = into_strings(args);
+        self
+    }
+
+    pub fn modifiers<I: IntoIterator<Item = S>, S: Into<String>>(mut self, modifiers: I) -> Self {
+        self.modifiers = into_strings(modifiers);
+        self
+    }
+
+    pub fn doc<S: Into<String>>(mut self, doc: S) -> Self {
+        self.doc = Some(doc.into());
+        self
+    }
+
+    pub fn body<S: Into<String>>(mut self, body: S) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    pub fn build(self) -> VimNode {
+        VimNode::Function {
+            name: self.name,
+            args: self.args,
+            modifiers: self.modifiers,
+            doc: self.doc,
+            body: self.body,
+            span: None,
+        }
+    }
+}
+
+/// Builder for [VimNode::Command], created by [VimNode::command].
+#[derive(Clone, Debug)]
+pub struct CommandBuilder {
+    name: String,
+    modifiers: Vec<String>,
+    repl: Option<String>,
+    doc: Option<String>,
+}
+
+impl CommandBuilder {
+    pub fn modifiers<I: IntoIterator<Item = S>, S: Into<String>>(mut self, modifiers: I) -> Self {
+        self.modifiers = into_strings(modifiers);
+        self
+    }
+
+    pub fn repl<S: Into<String>>(mut self, repl: S) -> Self {
+        self.repl = Some(repl.into());
+        self
+    }
+
+    pub fn doc<S: Into<String>>(mut self, doc: S) -> Self {
+        self.doc = Some(doc.into());
+        self
+    }
+
+    pub fn build(self) -> VimNode {
+        VimNode::Command {
+            name: self.name,
+            modifiers: self.modifiers,
+            repl: self.repl,
+            doc: self.doc,
+            span: None,
+        }
+    }
+}
+
+/// Builder for [VimNode::Variable], created by [VimNode::variable].
+#[derive(Clone, Debug)]
+pub struct VariableBuilder {
+    name: String,
+    init_value_token: String,
+    doc: Option<String>,
+}
+
+impl VariableBuilder {
+    pub fn doc<S: Into<String>>(mut self, doc: S) -> Self {
+        self.doc = Some(doc.into());
+        self
+    }
+
+    pub fn build(self) -> VimNode {
+        VimNode::Variable {
+            name: self.name,
+            init_value_token: self.init_value_token,
+            doc: self.doc,
+            span: None,
+        }
+    }
+}
+
+/// Builder for [VimNode::Flag], created by [VimNode::flag].
+#[derive(Clone, Debug)]
+pub struct FlagBuilder {
+    name: String,
+    default_value_token: Option<String>,
+    doc: Option<String>,
+}
+
+impl FlagBuilder {
+    pub fn default_value_token<S: Into<String>>(mut self, default_value_token: S) -> Self {
+        self.default_value_token = Some(default_value_token.into());
+        self
+    }
+
+    pub fn doc<S: Into<String>>(mut self, doc: S) -> Self {
+        self.doc = Some(doc.into());
+        self
+    }
+
+    pub fn build(self) -> VimNode {
+        VimNode::Flag {
+            name: self.name,
+            default_value_token: self.default_value_token,
+            doc: self.doc,
+            span: None,
+        }
+    }
+}
+
+/// Builder for [VimNode::Augroup], created by [VimNode::augroup].
+#[derive(Clone, Debug)]
+pub struct AugroupBuilder {
+    name: String,
+    doc: Option<String>,
+}
+
+impl AugroupBuilder {
+    pub fn doc<S: Into<String>>(mut self, doc: S) -> Self {
+        self.doc = Some(doc.into());
+        self
+    }
+
+    pub fn build(self) -> VimNode {
+        VimNode::Augroup {
+            name: self.name,
+            doc: self.doc,
+            span: None,
+        }
+    }
+}
+
+/// Builder for [VimNode::Autocmd], created by [VimNode::autocmd].
+#[derive(Clone, Debug)]
+pub struct AutocmdBuilder {
+    group: Option<String>,
+    events: Vec<String>,
+    patterns: Vec<String>,
+    command: String,
+    doc: Option<String>,
+}
+
+impl AutocmdBuilder {
+    pub fn group<S: Into<String>>(mut self, group: S) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    pub fn doc<S: Into<String>>(mut self, doc: S) -> Self {
+        self.doc = Some(doc.into());
+        self
+    }
+
+    pub fn build(self) -> VimNode {
+        VimNode::Autocmd {
+            group: self.group,
+            events: self.events,
+            patterns: self.patterns,
+            command: self.command,
+            doc: self.doc,
+            span: None,
+        }
+    }
+}
+
+/// Builder for [VimNode::Mapping], created by [VimNode::mapping].
+#[derive(Clone, Debug)]
+pub struct MappingBuilder {
+    mode: String,
+    noremap: bool,
+    lhs: String,
+    rhs: String,
+    arguments: Vec<String>,
+    doc: Option<String>,
+}
+
+impl MappingBuilder {
+    pub fn noremap(mut self, noremap: bool) -> Self {
+        self.noremap = noremap;
+        self
+    }
+
+    pub fn arguments<I: IntoIterator<Item = S>, S: Into<String>>(mut self, arguments: I) -> Self {
+        self.arguments = into_strings(arguments);
+        self
+    }
+
+    pub fn doc<S: Into<String>>(mut self, doc: S) -> Self {
+        self.doc = Some(doc.into());
+        self
+    }
+
+    pub fn build(self) -> VimNode {
+        VimNode::Mapping {
+            mode: self.mode,
+            noremap: self.noremap,
+            lhs: self.lhs,
+            rhs: self.rhs,
+            arguments: self.arguments,
+            doc: self.doc,
+            span: None,
+        }
+    }
+}
+
+/// Builder for [VimNode::Highlight], created by [VimNode::highlight].
+#[derive(Clone, Debug)]
+pub struct HighlightBuilder {
+    name: String,
+    default: bool,
+    link: Option<String>,
+    attributes: Vec<String>,
+    doc: Option<String>,
+}
+
+impl HighlightBuilder {
+    pub fn default(mut self, default: bool) -> Self {
+        self.default = default;
+        self
+    }
+
+    pub fn link<S: Into<String>>(mut self, link: S) -> Self {
+        self.link = Some(link.into());
+        self
+    }
+
+    pub fn attributes<I: IntoIterator<Item = S>, S: Into<String>>(mut self, attributes: I) -> Self {
+        self.attributes = into_strings(attributes);
+        self
+    }
+
+    pub fn doc<S: Into<String>>(mut self, doc: S) -> Self {
+        self.doc = Some(doc.into());
+        self
+    }
+
+    pub fn build(self) -> VimNode {
+        VimNode::Highlight {
+            name: self.name,
+            default: self.default,
+            link: self.link,
+            attributes: self.attributes,
+            doc: self.doc,
+            span: None,
+        }
+    }
+}
+
+/// Builder for [VimNode::Sign], created by [VimNode::sign].
+#[derive(Clone, Debug)]
+pub struct SignBuilder {
+    name: String,
+    attributes: Vec<String>,
+    doc: Option<String>,
+}
+
+impl SignBuilder {
+    pub fn attributes<I: IntoIterator<Item = S>, S: Into<String>>(mut self, attributes: I) -> Self {
+        self.attributes = into_strings(attributes);
+        self
+    }
+
+    pub fn doc<S: Into<String>>(mut self, doc: S) -> Self {
+        self.doc = Some(doc.into());
+        self
+    }
+
+    pub fn build(self) -> VimNode {
+        VimNode::Sign {
+            name: self.name,
+            attributes: self.attributes,
+            doc: self.doc,
+            span: None,
+        }
+    }
+}
+
+/// Builder for [VimNode::SetOption], created by [VimNode::set_option].
+#[derive(Clone, Debug)]
+pub struct SetOptionBuilder {
+    name: String,
+    item: String,
+    value: Option<String>,
+    scope: String,
+    doc: Option<String>,
+}
+
+impl SetOptionBuilder {
+    pub fn value<S: Into<String>>(mut self, value: S) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// `local` for `:setlocal`, or `both` for `:set`.
+    pub fn scope<S: Into<String>>(mut self, scope: S) -> Self {
+        self.scope = scope.into();
+        self
+    }
+
+    pub fn doc<S: Into<String>>(mut self, doc: S) -> Self {
+        self.doc = Some(doc.into());
+        self
+    }
+
+    pub fn build(self) -> VimNode {
+        VimNode::SetOption {
+            name: self.name,
+            item: self.item,
+            value: self.value,
+            scope: self.scope,
+            doc: self.doc,
+            span: None,
+        }
+    }
+}
+
+impl From<FunctionBuilder> for VimNode {
+    fn from(builder: FunctionBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl From<CommandBuilder> for VimNode {
+    fn from(builder: CommandBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl From<VariableBuilder> for VimNode {
+    fn from(builder: VariableBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl From<FlagBuilder> for VimNode {
+    fn from(builder: FlagBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl From<AugroupBuilder> for VimNode {
+    fn from(builder: AugroupBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl From<AutocmdBuilder> for VimNode {
+    fn from(builder: AutocmdBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl From<MappingBuilder> for VimNode {
+    fn from(builder: MappingBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl From<HighlightBuilder> for VimNode {
+    fn from(builder: HighlightBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl From<SignBuilder> for VimNode {
+    fn from(builder: SignBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl From<SetOptionBuilder> for VimNode {
+    fn from(builder: SetOptionBuilder) -> Self {
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{VimModule, VimPlugin};
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn function_builder() {
+        assert_eq!(
+            VimNode::function("foo#Bar")
+                .args(["arg1", "..."])
+                .modifiers(["abort"])
+                .doc("Does a thing.")
+                .build(),
+            VimNode::Function {
+                name: "foo#Bar".into(),
+                args: vec!["arg1".into(), "...".into()],
+                modifiers: vec!["abort".into()],
+                doc: Some("Does a thing.".into()),
+                body: None,
+                span: None,
+            }
+        );
+    }
+
+    #[test]
+    fn flag_builder_defaults() {
+        assert_eq!(
+            VimNode::from(VimNode::flag("someflag")),
+            VimNode::Flag {
+                name: "someflag".into(),
+                default_value_token: None,
+                doc: None,
+                span: None,
+            }
+        );
+    }
+
+    #[test]
+    fn builders_match_parsed_nodes() {
+        let code = r#"
+augroup foo
+  autocmd BufRead *.foo setlocal ft=foo
+augroup END
+nnoremap <silent> <Leader>f :call foo#Run()<CR>
+hi default link FooKeyword Keyword
+sign define FooError text=>> texthl=Error
+setlocal sw=2
+"#;
+        let module = crate::VimParser::new()
+            .unwrap()
+            .parse_module_str(code)
+            .unwrap();
+        assert_eq!(
+            module.nodes,
+            vec![
+                VimNode::augroup("foo").build(),
+                VimNode::autocmd(["BufRead"], ["*.foo"], "setlocal ft=foo")
+                    .group("foo")
+                    .build(),
+                VimNode::mapping("n", "<Leader>f", ":call foo#Run()<CR>")
+                    .noremap(true)
+                    .arguments(["<silent>"])
+                    .build(),
+                VimNode::highlight("FooKeyword")
+                    .default(true)
+                    .link("Keyword")
+                    .build(),
+                VimNode::sign("FooError")
+                    .attributes(["text=>>", "texthl=Error"])
+                    .build(),
+                VimNode::set_option("sw", "sw=2")
+                    .value("2")
+                    .scope("local")
+                    .build(),
+            ]
+        );
+    }
+
+    #[test]
+    fn module_and_plugin_constructors() {
+        let module = VimModule::new(vec![VimNode::command("SomeCommand").into()])
+            .with_path("plugin/foo.vim")
+            .with_doc("Module doc");
+        assert_eq!(
+            VimPlugin::new(vec![module]),
+            VimPlugin {
+                root: None,
+                metadata: Default::default(),
+                content: vec![VimModule {
+                    path: Some(PathBuf::from("plugin/foo.vim")),
+                    doc: Some("Module doc".into()),
+                    nodes: vec![VimNode::Command {
+                        name: "SomeCommand".into(),
+                        modifiers: vec![],
+                        repl: None,
+                        doc: None,
+                        span: None,
+                    }],
+                    ..Default::default()
+                }],
+                vendored: vec![],
+                remote_plugins: vec![],
+                queries: vec![],
+                help_docs: vec![],
+                parse_info: None,
+                profile: None,
+                errors: vec![],
+            }
+        );
+    }
+}
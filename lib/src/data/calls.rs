@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// A call to a user-defined function found in a module (see [crate::VimModule::function_calls]).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionCall {
+    /// The function making the call, or None for calls at the top level of the module.
+    pub caller: Option<String>,
+    /// The called function's name, with `<SID>Foo` normalized to `s:Foo`.
+    pub callee: String,
+}
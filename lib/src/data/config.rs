@@ -0,0 +1,128 @@
+use crate::VimModule;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// A user config file like a vimrc or init.vim, with the kinds of statements that matter for
+/// dotfile analysis pulled out alongside the normal module metadata.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct VimConfig {
+    /// Functions, commands and other nodes parsed like any other module.
+    pub module: VimModule,
+    pub settings: Vec<ConfigSetting>,
+    pub mappings: Vec<ConfigMapping>,
+    pub autocmds: Vec<ConfigAutocmd>,
+    pub plugin_declarations: Vec<PluginDeclaration>,
+}
+
+/// An option set via `:set`, `:setlocal`, `:setglobal` or `:let &option = …`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfigSetting {
+    /// The command used, like `set` or `setlocal`.
+    pub command: String,
+    /// The option name, without any `no`/`inv` prefix.
+    pub name: String,
+    /// The full setting as written, like `sw=2` or `nocompatible`.
+    pub item: String,
+    /// The assigned value, if any.
+    pub value: Option<String>,
+}
+
+/// A key mapping defined with one of the `:map` family of commands.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfigMapping {
+    /// The command used, like `nnoremap`.
+    pub command: String,
+    /// Raw map arguments like `<silent>` (see [crate::MapArguments]).
+    pub arguments: Vec<String>,
+    pub lhs: String,
+    pub rhs: String,
+}
+
+/// An autocommand defined with `:autocmd`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfigAutocmd {
+    /// The group it was defined in, either explicitly or via an enclosing `:augroup`.
+    pub group: Option<String>,
+    pub events: Vec<String>,
+    pub patterns: Vec<String>,
+    pub command: String,
+}
+
+/// A plugin declared for a plugin manager, like `Plug 'tpope/vim-fugitive'`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PluginDeclaration {
+    pub manager: PluginManager,
+    /// The plugin as given to the manager, like `tpope/vim-fugitive` or a package name.
+    pub name: String,
+    /// Options passed along with the plugin as (key, raw value token) pairs, like
+    /// `("branch", "'main'")`.
+    pub options: Vec<(String, String)>,
+}
+
+impl PluginDeclaration {
+    /// The raw value token of the given option, if set.
+    pub fn option(&self, key: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The branch of the plugin repo to use, if specified.
+    pub fn branch(&self) -> Option<String> {
+        self.option("branch")
+            .or_else(|| self.option("rev"))
+            .and_then(|value| string_list(value).into_iter().next())
+    }
+
+    /// Commands or mappings that trigger lazy-loading the plugin, like vim-plug's `on`.
+    pub fn on(&self) -> Vec<String> {
+        ["on", "on_cmd", "on_map", "cmd", "keys"]
+            .into_iter()
+            .filter_map(|key| self.option(key))
+            .flat_map(string_list)
+            .collect()
+    }
+
+    /// Filetypes that trigger lazy-loading the plugin, like vim-plug's `for`.
+    pub fn for_filetypes(&self) -> Vec<String> {
+        ["for", "on_ft", "ft"]
+            .into_iter()
+            .filter_map(|key| self.option(key))
+            .flat_map(string_list)
+            .collect()
+    }
+}
+
+/// Gets the string contents of a value token that's either a quoted string or a list/table of
+/// them.
+fn string_list(value: &str) -> Vec<String> {
+    static STRING_LITERAL: OnceLock<Regex> = OnceLock::new();
+    let string_literal =
+        STRING_LITERAL.get_or_init(|| Regex::new(r#"'([^']*)'|"([^"]*)""#).unwrap());
+    string_literal
+        .captures_iter(value)
+        .filter_map(|c| c.get(1).or_else(|| c.get(2)))
+        .map(|m| m.as_str().to_string())
+        .collect()
+}
+
+/// A plugin manager (or builtin mechanism) that plugins can be declared for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PluginManager {
+    /// junegunn/vim-plug's `Plug`.
+    VimPlug,
+    /// VundleVim/Vundle.vim's `Plugin`.
+    Vundle,
+    /// Shougo/neobundle.vim's `NeoBundle`.
+    NeoBundle,
+    /// Shougo/dein.vim's `dein#add()`.
+    Dein,
+    /// k-takata/minpac's `minpac#add()`.
+    Minpac,
+    /// wbthomason/packer.nvim's lua `use`.
+    Packer,
+    /// Vim's builtin `:packadd`.
+    Packadd,
+}
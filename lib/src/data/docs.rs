@@ -0,0 +1,140 @@
+//! Structured content parsed out of the raw doc comments stored on nodes.
+
+use crate::VimNode;
+
+impl VimNode {
+    /// Example blocks in this node's doc, using vim help syntax: a line ending in `>` starts an
+    /// indented block, which ends at a line starting with `<` or at the next unindented line.
+    ///
+    /// Each example is returned with its common indentation removed.
+    pub fn examples(&self) -> Vec<String> {
+        self.get_doc().map(parse_examples).unwrap_or_default()
+    }
+
+    /// The args from a function's `@usage` doc directive (like `{path} [count]`), which document
+    /// how to call it when that differs from the literal signature, like optional args taken
+    /// through `...`.
+    pub fn usage(&self) -> Option<&str> {
+        match self {
+            VimNode::Function { doc: Some(doc), .. } => doc_directives(doc)
+                .find(|(directive, _)| *directive == "usage")
+                .map(|(_, usage)| usage.trim()),
+            _ => None,
+        }
+    }
+
+    /// The signature to show in rendered docs, like `foo#Bar({path}, [count])` for functions or
+    /// `:Foo` for commands.
+    ///
+    /// Functions use their `@usage` args if documented (see [VimNode::usage]) and otherwise
+    /// their literal args, with `...` shown as `[...]`.
+    pub fn signature(&self) -> Option<String> {
+        match self {
+            VimNode::Function { name, args, .. } => {
+                let args: Vec<String> = match self.usage() {
+                    Some(usage) => usage.split_whitespace().map(str::to_string).collect(),
+                    None => args
+                        .iter()
+                        .map(|arg| match arg.as_str() {
+                            "..." => "[...]".to_string(),
+                            arg => format!("{{{arg}}}"),
+                        })
+                        .collect(),
+                };
+                Some(format!("{name}({})", args.join(", ")))
+            }
+            VimNode::Command { name, .. } => Some(format!(":{name}")),
+            _ => None,
+        }
+    }
+}
+
+/// Iterates over doc lines starting with an `@directive`, as (directive, rest of line) pairs.
+pub(crate) fn doc_directives(doc: &str) -> impl Iterator<Item = (&str, &str)> {
+    doc.lines().filter_map(|line| {
+        let line = line.trim_start().strip_prefix('@')?;
+        Some(line.split_once(char::is_whitespace).unwrap_or((line, "")))
+    })
+}
+
+fn parse_examples(doc: &str) -> Vec<String> {
+    let mut examples = vec![];
+    let mut block: Option<Vec<&str>> = None;
+    for line in doc.lines() {
+        if let Some(lines) = &mut block {
+            let ends_block = line.starts_with('<')
+                || !(line.trim().is_empty() || line.starts_with(char::is_whitespace));
+            if !ends_block {
+                lines.push(line);
+                continue;
+            }
+            examples.extend(finish_example(block.take().unwrap()));
+            if line.starts_with('<') {
+                continue;
+            }
+        }
+        if line == ">" || line.ends_with(" >") {
+            block = Some(vec![]);
+        }
+    }
+    examples.extend(block.and_then(finish_example));
+    examples
+}
+
+fn finish_example(mut lines: Vec<&str>) -> Option<String> {
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+    let indent = lines
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()?;
+    Some(
+        lines
+            .iter()
+            .map(|l| l.get(indent..).unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn node_examples() {
+        let node = VimNode::function("foo#Bar")
+            .doc(
+                "Frobs things. For example: >\n    call foo#Bar()\n\n      \" Nested.\n<\n\
+                 Or with a count: >\n  call foo#Bar(2)\nMore docs, not in the example. >\n",
+            )
+            .build();
+        assert_eq!(
+            node.examples(),
+            vec![
+                "call foo#Bar()\n\n  \" Nested.".to_string(),
+                "call foo#Bar(2)".to_string(),
+            ]
+        );
+        assert_eq!(
+            VimNode::function("foo#Baz").build().examples(),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn function_usage_and_signature() {
+        let node = VimNode::function("foo#Bar")
+            .args(["path", "..."])
+            .doc("Does a thing.\n@usage {path} [count]")
+            .build();
+        assert_eq!(node.usage(), Some("{path} [count]"));
+        assert_eq!(node.signature(), Some("foo#Bar({path}, [count])".into()));
+        let node = VimNode::function("foo#Baz").args(["path", "..."]).build();
+        assert_eq!(node.usage(), None);
+        assert_eq!(node.signature(), Some("foo#Baz({path}, [...])".into()));
+    }
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+/// Key/value metadata from a module's header comment, like `" Maintainer: Jane <jane@x.org>`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModuleHeader {
+    pub author: Option<String>,
+    pub maintainer: Option<String>,
+    pub license: Option<String>,
+    pub version: Option<String>,
+    /// The "Last Change" (or "Last Modified") date, as written.
+    pub last_change: Option<String>,
+    /// Boilerplate license text from a comment block at the top, like an Apache or MIT license
+    /// notice. It's kept out of the module doc.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license_text: Option<String>,
+}
+
+impl ModuleHeader {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Fills in any fields missing here from `other`.
+    pub(crate) fn merge(&mut self, other: &ModuleHeader) {
+        for (field, other_field) in [
+            (&mut self.author, &other.author),
+            (&mut self.maintainer, &other.maintainer),
+            (&mut self.license, &other.license),
+            (&mut self.version, &other.version),
+            (&mut self.last_change, &other.last_change),
+            (&mut self.license_text, &other.license_text),
+        ] {
+            if field.is_none() {
+                field.clone_from(other_field);
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{VimNode, VimPlugin};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A vim help file from a plugin's doc/ dir, like doc/foo.txt.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct VimHelpDoc {
+    /// Path of the help file, relative to the plugin root.
+    pub path: PathBuf,
+    /// Headings following `====` separator lines, in file order.
+    pub sections: Vec<HelpSection>,
+    pub entries: Vec<HelpEntry>,
+}
+
+/// A heading in a help file, like `1. Introduction  *foo-intro*`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HelpSection {
+    /// The heading text without its tags.
+    pub title: String,
+    pub tags: Vec<String>,
+    /// 0-based line number of the heading.
+    pub line: usize,
+}
+
+/// A block of help text introduced by one or more `*tag*` definitions, running until the next
+/// tag or section separator.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HelpEntry {
+    pub tags: Vec<String>,
+    /// The text of the block with tag definitions removed, like a function signature followed by
+    /// its description.
+    pub text: String,
+    /// 0-based line number of the first tag line.
+    pub line: usize,
+}
+
+impl VimHelpDoc {
+    /// Finds the entry defining the given tag.
+    pub fn entry(&self, tag: &str) -> Option<&HelpEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.tags.iter().any(|t| t == tag))
+    }
+}
+
+impl VimPlugin {
+    /// Finds the entry in the plugin's help files documenting a node, by its help tag (see
+    /// [VimNode::help_tag]). Flag tags are qualified with the plugin's [VimPlugin::metadata]
+    /// name.
+    pub fn help_entry(&self, node: &VimNode) -> Option<(&VimHelpDoc, &HelpEntry)> {
+        let tag = node.help_tag(self.metadata.name.as_deref().unwrap_or_default())?;
+        self.help_docs
+            .iter()
+            .find_map(|doc| Some((doc, doc.entry(&tag)?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{VimModule, VimPluginMetadata};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn help_entry_by_node_tag() {
+        let entry = |tag: &str, text: &str| HelpEntry {
+            tags: vec![tag.into()],
+            text: text.into(),
+            line: 0,
+        };
+        let plugin = VimPlugin {
+            metadata: VimPluginMetadata {
+                name: Some("foo".into()),
+                ..Default::default()
+            },
+            help_docs: vec![VimHelpDoc {
+                path: "doc/foo.txt".into(),
+                sections: vec![],
+                entries: vec![
+                    entry("foo#Bar()", "Frobs."),
+                    entry("foo:verbose", "Be loud."),
+                ],
+            }],
+            ..VimPlugin::new(vec![VimModule::new(vec![])])
+        };
+        let help_text = |node: VimNode| {
+            plugin
+                .help_entry(&node)
+                .map(|(doc, entry)| (doc.path.to_str().unwrap(), entry.text.as_str()))
+        };
+        assert_eq!(
+            help_text(VimNode::function("foo#Bar").into()),
+            Some(("doc/foo.txt", "Frobs."))
+        );
+        assert_eq!(
+            help_text(VimNode::flag("verbose").into()),
+            Some(("doc/foo.txt", "Be loud."))
+        );
+        assert_eq!(help_text(VimNode::command("Foo").into()), None);
+    }
+}
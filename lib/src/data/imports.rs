@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+/// A Vim9 script `:import` of another script (see [crate::VimModule::vim9_imports]).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Vim9Import {
+    /// The imported script's path as written, like `./util.vim` or `foo/util.vim`.
+    pub path: String,
+    /// The name the importing script refers to it by, from `as {name}` or else the file name
+    /// without its extension.
+    pub name: String,
+    /// Whether it was imported with `import autoload`, which searches autoload/ dirs and loads
+    /// the script on first use.
+    pub autoload: bool,
+    /// Exported items used through the import's name, like `Trim` for `util.Trim()`, in order
+    /// of first use.
+    pub used_items: Vec<String>,
+}
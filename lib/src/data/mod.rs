@@ -0,0 +1,745 @@
+use crate::parser::filetype_of;
+use crate::{classify_runtime_path_with, Section};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use vim_plugin_metadata_macros::ExportVariants;
+
+mod builders;
+mod calls;
+mod config;
+mod docs;
+mod header;
+mod help;
+mod imports;
+mod modeline;
+mod modifiers;
+mod node_tree;
+mod outline;
+mod parse_info;
+mod plugin_metadata;
+mod profile;
+mod queries;
+mod remote;
+mod span;
+mod stats;
+mod visibility;
+mod warning;
+
+pub use builders::{
+    AugroupBuilder, AutocmdBuilder, CommandBuilder, FlagBuilder, FunctionBuilder, HighlightBuilder,
+    MappingBuilder, SetOptionBuilder, SignBuilder, VariableBuilder,
+};
+pub use calls::FunctionCall;
+pub use config::{
+    ConfigAutocmd, ConfigMapping, ConfigSetting, PluginDeclaration, PluginManager, VimConfig,
+};
+pub(crate) use docs::doc_directives;
+pub use header::ModuleHeader;
+pub use help::{HelpEntry, HelpSection, VimHelpDoc};
+pub use imports::Vim9Import;
+pub use modeline::Modeline;
+pub use modifiers::{CommandAttributes, FunctionModifiers, MapArguments};
+pub use node_tree::VimNodeTree;
+pub use outline::{DocOutline, DocSection};
+pub(crate) use parse_info::Fingerprint;
+pub use parse_info::ParseInfo;
+pub use plugin_metadata::VimPluginMetadata;
+pub use profile::{ModuleProfile, ParseProfile};
+pub use queries::TreeSitterQuery;
+pub use remote::{RemotePlugin, RemoteRegistration};
+pub use span::{Span, TextEdit};
+pub use stats::ModuleStats;
+pub use visibility::Visibility;
+pub use warning::{ModuleError, Warning, WarningKind};
+
+/// A representation of a single high-level grammar token of vim syntax,
+/// such as a comment or function.
+///
+/// Serializes with a `kind` field naming the variant, like `{"kind": "Function", …}`.
+///
+/// Every variant has a `span` with where it was defined, if the parser recorded spans (see
+/// [VimNode::span]).
+///
+/// New variants and fields can be added without a breaking release, so outside this crate nodes
+/// are created with builders like [VimNode::function] and matched with `..` rest patterns.
+///
+/// The python bindings mirror these variants through the `vim_node_variants!` macro, so field
+/// types are limited to what they know how to convert, unless the field is left out with
+/// `#[export_variants(skip)]`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ExportVariants)]
+#[serde(tag = "kind")]
+#[non_exhaustive]
+pub enum VimNode {
+    #[non_exhaustive]
+    StandaloneDocComment {
+        doc: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[export_variants(skip)]
+        span: Option<Span>,
+    },
+    #[non_exhaustive]
+    Function {
+        name: String,
+        args: Vec<String>,
+        modifiers: Vec<String>,
+        doc: Option<String>,
+        /// The statements between the declaration and `endfunction`, if the parser was
+        /// configured to capture them (see [crate::VimParser::set_function_body_capture]).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[export_variants(skip)]
+        body: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[export_variants(skip)]
+        span: Option<Span>,
+    },
+    #[non_exhaustive]
+    Command {
+        name: String,
+        modifiers: Vec<String>,
+        /// The replacement text the command executes, if defined.
+        repl: Option<String>,
+        doc: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[export_variants(skip)]
+        span: Option<Span>,
+    },
+    #[non_exhaustive]
+    Variable {
+        name: String,
+        init_value_token: String,
+        doc: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[export_variants(skip)]
+        span: Option<Span>,
+    },
+    /// A defined "Flag" like the mechanism used in google/vim-maktaba.
+    #[non_exhaustive]
+    Flag {
+        name: String,
+        default_value_token: Option<String>,
+        doc: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[export_variants(skip)]
+        span: Option<Span>,
+    },
+    /// An autocommand group started with `:augroup`.
+    #[non_exhaustive]
+    Augroup {
+        name: String,
+        doc: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[export_variants(skip)]
+        span: Option<Span>,
+    },
+    /// An autocommand defined with `:autocmd`.
+    #[non_exhaustive]
+    Autocmd {
+        /// The group it was defined in, either explicitly or via an enclosing `:augroup`.
+        group: Option<String>,
+        events: Vec<String>,
+        patterns: Vec<String>,
+        /// The command executed when the autocmd fires.
+        command: String,
+        doc: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[export_variants(skip)]
+        span: Option<Span>,
+    },
+    /// A key mapping defined with one of the `:map` family of commands.
+    #[non_exhaustive]
+    Mapping {
+        /// The mode letters from the command, like `n` for `:nnoremap`. Empty for `:map` and
+        /// `:noremap` (normal, visual and operator-pending) or `!` for `:map!` (insert and
+        /// command-line).
+        mode: String,
+        /// Whether the rhs is exempt from remapping, as with `:nnoremap`.
+        noremap: bool,
+        lhs: String,
+        rhs: String,
+        /// Raw map arguments like `<silent>` (see [VimNode::map_arguments]).
+        arguments: Vec<String>,
+        doc: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[export_variants(skip)]
+        span: Option<Span>,
+    },
+    /// A highlight group defined with `:highlight`, either with its own attributes or linked to
+    /// another group.
+    #[non_exhaustive]
+    Highlight {
+        name: String,
+        /// Whether it only applies if the group isn't already defined, as with `:hi default`.
+        default: bool,
+        /// The group it links to, for `:highlight link`.
+        link: Option<String>,
+        /// Raw attributes like `guifg=#ff0000`.
+        attributes: Vec<String>,
+        doc: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[export_variants(skip)]
+        span: Option<Span>,
+    },
+    /// A sign defined with `:sign define`.
+    #[non_exhaustive]
+    Sign {
+        name: String,
+        /// Raw arguments like `text=>>` or `texthl=Error`.
+        attributes: Vec<String>,
+        doc: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[export_variants(skip)]
+        span: Option<Span>,
+    },
+    /// An option set with `:set` or `:setlocal`, one per option for statements setting several.
+    #[non_exhaustive]
+    SetOption {
+        /// The option name, without any `no`/`inv` prefix.
+        name: String,
+        /// The full setting as written, like `sw=2`, `cpo+=x` or `nowrap`.
+        item: String,
+        /// The assigned value, if any.
+        value: Option<String>,
+        /// `local` for `:setlocal`, or `both` for `:set`, which sets the global and local values.
+        scope: String,
+        doc: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[export_variants(skip)]
+        span: Option<Span>,
+    },
+}
+
+/// The kind of a [VimNode], without any of its contents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum VimNodeKind {
+    StandaloneDocComment,
+    Function,
+    Command,
+    Variable,
+    Flag,
+    Augroup,
+    Autocmd,
+    Mapping,
+    Highlight,
+    Sign,
+    SetOption,
+}
+
+impl VimNode {
+    pub fn kind(&self) -> VimNodeKind {
+        match self {
+            VimNode::StandaloneDocComment { .. } => VimNodeKind::StandaloneDocComment,
+            VimNode::Function { .. } => VimNodeKind::Function,
+            VimNode::Command { .. } => VimNodeKind::Command,
+            VimNode::Variable { .. } => VimNodeKind::Variable,
+            VimNode::Flag { .. } => VimNodeKind::Flag,
+            VimNode::Augroup { .. } => VimNodeKind::Augroup,
+            VimNode::Autocmd { .. } => VimNodeKind::Autocmd,
+            VimNode::Mapping { .. } => VimNodeKind::Mapping,
+            VimNode::Highlight { .. } => VimNodeKind::Highlight,
+            VimNode::Sign { .. } => VimNodeKind::Sign,
+            VimNode::SetOption { .. } => VimNodeKind::SetOption,
+        }
+    }
+
+    /// The name of the symbol this node defines, if any.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            // Options belong to vim, so setting one doesn't define a symbol.
+            VimNode::StandaloneDocComment { .. }
+            | VimNode::Autocmd { .. }
+            | VimNode::Mapping { .. }
+            | VimNode::SetOption { .. } => None,
+            VimNode::Function { name, .. }
+            | VimNode::Command { name, .. }
+            | VimNode::Variable { name, .. }
+            | VimNode::Flag { name, .. }
+            | VimNode::Augroup { name, .. }
+            | VimNode::Highlight { name, .. }
+            | VimNode::Sign { name, .. } => Some(name.as_str()),
+        }
+    }
+
+    /// The vim help tag conventionally used to document this node, if any.
+    ///
+    /// Flags have no global name of their own, so their tags are qualified by `plugin_name`.
+    pub fn help_tag(&self, plugin_name: &str) -> Option<String> {
+        match self {
+            VimNode::StandaloneDocComment { .. }
+            | VimNode::Augroup { .. }
+            | VimNode::Autocmd { .. }
+            | VimNode::Mapping { .. }
+            | VimNode::Sign { .. }
+            | VimNode::SetOption { .. } => None,
+            VimNode::Function { name, .. } => Some(format!("{name}()")),
+            VimNode::Command { name, .. } => Some(format!(":{name}")),
+            VimNode::Variable { name, .. } => Some(name.to_owned()),
+            VimNode::Flag { name, .. } => Some(format!("{plugin_name}:{name}")),
+            VimNode::Highlight { name, .. } => Some(format!("hl-{name}")),
+        }
+    }
+
+    pub fn get_doc(&self) -> Option<&str> {
+        match self {
+            VimNode::StandaloneDocComment { doc, .. } => Some(doc.as_str()),
+            VimNode::Function { doc, .. }
+            | VimNode::Command { doc, .. }
+            | VimNode::Variable { doc, .. }
+            | VimNode::Flag { doc, .. }
+            | VimNode::Augroup { doc, .. }
+            | VimNode::Autocmd { doc, .. }
+            | VimNode::Mapping { doc, .. }
+            | VimNode::Highlight { doc, .. }
+            | VimNode::Sign { doc, .. }
+            | VimNode::SetOption { doc, .. } => doc.as_deref(),
+        }
+    }
+
+    /// Where the node is defined in its file, if the parser recorded spans (see
+    /// [crate::VimParser::set_record_spans]). Spans cover the definition itself, not its doc
+    /// comment.
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            VimNode::StandaloneDocComment { span, .. }
+            | VimNode::Function { span, .. }
+            | VimNode::Command { span, .. }
+            | VimNode::Variable { span, .. }
+            | VimNode::Flag { span, .. }
+            | VimNode::Augroup { span, .. }
+            | VimNode::Autocmd { span, .. }
+            | VimNode::Mapping { span, .. }
+            | VimNode::Highlight { span, .. }
+            | VimNode::Sign { span, .. }
+            | VimNode::SetOption { span, .. } => span.as_ref(),
+        }
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        *self.span_mut() = Some(span);
+        self
+    }
+
+    pub(crate) fn span_mut(&mut self) -> &mut Option<Span> {
+        match self {
+            VimNode::StandaloneDocComment { span, .. }
+            | VimNode::Function { span, .. }
+            | VimNode::Command { span, .. }
+            | VimNode::Variable { span, .. }
+            | VimNode::Flag { span, .. }
+            | VimNode::Augroup { span, .. }
+            | VimNode::Autocmd { span, .. }
+            | VimNode::Mapping { span, .. }
+            | VimNode::Highlight { span, .. }
+            | VimNode::Sign { span, .. }
+            | VimNode::SetOption { span, .. } => span,
+        }
+    }
+}
+
+/// An individual module (a.k.a. file) of vimscript code.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VimModule {
+    pub path: Option<PathBuf>,
+    pub language: ModuleLanguage,
+    pub doc: Option<String>,
+    pub nodes: Vec<VimNode>,
+    /// Where the comment block `doc` came from, if the parser recorded spans.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc_span: Option<Span>,
+    /// Fields like `Maintainer:` from the comment lines at the top of the file.
+    pub header: ModuleHeader,
+    /// The modeline vim would apply when editing this file, if any.
+    pub modeline: Option<Modeline>,
+    /// The encoding declared by `:scriptencoding`, as written (like `latin1`). Files are decoded
+    /// from it when parsed.
+    pub encoding: Option<String>,
+    pub stats: ModuleStats,
+    /// Autoload functions called from this module, like `foo#bar#Baz`, in order of first use.
+    pub autoload_references: Vec<String>,
+    /// The subset of `autoload_references` only called under `silent!` or in `try` blocks, which
+    /// the module treats as optional integrations rather than hard requirements.
+    pub optional_autoload_references: Vec<String>,
+    /// Libraries loaded with `maktaba#library#Require`, like `foo` for
+    /// `maktaba#library#Require('foo')`, in order of first use.
+    pub required_libraries: Vec<String>,
+    /// Calls to user-defined functions, deduplicated in order of first use. Calls to builtin
+    /// functions and dict methods like `self.Foo()` are left out.
+    pub function_calls: Vec<FunctionCall>,
+    /// Scripts imported with `:import` in a Vim9 script, in source order.
+    pub vim9_imports: Vec<Vim9Import>,
+    /// Names exported from a Vim9 script, in source order.
+    pub vim9_exports: Vec<String>,
+    /// Problems found while parsing, like syntax errors the parser skipped over.
+    pub warnings: Vec<Warning>,
+}
+
+/// The language a module is written in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ModuleLanguage {
+    /// Legacy vimscript.
+    #[default]
+    Vim,
+    /// Vim9 script, from files starting with `vim9script`.
+    Vim9,
+    /// Lua, from .lua files in Neovim plugins.
+    Lua,
+}
+
+impl VimModule {
+    pub fn new(nodes: Vec<VimNode>) -> Self {
+        Self {
+            nodes,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_path<P: Into<PathBuf>>(self, path: P) -> Self {
+        Self {
+            path: Some(path.into()),
+            ..self
+        }
+    }
+
+    pub fn with_doc<S: Into<String>>(self, doc: S) -> Self {
+        Self {
+            doc: Some(doc.into()),
+            ..self
+        }
+    }
+
+    /// The runtime section this module belongs to, based on its path relative to the plugin root.
+    ///
+    /// Dir names match case-insensitively, since a parser only keeps paths like `Plugin/x.vim`
+    /// if it was configured to match them that way.
+    pub fn section(&self) -> Option<Section> {
+        classify_runtime_path_with(self.path.as_ref()?, true)
+    }
+
+    /// The filetype this module applies to if it's in ftplugin/, indent/ or syntax/, like
+    /// `python` for ftplugin/python_fold.vim or syntax/python/extra.vim.
+    pub fn filetype(&self) -> Option<String> {
+        self.path.as_deref().and_then(filetype_of)
+    }
+
+    /// Sorts nodes canonically by source position, then by kind and name.
+    ///
+    /// Nodes without a recorded span sort before those with one, ordered by kind and name. The
+    /// sort is stable, so nodes with the same kind and name (like standalone doc comments) keep
+    /// their original relative order.
+    pub fn normalize(&mut self) {
+        self.nodes.sort_by(|a, b| {
+            let position = |node: &VimNode| node.span().map(|span| span.start);
+            (position(a), a.kind(), a.name()).cmp(&(position(b), b.kind(), b.name()))
+        });
+    }
+
+    /// Drops recorded spans, for parsers configured not to record them.
+    pub(crate) fn clear_spans(&mut self) {
+        for node in &mut self.nodes {
+            *node.span_mut() = None;
+        }
+        self.doc_span = None;
+    }
+}
+
+/// An entire vim plugin with all the metadata parsed from its files.
+///
+/// Plugins can be serialized with any serde format and loaded back for analysis elsewhere.
+/// Missing fields deserialize to defaults, so data saved by older versions still loads.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VimPlugin {
+    /// The dir the plugin was parsed from, which module paths are relative to.
+    pub root: Option<PathBuf>,
+    /// Name, version and other details about the plugin as a whole.
+    pub metadata: VimPluginMetadata,
+    pub content: Vec<VimModule>,
+    /// Other plugins found vendored inside this one (see [crate::VendoredPlugins]).
+    pub vendored: Vec<VendoredPlugin>,
+    /// Neovim remote plugins found under rplugin/.
+    pub remote_plugins: Vec<RemotePlugin>,
+    /// Neovim tree-sitter queries found under queries/{lang}/.
+    pub queries: Vec<TreeSitterQuery>,
+    /// Help files found under doc/.
+    pub help_docs: Vec<VimHelpDoc>,
+    /// How and when the plugin was parsed, if the parser was configured to record it (see
+    /// [crate::VimParser::set_record_parse_info]).
+    pub parse_info: Option<ParseInfo>,
+    /// Per-module parse timings, if the parser was configured to record them (see
+    /// [crate::VimParser::set_record_profile]).
+    pub profile: Option<ParseProfile>,
+    /// Modules that failed to read or parse and were left out of [VimPlugin::content], if the
+    /// parser was configured to continue past them (see [crate::VimParser::set_error_policy]).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ModuleError>,
+}
+
+/// A plugin vendored inside another plugin's directory, like bundle/foo/.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VendoredPlugin {
+    /// Path of the vendored plugin's root relative to the parent plugin root.
+    pub path: PathBuf,
+    /// The parsed vendored plugin, if configured to parse it.
+    pub plugin: Option<VimPlugin>,
+}
+
+impl VimPlugin {
+    pub fn new(content: Vec<VimModule>) -> Self {
+        Self {
+            content,
+            ..Default::default()
+        }
+    }
+
+    /// Orders modules by path and their nodes by position, kind and name (see
+    /// [VimModule::normalize]).
+    ///
+    /// Useful for stable diffs and comparisons, where the order modules were discovered in or
+    /// nodes were defined in isn't meaningful.
+    pub fn normalize(&mut self) {
+        self.content.sort_by(|a, b| a.path.cmp(&b.path));
+        for module in self.content.iter_mut() {
+            module.normalize();
+        }
+    }
+
+    /// Returns modules grouped by their runtime section (see [VimModule::section]), with sections
+    /// in the order they first appear.
+    ///
+    /// Modules without a path or outside any known section are omitted.
+    pub fn sections(&self) -> Vec<(Section, Vec<&VimModule>)> {
+        let mut sections: Vec<(Section, Vec<&VimModule>)> = vec![];
+        for module in &self.content {
+            let Some(section) = module.section() else {
+                continue;
+            };
+            match sections.iter_mut().find(|(s, _)| *s == section) {
+                Some((_, modules)) => modules.push(module),
+                None => sections.push((section, vec![module])),
+            }
+        }
+        sections
+    }
+
+    /// Returns the distinct languages this plugin ships tree-sitter queries of the given type
+    /// for (like `highlights` or `textobjects`), sorted by name.
+    pub fn query_languages(&self, query_type: &str) -> Vec<&str> {
+        let mut languages: Vec<&str> = self
+            .queries
+            .iter()
+            .filter(|q| q.query_type == query_type)
+            .map(|q| q.language.as_str())
+            .collect();
+        languages.sort();
+        languages.dedup();
+        languages
+    }
+
+    /// Header fields for the plugin as a whole, taking each field from the first module that
+    /// sets it (see [VimModule::header]).
+    pub fn header(&self) -> ModuleHeader {
+        let mut header = ModuleHeader::default();
+        for module in &self.content {
+            header.merge(&module.header);
+        }
+        header
+    }
+
+    /// Parse warnings from all modules, along with the path of the module each came from.
+    pub fn warnings(&self) -> impl Iterator<Item = (Option<&Path>, &Warning)> {
+        self.content.iter().flat_map(|module| {
+            module
+                .warnings
+                .iter()
+                .map(move |warning| (module.path.as_deref(), warning))
+        })
+    }
+
+    /// Line counts summed across all modules (see [VimModule::stats]).
+    pub fn stats(&self) -> ModuleStats {
+        let mut stats = ModuleStats::default();
+        for module in &self.content {
+            stats += module.stats;
+        }
+        stats
+    }
+
+    /// Returns a normalized copy of this plugin (see [VimPlugin::normalize]).
+    pub fn sorted(&self) -> Self {
+        let mut plugin = self.clone();
+        plugin.normalize();
+        plugin
+    }
+
+    /// Serializes the plugin as compact JSON.
+    pub fn write_to<W: Write>(&self, writer: W) -> crate::Result<()> {
+        serde_json::to_writer(writer, self)?;
+        Ok(())
+    }
+
+    /// Loads a plugin previously written with [VimPlugin::write_to].
+    pub fn read_from<R: Read>(reader: R) -> crate::Result<Self> {
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SectionKind, VimParser};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn plugin_sections() {
+        let plugin = VimPlugin::new(
+            [
+                "plugin/a.vim",
+                "autoload/a.vim",
+                "autoload/a/b.vim",
+                "after/ftplugin/vim.vim",
+                "unknown/x.vim",
+            ]
+            .into_iter()
+            .map(|path| VimModule::default().with_path(path))
+            .collect(),
+        );
+        assert_eq!(
+            plugin
+                .sections()
+                .into_iter()
+                .map(|(section, modules)| (section, modules.len()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    Section {
+                        kind: SectionKind::Plugin,
+                        after: false
+                    },
+                    1
+                ),
+                (
+                    Section {
+                        kind: SectionKind::Autoload,
+                        after: false
+                    },
+                    2
+                ),
+                (
+                    Section {
+                        kind: SectionKind::Ftplugin,
+                        after: true
+                    },
+                    1
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn plugin_header() {
+        let plugin = VimPlugin::new(vec![
+            VimModule {
+                header: ModuleHeader {
+                    maintainer: Some("Jane".into()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            VimModule {
+                header: ModuleHeader {
+                    maintainer: Some("John".into()),
+                    license: Some("MIT".into()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ]);
+        assert_eq!(
+            plugin.header(),
+            ModuleHeader {
+                maintainer: Some("Jane".into()),
+                license: Some("MIT".into()),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn plugin_json_round_trip() {
+        let code = r#"
+" Maintainer: Jane
+""
+" Frobs things.
+func! foo#Frob(...)
+  call bar#Baz()
+endfunc
+" vim: set sw=2:
+"#;
+        let module = crate::VimParser::new()
+            .unwrap()
+            .parse_module_str(code)
+            .unwrap()
+            .with_path("autoload/foo.vim");
+        let plugin = VimPlugin::new(vec![module]);
+        let mut json = vec![];
+        plugin.write_to(&mut json).unwrap();
+        let loaded = VimPlugin::read_from(json.as_slice()).unwrap();
+        assert_eq!(loaded, plugin);
+        assert_eq!(
+            crate::lint::lint_plugin(&loaded),
+            crate::lint::lint_plugin(&plugin)
+        );
+        assert_eq!(
+            loaded.unresolved_autoload_namespaces(&[]),
+            plugin.unresolved_autoload_namespaces(&[])
+        );
+        // Data from older versions without newer fields still loads.
+        let old_json = r#"{"content": [{"nodes": [{"kind": "Command", "name": "Foo",
+            "modifiers": [], "repl": null, "doc": null}]}]}"#;
+        assert_eq!(
+            VimPlugin::read_from(old_json.as_bytes()).unwrap(),
+            VimPlugin::new(vec![VimModule::new(vec![VimNode::command("Foo").build()])])
+        );
+    }
+
+    #[test]
+    fn plugin_sorted() {
+        let plugin = VimPlugin::new(vec![
+            VimModule::new(vec![
+                VimNode::variable("s:b", "1").into(),
+                VimNode::function("B").into(),
+                VimNode::variable("s:a", "2").into(),
+                VimNode::function("A").into(),
+            ])
+            .with_path("plugin/b.vim"),
+            VimModule::new(vec![]).with_path("autoload/a.vim"),
+        ]);
+        assert_eq!(
+            plugin.sorted(),
+            VimPlugin::new(vec![
+                VimModule::new(vec![]).with_path("autoload/a.vim"),
+                VimModule::new(vec![
+                    VimNode::function("A").into(),
+                    VimNode::function("B").into(),
+                    VimNode::variable("s:a", "2").into(),
+                    VimNode::variable("s:b", "1").into(),
+                ])
+                .with_path("plugin/b.vim"),
+            ])
+        );
+    }
+
+    #[test]
+    fn module_normalize_orders_by_position() {
+        let mut parser = VimParser::new().unwrap();
+        parser.set_record_spans(true);
+        let mut module = parser
+            .parse_module_str("let s:b = 1\nfunction! B()\nendfunction\nlet s:a = 2\n")
+            .unwrap();
+        let expected = module.nodes.clone();
+        module.nodes.reverse();
+        module.normalize();
+        assert_eq!(module.nodes, expected);
+    }
+}
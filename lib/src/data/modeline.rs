@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+/// A vim modeline like `" vim: set sw=2 et:` (see `:help modeline`).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Modeline {
+    /// Options in the order given, with values for `name=value` options and None for flags like
+    /// `et` or `noet`.
+    pub options: Vec<(String, Option<String>)>,
+}
+
+impl Modeline {
+    /// Looks up an option by the exact name it was given as, like `sw` or `shiftwidth`.
+    ///
+    /// Returns `Some(None)` for flags without a value.
+    pub fn option(&self, name: &str) -> Option<Option<&str>> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_deref())
+    }
+}
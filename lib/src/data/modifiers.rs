@@ -0,0 +1,245 @@
+//! Interpreted forms of the raw modifier strings stored on nodes.
+//!
+//! Nodes keep modifiers exactly as written in the source. These types parse them into named
+//! fields for consumers who don't want to re-implement vim's modifier syntax.
+
+use crate::VimNode;
+use serde::{Deserialize, Serialize};
+
+/// Parsed form of [VimNode::Function] modifiers like `!`, `range` and `abort`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FunctionModifiers {
+    pub bang: bool,
+    pub range: bool,
+    pub dict: bool,
+    pub abort: bool,
+    pub closure: bool,
+    /// Any modifiers not recognized above, in their raw form.
+    pub unknown: Vec<String>,
+}
+
+impl FunctionModifiers {
+    pub fn parse<S: AsRef<str>>(raw_modifiers: &[S]) -> Self {
+        let mut modifiers = Self::default();
+        for raw in raw_modifiers {
+            match raw.as_ref() {
+                "!" => modifiers.bang = true,
+                "range" => modifiers.range = true,
+                "dict" => modifiers.dict = true,
+                "abort" => modifiers.abort = true,
+                "closure" => modifiers.closure = true,
+                other => modifiers.unknown.push(other.to_string()),
+            }
+        }
+        modifiers
+    }
+}
+
+/// Parsed form of [VimNode::Command] attributes like `-nargs=*` and `-bang`.
+///
+/// Attributes that take an optional value (`-range`, `-count`) are recorded with an empty string
+/// when given without one.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommandAttributes {
+    pub nargs: Option<String>,
+    pub complete: Option<String>,
+    pub range: Option<String>,
+    pub count: Option<String>,
+    pub addr: Option<String>,
+    pub bang: bool,
+    pub bar: bool,
+    pub register: bool,
+    pub buffer: bool,
+    pub keepscript: bool,
+    /// Any attributes not recognized above, in their raw form.
+    pub unknown: Vec<String>,
+}
+
+impl CommandAttributes {
+    pub fn parse<S: AsRef<str>>(raw_attributes: &[S]) -> Self {
+        let mut attributes = Self::default();
+        for raw in raw_attributes {
+            let raw = raw.as_ref();
+            let (name, value) = match raw.split_once('=') {
+                Some((name, value)) => (name, value),
+                None => (raw, ""),
+            };
+            match name {
+                "-nargs" => attributes.nargs = Some(value.to_string()),
+                "-complete" => attributes.complete = Some(value.to_string()),
+                "-range" => attributes.range = Some(value.to_string()),
+                "-count" => attributes.count = Some(value.to_string()),
+                "-addr" => attributes.addr = Some(value.to_string()),
+                "-bang" => attributes.bang = true,
+                "-bar" => attributes.bar = true,
+                "-register" => attributes.register = true,
+                "-buffer" => attributes.buffer = true,
+                "-keepscript" => attributes.keepscript = true,
+                _ => attributes.unknown.push(raw.to_string()),
+            }
+        }
+        attributes
+    }
+
+    /// The function named by `-complete=custom,{func}` or `-complete=customlist,{func}`, which
+    /// vim calls to complete the command's arguments.
+    pub fn completion_function(&self) -> Option<&str> {
+        let (kind, function) = self.complete.as_deref()?.split_once(',')?;
+        matches!(kind, "custom" | "customlist").then_some(function)
+    }
+}
+
+/// Parsed form of `:map` arguments like `<silent>` and `<buffer>`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MapArguments {
+    pub buffer: bool,
+    pub nowait: bool,
+    pub silent: bool,
+    pub special: bool,
+    pub script: bool,
+    pub expr: bool,
+    pub unique: bool,
+    /// Any arguments not recognized above, in their raw form.
+    pub unknown: Vec<String>,
+}
+
+impl MapArguments {
+    pub fn parse<S: AsRef<str>>(raw_arguments: &[S]) -> Self {
+        let mut arguments = Self::default();
+        for raw in raw_arguments {
+            let raw = raw.as_ref();
+            match raw.to_ascii_lowercase().as_str() {
+                "<buffer>" => arguments.buffer = true,
+                "<nowait>" => arguments.nowait = true,
+                "<silent>" => arguments.silent = true,
+                "<special>" => arguments.special = true,
+                "<script>" => arguments.script = true,
+                "<expr>" => arguments.expr = true,
+                "<unique>" => arguments.unique = true,
+                _ => arguments.unknown.push(raw.to_string()),
+            }
+        }
+        arguments
+    }
+}
+
+impl VimNode {
+    /// Parsed modifiers if this is a [VimNode::Function].
+    pub fn function_modifiers(&self) -> Option<FunctionModifiers> {
+        match self {
+            VimNode::Function { modifiers, .. } => Some(FunctionModifiers::parse(modifiers)),
+            _ => None,
+        }
+    }
+
+    /// Parsed attributes if this is a [VimNode::Command].
+    pub fn command_attributes(&self) -> Option<CommandAttributes> {
+        match self {
+            VimNode::Command { modifiers, .. } => Some(CommandAttributes::parse(modifiers)),
+            _ => None,
+        }
+    }
+
+    /// The custom completion function if this is a [VimNode::Command] with one (see
+    /// [CommandAttributes::completion_function]).
+    pub fn completion_function(&self) -> Option<String> {
+        self.command_attributes()?
+            .completion_function()
+            .map(str::to_string)
+    }
+
+    /// Parsed arguments if this is a [VimNode::Mapping].
+    pub fn map_arguments(&self) -> Option<MapArguments> {
+        match self {
+            VimNode::Mapping { arguments, .. } => Some(MapArguments::parse(arguments)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn function_modifiers() {
+        let node = VimNode::function("Foo")
+            .modifiers(["!", "range", "abort", "weird"])
+            .build();
+        assert_eq!(
+            node.function_modifiers(),
+            Some(FunctionModifiers {
+                bang: true,
+                range: true,
+                abort: true,
+                unknown: vec!["weird".into()],
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn command_attributes() {
+        let node = VimNode::command("Foo")
+            .modifiers([
+                "-range",
+                "-nargs=+",
+                "-complete=customlist,foo#Complete",
+                "-bar",
+            ])
+            .build();
+        assert_eq!(
+            node.command_attributes(),
+            Some(CommandAttributes {
+                nargs: Some("+".into()),
+                complete: Some("customlist,foo#Complete".into()),
+                range: Some("".into()),
+                bar: true,
+                ..Default::default()
+            })
+        );
+        assert_eq!(node.function_modifiers(), None);
+        assert_eq!(node.completion_function().as_deref(), Some("foo#Complete"));
+        assert_eq!(
+            VimNode::command("Foo")
+                .modifiers(["-complete=file"])
+                .build()
+                .completion_function(),
+            None
+        );
+    }
+
+    #[test]
+    fn map_arguments() {
+        assert_eq!(
+            MapArguments::parse(&["<silent>", "<BUFFER>"]),
+            MapArguments {
+                silent: true,
+                buffer: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn modifiers_json_format() {
+        let modifiers = FunctionModifiers::parse(&["abort", "weird"]);
+        let json = serde_json::to_string(&modifiers).unwrap();
+        assert_eq!(
+            json,
+            r#"{"bang":false,"range":false,"dict":false,"abort":true,"closure":false,"unknown":["weird"]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<FunctionModifiers>(&json).unwrap(),
+            modifiers
+        );
+        assert_eq!(
+            serde_json::from_str::<CommandAttributes>(r#"{"nargs":"*"}"#).unwrap(),
+            CommandAttributes::parse(&["-nargs=*"])
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use crate::VimNode;
+
+/// A node of a module along with the definitions nested under it in the code, like the autocmds
+/// in an augroup block or functions defined inside a function's body.
+///
+/// See [crate::VimParser::parse_module_tree].
+#[derive(Clone, Debug, PartialEq)]
+pub struct VimNodeTree {
+    pub node: VimNode,
+    pub children: Vec<VimNodeTree>,
+}
+
+impl VimNodeTree {
+    /// A node with no children.
+    pub fn leaf(node: VimNode) -> Self {
+        Self {
+            node,
+            children: vec![],
+        }
+    }
+
+    /// Iterates over the nodes of some trees depth-first, with parents before their children.
+    ///
+    /// That's source order, so for modules without nested definitions it matches the nodes
+    /// [crate::VimParser::parse_module_str] returns.
+    pub fn flatten(trees: &[VimNodeTree]) -> impl Iterator<Item = &VimNode> {
+        let mut stack: Vec<&VimNodeTree> = trees.iter().rev().collect();
+        std::iter::from_fn(move || {
+            let tree = stack.pop()?;
+            stack.extend(tree.children.iter().rev());
+            Some(&tree.node)
+        })
+    }
+
+    /// Calls `f` on every node of some trees, parents before their children.
+    pub(crate) fn for_each_node_mut<F: FnMut(&mut VimNode)>(trees: &mut [VimNodeTree], f: &mut F) {
+        for tree in trees {
+            f(&mut tree.node);
+            Self::for_each_node_mut(&mut tree.children, f);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VimParser;
+    use pretty_assertions::assert_eq;
+
+    /// Summarizes trees as their node names, with children indented under their parents.
+    fn outline(trees: &[VimNodeTree], depth: usize) -> Vec<String> {
+        trees
+            .iter()
+            .flat_map(|tree| {
+                let name = match &tree.node {
+                    VimNode::Autocmd {
+                        group, patterns, ..
+                    } => format!(
+                        "autocmd {} {}",
+                        group.as_deref().unwrap_or("-"),
+                        patterns.join(",")
+                    ),
+                    node => node.name().unwrap().to_string(),
+                };
+                std::iter::once(format!("{}{name}", "  ".repeat(depth)))
+                    .chain(outline(&tree.children, depth + 1))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_module_tree_follows_syntax_nesting() {
+        let code = r#"
+augroup foo
+  autocmd!
+  autocmd BufRead *.foo setlocal ft=foo
+  if has('nvim')
+    autocmd TermOpen * startinsert
+  endif
+augroup END
+autocmd foo BufNewFile *.foo setlocal ft=foo
+if !exists('*Outer')
+  function! Outer() abort
+    let l:thing = {}
+    ""
+    " Documented dict function.
+    function l:thing.Inner() dict
+    endfunction
+    return l:thing
+  endfunction
+else
+  command Fallback echo
+endif
+"#;
+        let trees = VimParser::new().unwrap().parse_module_tree(code).unwrap();
+        assert_eq!(
+            outline(&trees, 0),
+            vec![
+                "foo",
+                "  autocmd foo *.foo",
+                "  autocmd foo *",
+                "autocmd foo *.foo",
+                "Outer",
+                "  l:thing",
+                "  l:thing.Inner",
+                "Fallback",
+            ]
+        );
+        assert!(matches!(
+            &trees[2].children[1].node,
+            VimNode::Function { doc: Some(doc), .. } if doc == "Documented dict function."
+        ));
+        // Flattening gives all nodes in source order.
+        assert_eq!(
+            VimNodeTree::flatten(&trees)
+                .filter_map(VimNode::name)
+                .collect::<Vec<_>>(),
+            vec!["foo", "Outer", "l:thing", "l:thing.Inner", "Fallback"]
+        );
+    }
+}
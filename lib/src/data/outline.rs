@@ -0,0 +1,127 @@
+use crate::data::doc_directives;
+use crate::{VimNode, VimPlugin};
+use std::path::PathBuf;
+
+/// The layout of a plugin's generated help, from `@section` and `@order` doc directives.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DocOutline {
+    /// Section ids from the plugin's `@order` directive, which can also name sections the help
+    /// generator creates itself (like `functions` or `commands`).
+    pub order: Vec<String>,
+    /// Sections defined with `@section`, sorted by `order` and then in the order they're defined.
+    pub sections: Vec<DocSection>,
+}
+
+/// A custom help section, from a doc comment starting with `@section Title[, id]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DocSection {
+    /// The id given after the title, or else the title lowercased without spaces.
+    pub id: String,
+    pub title: String,
+    /// The rest of the doc comment after the `@section` line.
+    pub doc: String,
+    /// The module the section was defined in.
+    pub module: Option<PathBuf>,
+}
+
+impl VimPlugin {
+    /// Collects `@section` and `@order` directives from module docs and standalone doc comments.
+    ///
+    /// If there are several `@order` directives, the first one wins.
+    pub fn doc_outline(&self) -> DocOutline {
+        let mut outline = DocOutline::default();
+        let mut order = None;
+        for module in &self.content {
+            let standalone_docs = module.nodes.iter().filter_map(|node| match node {
+                VimNode::StandaloneDocComment { doc, .. } => Some(doc.as_str()),
+                _ => None,
+            });
+            for doc in module.doc.as_deref().into_iter().chain(standalone_docs) {
+                if order.is_none() {
+                    order = doc_directives(doc)
+                        .find(|(directive, _)| *directive == "order")
+                        .map(|(_, ids)| ids.split_whitespace().map(str::to_string).collect());
+                }
+                if let Some(mut section) = parse_section(doc) {
+                    section.module = module.path.clone();
+                    outline.sections.push(section);
+                }
+            }
+        }
+        outline.order = order.unwrap_or_default();
+        let position = |section: &DocSection| {
+            outline
+                .order
+                .iter()
+                .position(|id| *id == section.id)
+                .unwrap_or(usize::MAX)
+        };
+        // Stable sort, so unordered sections stay in definition order.
+        let mut sections = std::mem::take(&mut outline.sections);
+        sections.sort_by_key(position);
+        outline.sections = sections;
+        outline
+    }
+}
+
+fn parse_section(doc: &str) -> Option<DocSection> {
+    let (_, rest) = doc_directives(doc).find(|(directive, _)| *directive == "section")?;
+    let (title, id) = match rest.split_once(',') {
+        Some((title, id)) => (title.trim(), id.trim().to_string()),
+        None => (
+            rest.trim(),
+            rest.split_whitespace().collect::<String>().to_lowercase(),
+        ),
+    };
+    let doc = doc
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("@section"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(DocSection {
+        id,
+        title: title.to_string(),
+        doc: doc.trim_matches('\n').to_string(),
+        module: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VimModule;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn doc_outline_ordering() {
+        let plugin = VimPlugin::new(vec![
+            VimModule::new(vec![
+                VimNode::doc_comment("@section Introduction, intro\nFrobs things."),
+                VimNode::doc_comment("@section About Us\nBy Jane."),
+            ])
+            .with_path("plugin/foo.vim")
+            .with_doc("@order intro config functions aboutus"),
+            VimModule::new(vec![VimNode::doc_comment(
+                "@section Configuration, config\nSet g:foo_things.",
+            )])
+            .with_path("autoload/foo.vim"),
+        ]);
+        let outline = plugin.doc_outline();
+        assert_eq!(
+            outline.order,
+            vec!["intro", "config", "functions", "aboutus"]
+        );
+        assert_eq!(
+            outline
+                .sections
+                .iter()
+                .map(|s| (s.id.as_str(), s.title.as_str(), s.doc.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("intro", "Introduction", "Frobs things."),
+                ("config", "Configuration", "Set g:foo_things."),
+                ("aboutus", "About Us", "By Jane."),
+            ]
+        );
+    }
+}
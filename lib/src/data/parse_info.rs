@@ -0,0 +1,137 @@
+use crate::parser::{decode_source_with, plugin_module_paths, WalkOptions};
+use crate::{EncodingFallback, VimPlugin};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Provenance of parsed metadata, for checking whether cached results are still valid.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ParseInfo {
+    /// Version of this crate that did the parsing.
+    pub crate_version: String,
+    /// ABI version of the tree-sitter vim grammar used.
+    pub grammar_version: usize,
+    pub parsed_at: SystemTime,
+    /// Hash of the paths and contents of all parsed files, stable across runs and platforms.
+    pub fingerprint: u64,
+    /// How files that didn't decode cleanly were read, which the fingerprint depends on.
+    #[serde(default)]
+    pub encoding_fallback: EncodingFallback,
+}
+
+impl ParseInfo {
+    pub(crate) fn new(
+        grammar_version: usize,
+        fingerprint: u64,
+        encoding_fallback: EncodingFallback,
+    ) -> Self {
+        Self {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            grammar_version,
+            parsed_at: SystemTime::now(),
+            fingerprint,
+            encoding_fallback,
+        }
+    }
+}
+
+impl VimPlugin {
+    /// Whether the plugin's metadata may be out of date, because its files changed since it was
+    /// parsed or it was parsed by a different crate or grammar version.
+    ///
+    /// Plugins without a [VimPlugin::root] or [ParseInfo] are always considered stale. Files
+    /// are compared assuming the default [crate::VimParserBuilder] sections, and read with the
+    /// [ParseInfo::encoding_fallback] they were parsed with.
+    pub fn is_stale(&self) -> crate::Result<bool> {
+        let (Some(root), Some(info)) = (&self.root, &self.parse_info) else {
+            return Ok(true);
+        };
+        if info.crate_version != env!("CARGO_PKG_VERSION")
+            || info.grammar_version != tree_sitter_vim::language().version()
+        {
+            return Ok(true);
+        }
+        let mut fingerprint = Fingerprint::new();
+        for module_path in plugin_module_paths(root, &WalkOptions::default())? {
+            let relative_path = module_path.strip_prefix(root).unwrap();
+            // Files that don't decode were skipped with ErrorPolicy::CollectErrors.
+            if let Ok(code) = decode_source_with(&fs::read(&module_path)?, info.encoding_fallback) {
+                fingerprint.add_module(relative_path, &code);
+            }
+        }
+        Ok(fingerprint.finish() != info.fingerprint)
+    }
+}
+
+/// Incrementally computes a 64-bit FNV-1a hash, which unlike std's hashers is guaranteed stable.
+#[derive(Clone, Debug)]
+pub(crate) struct Fingerprint(u64);
+
+impl Fingerprint {
+    pub fn new() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+
+    pub fn add_module(&mut self, relative_path: &Path, code: &str) {
+        self.update(relative_path.to_string_lossy().as_bytes());
+        self.update(code.as_bytes());
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+        // Separate fields so ("ab", "c") and ("a", "bc") hash differently.
+        self.0 ^= 0xff;
+        self.0 = self.0.wrapping_mul(0x100000001b3);
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EncodingFallback, ErrorPolicy, VimParser};
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn is_stale_after_changes() {
+        let tmp_dir = tempdir().unwrap();
+        fs::create_dir(tmp_dir.path().join("plugin")).unwrap();
+        fs::write(tmp_dir.path().join("plugin/foo.vim"), "let g:foo = 1\n").unwrap();
+        let mut parser = VimParser::new().unwrap();
+        assert_eq!(
+            parser.parse_plugin_dir(tmp_dir.path()).unwrap().parse_info,
+            None
+        );
+        parser.set_record_parse_info(true);
+        let plugin = parser.parse_plugin_dir(tmp_dir.path()).unwrap();
+        assert!(plugin.parse_info.is_some());
+        assert!(!plugin.is_stale().unwrap());
+        fs::write(tmp_dir.path().join("plugin/bar.vim"), "").unwrap();
+        assert!(plugin.is_stale().unwrap());
+    }
+
+    #[test]
+    fn is_stale_reads_files_with_encoding_fallback() {
+        let tmp_dir = tempdir().unwrap();
+        fs::create_dir(tmp_dir.path().join("plugin")).unwrap();
+        fs::write(tmp_dir.path().join("plugin/foo.vim"), b"\" Caf\xe9\n").unwrap();
+        for encoding_fallback in [EncodingFallback::Latin1, EncodingFallback::Strict] {
+            let plugin = VimParser::builder()
+                .record_parse_info(true)
+                .encoding_fallback(encoding_fallback)
+                .error_policy(ErrorPolicy::CollectErrors)
+                .build()
+                .unwrap()
+                .parse_plugin_dir(tmp_dir.path())
+                .unwrap();
+            assert!(!plugin.is_stale().unwrap(), "{encoding_fallback:?}");
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+/// Descriptive metadata for a plugin as a whole, from its addon-info.json manifest, the header
+/// of its help file or the headers of its plugin/ files.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VimPluginMetadata {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub version: Option<String>,
+    pub author: Option<String>,
+    pub license: Option<String>,
+    /// The plugin's homepage or repository URL.
+    pub homepage: Option<String>,
+    /// Names of other plugins this one depends on.
+    pub dependencies: Vec<String>,
+}
+
+impl VimPluginMetadata {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Fills in any fields missing here from `other`.
+    pub(crate) fn merge(&mut self, other: VimPluginMetadata) {
+        for (field, other_field) in [
+            (&mut self.name, other.name),
+            (&mut self.description, other.description),
+            (&mut self.version, other.version),
+            (&mut self.author, other.author),
+            (&mut self.license, other.license),
+            (&mut self.homepage, other.homepage),
+        ] {
+            if field.is_none() {
+                *field = other_field;
+            }
+        }
+        if self.dependencies.is_empty() {
+            self.dependencies = other.dependencies;
+        }
+    }
+}
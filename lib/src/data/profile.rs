@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How long parsing each module of a plugin took, for finding pathological files and measuring
+/// performance (see [crate::VimParser::set_record_profile]).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParseProfile {
+    /// Modules in the order they were parsed.
+    pub modules: Vec<ModuleProfile>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModuleProfile {
+    /// Path of the module, relative to the plugin root.
+    pub path: PathBuf,
+    /// Time spent reading, decoding and parsing the file.
+    pub duration: Duration,
+    /// Size of the decoded source.
+    pub bytes: usize,
+    pub node_count: usize,
+}
+
+impl ParseProfile {
+    /// The total time spent parsing modules.
+    pub fn total_duration(&self) -> Duration {
+        self.modules.iter().map(|module| module.duration).sum()
+    }
+
+    /// The `n` modules that took longest to parse, slowest first.
+    pub fn slowest(&self, n: usize) -> Vec<&ModuleProfile> {
+        let mut modules: Vec<_> = self.modules.iter().collect();
+        modules.sort_by_key(|module| std::cmp::Reverse(module.duration));
+        modules.truncate(n);
+        modules
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn slowest_modules() {
+        let module = |path: &str, millis| ModuleProfile {
+            path: path.into(),
+            duration: Duration::from_millis(millis),
+            bytes: 0,
+            node_count: 0,
+        };
+        let profile = ParseProfile {
+            modules: vec![module("a.vim", 2), module("b.vim", 30), module("c.vim", 5)],
+        };
+        assert_eq!(profile.total_duration(), Duration::from_millis(37));
+        assert_eq!(
+            profile
+                .slowest(2)
+                .iter()
+                .map(|m| m.path.to_str().unwrap())
+                .collect::<Vec<_>>(),
+            vec!["b.vim", "c.vim"]
+        );
+    }
+}
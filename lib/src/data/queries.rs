@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A Neovim tree-sitter query file like queries/python/highlights.scm.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TreeSitterQuery {
+    /// The language the query extends, like `python`.
+    pub language: String,
+    /// The kind of query, like `highlights`, `injections` or `textobjects`.
+    pub query_type: String,
+    /// Path of the query file relative to the plugin root.
+    pub path: PathBuf,
+    /// Distinct capture names used in the query, like `function.call`, in order of appearance.
+    pub captures: Vec<String>,
+}
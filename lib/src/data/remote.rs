@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A Neovim remote plugin under rplugin/{host}/ (see `:help remote-plugin`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RemotePlugin {
+    /// The host that runs the plugin, like `python3` or `node`.
+    pub host: String,
+    /// The entry script or package, relative to the plugin root (like
+    /// rplugin/python3/foo.py), or as given in a registration stub if no file was found for it.
+    pub path: PathBuf,
+    /// Commands, functions and autocmds registered for the plugin by vim-side
+    /// `remote#host#RegisterPlugin()` stubs.
+    pub registrations: Vec<RemoteRegistration>,
+}
+
+/// A single handler a remote plugin registers, from the specs passed to
+/// `remote#host#RegisterPlugin()`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RemoteRegistration {
+    /// The kind of handler: `command`, `function` or `autocmd`.
+    pub kind: String,
+    /// The command/function name, or the event for an autocmd.
+    pub name: String,
+    /// Whether vim blocks waiting for the handler to finish.
+    pub sync: bool,
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use std::ops::{Range, RangeInclusive};
+use std::path::PathBuf;
+
+/// A location in a source file.
+///
+/// Rows and columns are 0-based, with columns counted in bytes like tree-sitter points.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Span {
+    /// The (row, column) where the span starts.
+    pub start: (usize, usize),
+    /// The (row, column) just past the end of the span.
+    pub end: (usize, usize),
+    pub byte_range: Range<usize>,
+}
+
+impl Span {
+    /// Whether the (row, column) position is within the span.
+    pub fn contains(&self, position: (usize, usize)) -> bool {
+        self.start <= position && position < self.end
+    }
+
+    /// The span of whole lines of `code`, from the start of the first row to the end of the last
+    /// one (not including its line break).
+    pub(crate) fn of_rows(code: &str, rows: RangeInclusive<usize>) -> Self {
+        let mut start_byte = code.len();
+        let mut end = (*rows.end(), 0);
+        let mut end_byte = code.len();
+        let mut line_start = 0;
+        for (row, line) in code.split_inclusive('\n').enumerate() {
+            if row == *rows.start() {
+                start_byte = line_start;
+            }
+            if row == *rows.end() {
+                let content = line.trim_end_matches(['\n', '\r']);
+                end.1 = content.len();
+                end_byte = line_start + content.len();
+                break;
+            }
+            line_start += line.len();
+        }
+        Self {
+            start: (*rows.start(), 0),
+            end,
+            byte_range: start_byte..end_byte,
+        }
+    }
+}
+
+/// A change to source text: replacing the text at `range` with `new_text`.
+///
+/// Insertions use an empty range at the insertion point.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TextEdit {
+    /// The file to edit, when not implied by context (like the file of a lint finding).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    pub range: Span,
+    pub new_text: String,
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use std::ops::AddAssign;
+
+/// Line counts for a module, or summed across modules.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleStats {
+    pub blank_lines: usize,
+    /// Lines holding only a comment, including doc comments.
+    pub comment_lines: usize,
+    /// Comment lines that are part of a `""` doc comment block.
+    pub doc_comment_lines: usize,
+    /// Lines with any code on them, even if they also end in a comment.
+    pub code_lines: usize,
+}
+
+impl ModuleStats {
+    pub fn total_lines(&self) -> usize {
+        self.blank_lines + self.comment_lines + self.code_lines
+    }
+
+    /// Comment lines per line of code, or None if there's no code.
+    pub fn comment_ratio(&self) -> Option<f64> {
+        (self.code_lines > 0).then(|| self.comment_lines as f64 / self.code_lines as f64)
+    }
+}
+
+impl AddAssign for ModuleStats {
+    fn add_assign(&mut self, other: Self) {
+        self.blank_lines += other.blank_lines;
+        self.comment_lines += other.comment_lines;
+        self.doc_comment_lines += other.doc_comment_lines;
+        self.code_lines += other.code_lines;
+    }
+}
@@ -0,0 +1,130 @@
+//! Visibility of functions and variables, from vim's name scoping rules (see
+//! `:help internal-variables` and `:help local-function`).
+
+use crate::VimNode;
+use serde::{Deserialize, Serialize};
+
+/// Where a function or variable can be referenced from, based on its name's scope prefix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Visibility {
+    /// `s:` or `<SID>` names, only reachable from the defining script.
+    ScriptLocal,
+    /// `g:` names and unprefixed names outside functions, reachable from anywhere.
+    Global,
+    /// Names under an autoload namespace like `foo#bar#Baz`, loaded on first use.
+    Autoload,
+    /// `b:` names, set per buffer.
+    BufferLocal,
+    /// `w:` names, set per window.
+    WindowLocal,
+    /// `t:` names, set per tab page.
+    TabLocal,
+    /// `l:` and `a:` names, local to a function call.
+    FunctionLocal,
+    /// `v:` names predefined by vim.
+    Vim,
+}
+
+impl Visibility {
+    /// Classifies a function or variable name by its scope prefix.
+    ///
+    /// This follows legacy vimscript rules. In vim9script, unprefixed names are script-local
+    /// unless exported, which can't be told from the name alone.
+    pub fn of_name(name: &str) -> Self {
+        if name
+            .get(..5)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("<SID>"))
+        {
+            return Visibility::ScriptLocal;
+        }
+        let (scope, rest) = match name.split_once(':') {
+            Some((scope, rest)) if scope.len() == 1 => (scope, rest),
+            _ => ("", name),
+        };
+        match scope {
+            "s" => Visibility::ScriptLocal,
+            "b" => Visibility::BufferLocal,
+            "w" => Visibility::WindowLocal,
+            "t" => Visibility::TabLocal,
+            "l" | "a" => Visibility::FunctionLocal,
+            "v" => Visibility::Vim,
+            _ if rest.contains('#') => Visibility::Autoload,
+            _ => Visibility::Global,
+        }
+    }
+
+    /// Whether the name is usable outside the defining script as part of its API, as for
+    /// [Visibility::Global] and [Visibility::Autoload].
+    pub fn is_public(&self) -> bool {
+        matches!(self, Visibility::Global | Visibility::Autoload)
+    }
+}
+
+impl VimNode {
+    /// The visibility of a [VimNode::Function] or [VimNode::Variable] from its name, or None for
+    /// other nodes.
+    pub fn visibility(&self) -> Option<Visibility> {
+        match self {
+            VimNode::Function { name, .. } | VimNode::Variable { name, .. } => {
+                Some(Visibility::of_name(name))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the node is part of its plugin's public API, as opposed to internal helpers like
+    /// script-local functions or buffer variables. Nodes without a [VimNode::visibility] count
+    /// as public.
+    pub fn is_public(&self) -> bool {
+        match self.visibility() {
+            Some(visibility) => visibility.is_public(),
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn visibility_of_names() {
+        assert_eq!(
+            [
+                "s:Helper",
+                "<sid>Helper",
+                "g:foo_enabled",
+                "Foo",
+                "foo#bar#Baz",
+                "g:foo#bar#setting",
+                "b:foo_state",
+                "w:x",
+                "t:x",
+                "l:count",
+                "a:000",
+                "v:version",
+            ]
+            .map(Visibility::of_name),
+            [
+                Visibility::ScriptLocal,
+                Visibility::ScriptLocal,
+                Visibility::Global,
+                Visibility::Global,
+                Visibility::Autoload,
+                Visibility::Autoload,
+                Visibility::BufferLocal,
+                Visibility::WindowLocal,
+                Visibility::TabLocal,
+                Visibility::FunctionLocal,
+                Visibility::FunctionLocal,
+                Visibility::Vim,
+            ]
+        );
+        assert_eq!(
+            VimNode::function("s:Foo").build().visibility(),
+            Some(Visibility::ScriptLocal)
+        );
+        assert_eq!(VimNode::command("Foo").build().visibility(), None);
+    }
+}
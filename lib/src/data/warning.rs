@@ -0,0 +1,65 @@
+use crate::Span;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+
+/// A problem found while parsing a module that didn't stop the rest of it from being parsed,
+/// like a syntax error in one statement.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub message: String,
+    /// Where in the module the problem is.
+    pub span: Span,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WarningKind {
+    /// Code the grammar couldn't parse, which is skipped.
+    SyntaxError,
+    /// A definition whose name couldn't be found, like a function with a malformed signature.
+    MissingName,
+    /// Syntax nodes grouped in a way the parser didn't expect, indicating a parser bug.
+    Internal,
+    /// A module path the parser couldn't handle normally, like one that isn't valid unicode.
+    /// These have an empty span at the start of the module.
+    Path,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (row, column) = self.span.start;
+        write!(f, "{}:{}: {}", row + 1, column + 1, self.message)
+    }
+}
+
+/// A module that couldn't be read or parsed at all, recorded in [crate::VimPlugin::errors] by a
+/// parser configured with [crate::ErrorPolicy::CollectErrors].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModuleError {
+    /// Path of the module, relative to the plugin root.
+    pub path: PathBuf,
+    /// The (row, column) in the module where the problem is, when it can be pinned down.
+    pub position: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl ModuleError {
+    pub(crate) fn new(path: PathBuf, err: &crate::Error) -> Self {
+        Self {
+            path,
+            position: err.position(),
+            message: err.inner().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ModuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some((row, column)) = self.position {
+            write!(f, ":{}:{}", row + 1, column + 1)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
@@ -0,0 +1,107 @@
+use crate::VimPlugin;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Another plugin or library a plugin depends on (see [VimPlugin::dependencies]).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PluginDependency {
+    pub name: String,
+    /// Where the dependency was declared, in the order found.
+    pub sources: Vec<DependencySource>,
+}
+
+/// How a [PluginDependency] was declared.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum DependencySource {
+    /// Listed in the addon-info.json `dependencies`.
+    Manifest,
+    /// A dependency on maktaba itself, from a call to `maktaba#plugin#Enter` registering the
+    /// plugin with it in the module at this path.
+    MaktabaEnter(Option<PathBuf>),
+    /// Loaded with `maktaba#library#Require` in the module at this path.
+    LibraryRequire(Option<PathBuf>),
+}
+
+impl VimPlugin {
+    /// Lists the plugins and libraries this plugin requires, from its manifest and from maktaba
+    /// calls that load them, in order of first mention.
+    pub fn dependencies(&self) -> Vec<PluginDependency> {
+        let mut dependencies: Vec<PluginDependency> = vec![];
+        let mut add = |name: &str, source: DependencySource| {
+            if self.metadata.name.as_deref() == Some(name) {
+                return;
+            }
+            match dependencies.iter_mut().find(|d| d.name == name) {
+                Some(existing) => existing.sources.push(source),
+                None => dependencies.push(PluginDependency {
+                    name: name.to_string(),
+                    sources: vec![source],
+                }),
+            }
+        };
+        for name in &self.metadata.dependencies {
+            add(name, DependencySource::Manifest);
+        }
+        for module in &self.content {
+            if module
+                .autoload_references
+                .iter()
+                .any(|r| r == "maktaba#plugin#Enter")
+            {
+                add(
+                    "maktaba",
+                    DependencySource::MaktabaEnter(module.path.clone()),
+                );
+            }
+            for library in &module.required_libraries {
+                add(
+                    library,
+                    DependencySource::LibraryRequire(module.path.clone()),
+                );
+            }
+        }
+        dependencies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{VimModule, VimParser};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn dependencies_from_manifest_and_maktaba_calls() {
+        let code = r#"
+let [s:plugin, s:enter] = maktaba#plugin#Enter(expand('<sfile>:p'))
+call maktaba#library#Require('glaive')
+call maktaba#library#Require(s:dynamic)
+"#;
+        let module = VimParser::new()
+            .unwrap()
+            .parse_module_str(code)
+            .unwrap()
+            .with_path("plugin/foo.vim");
+        let mut plugin = VimPlugin::new(vec![module, VimModule::default()]);
+        plugin.metadata.name = Some("foo".into());
+        plugin.metadata.dependencies = vec!["maktaba".into(), "foo".into()];
+        assert_eq!(
+            plugin.dependencies(),
+            vec![
+                PluginDependency {
+                    name: "maktaba".into(),
+                    sources: vec![
+                        DependencySource::Manifest,
+                        DependencySource::MaktabaEnter(Some("plugin/foo.vim".into())),
+                    ],
+                },
+                PluginDependency {
+                    name: "glaive".into(),
+                    sources: vec![DependencySource::LibraryRequire(Some(
+                        "plugin/foo.vim".into()
+                    ))],
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use crate::{VimNode, VimNodeKind, VimPlugin};
+use std::collections::{HashMap, HashSet};
+
+/// Differences in the symbols two versions of a plugin define, from [diff_plugins].
+///
+/// Symbols are matched up by kind and name, regardless of which module defines them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PluginDiff {
+    /// Symbols only in the new plugin, in definition order.
+    pub added: Vec<VimNode>,
+    /// Symbols only in the old plugin, in definition order.
+    pub removed: Vec<VimNode>,
+    /// Symbols in both whose signature or docs differ, as (old, new) pairs.
+    pub changed: Vec<(VimNode, VimNode)>,
+}
+
+impl PluginDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// The changed symbols whose definitions differ beyond their docs, like in their args or
+    /// modifiers, as (old, new) pairs.
+    pub fn signature_changes(&self) -> impl Iterator<Item = &(VimNode, VimNode)> {
+        self.changed
+            .iter()
+            .filter(|(old, new)| without_doc(old) != without_doc(new))
+    }
+
+    /// Whether the new version might break users of the old one, because it removed a public
+    /// symbol or changed a public symbol's signature (see [VimNode::is_public]).
+    ///
+    /// Like semver, additions and doc changes aren't breaking.
+    pub fn is_breaking(&self) -> bool {
+        self.removed.iter().any(VimNode::is_public)
+            || self.signature_changes().any(|(old, _)| old.is_public())
+    }
+}
+
+impl VimPlugin {
+    /// Compares this plugin's symbols against a newer version's (see [diff_plugins]).
+    pub fn diff(&self, new: &VimPlugin) -> PluginDiff {
+        diff_plugins(self, new)
+    }
+}
+
+/// Compares the symbols defined by an old and new version of a plugin.
+///
+/// If a plugin defines the same symbol more than once, only the first definition is compared.
+pub fn diff_plugins(old: &VimPlugin, new: &VimPlugin) -> PluginDiff {
+    let old_symbols = first_definitions(old);
+    let new_symbols = first_definitions(new);
+    let old_by_key: HashMap<_, _> = old_symbols.iter().copied().collect();
+    let new_by_key: HashMap<_, _> = new_symbols.iter().copied().collect();
+    let mut diff = PluginDiff::default();
+    for (key, old_node) in &old_symbols {
+        if !new_by_key.contains_key(key) {
+            diff.removed.push((*old_node).clone());
+        }
+    }
+    for (key, new_node) in &new_symbols {
+        match old_by_key.get(key) {
+            None => diff.added.push((*new_node).clone()),
+            Some(old_node) if without_span_or_body(old_node) != without_span_or_body(new_node) => {
+                diff.changed
+                    .push(((*old_node).clone(), (*new_node).clone()))
+            }
+            Some(_) => {}
+        }
+    }
+    diff
+}
+
+/// The first definition of each symbol, keyed by kind and name, in definition order.
+fn first_definitions(plugin: &VimPlugin) -> Vec<((VimNodeKind, &str), &VimNode)> {
+    let mut seen = HashSet::new();
+    plugin
+        .symbols()
+        .map(|symbol| ((symbol.node.kind(), symbol.name()), symbol.node))
+        .filter(|(key, _)| seen.insert(*key))
+        .collect()
+}
+
+/// A copy of the node without its span or function body, so moving a definition within its file
+/// or changing its implementation doesn't count as a change.
+fn without_span_or_body(node: &VimNode) -> VimNode {
+    let mut node = node.clone();
+    *node.span_mut() = None;
+    if let VimNode::Function { body, .. } = &mut node {
+        *body = None;
+    }
+    node
+}
+
+/// A copy of the node with its doc cleared too, for comparing definitions alone.
+fn without_doc(node: &VimNode) -> VimNode {
+    let mut node = without_span_or_body(node);
+    match &mut node {
+        VimNode::StandaloneDocComment { .. } => {}
+        VimNode::Function { doc, .. }
+        | VimNode::Command { doc, .. }
+        | VimNode::Variable { doc, .. }
+        | VimNode::Flag { doc, .. }
+        | VimNode::Augroup { doc, .. }
+        | VimNode::Autocmd { doc, .. }
+        | VimNode::Mapping { doc, .. }
+        | VimNode::Highlight { doc, .. }
+        | VimNode::Sign { doc, .. }
+        | VimNode::SetOption { doc, .. } => *doc = None,
+    }
+    node
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Span, VimModule};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn diff_symbols() {
+        let old = VimPlugin::new(vec![VimModule::new(vec![
+            VimNode::function("foo#Bar").build(),
+            VimNode::function("foo#Baz").args(["x"]).build(),
+            VimNode::command("Foo").build(),
+        ])]);
+        let new = VimPlugin::new(vec![
+            VimModule::new(vec![VimNode::function("foo#Baz").args(["x", "y"]).build()]),
+            VimModule::new(vec![
+                VimNode::command("Foo").build(),
+                VimNode::function("Foo").build(),
+            ]),
+        ]);
+        assert_eq!(
+            diff_plugins(&old, &new),
+            PluginDiff {
+                added: vec![VimNode::function("Foo").build()],
+                removed: vec![VimNode::function("foo#Bar").build()],
+                changed: vec![(
+                    VimNode::function("foo#Baz").args(["x"]).build(),
+                    VimNode::function("foo#Baz").args(["x", "y"]).build(),
+                )],
+            }
+        );
+        assert!(diff_plugins(&new, &new).is_empty());
+        let mut moved = new.clone();
+        for node in moved
+            .content
+            .iter_mut()
+            .flat_map(|module| &mut module.nodes)
+        {
+            *node = node.clone().with_span(Span::default());
+        }
+        assert!(diff_plugins(&moved, &new).is_empty());
+        let reimplemented = VimPlugin::new(vec![
+            VimModule::new(vec![VimNode::function("foo#Baz")
+                .args(["x", "y"])
+                .body("  return a:x\n")
+                .build()]),
+            VimModule::new(vec![
+                VimNode::command("Foo").build(),
+                VimNode::function("Foo").body("").build(),
+            ]),
+        ]);
+        assert!(diff_plugins(&new, &reimplemented).is_empty());
+    }
+
+    #[test]
+    fn diff_signature_changes_and_breakage() {
+        let old = VimPlugin::new(vec![VimModule::new(vec![
+            VimNode::function("foo#Bar").doc("Old docs.").build(),
+            VimNode::function("s:Helper").args(["x"]).build(),
+            VimNode::command("Foo").build(),
+        ])]);
+        let new = VimPlugin::new(vec![VimModule::new(vec![
+            VimNode::function("foo#Bar").doc("New docs.").build(),
+            VimNode::function("s:Helper").build(),
+            VimNode::command("Foo").modifiers(["-bang"]).build(),
+        ])]);
+        let diff = old.diff(&new);
+        assert_eq!(diff.changed.len(), 3);
+        assert_eq!(
+            diff.signature_changes()
+                .map(|(old, _)| old.name().unwrap())
+                .collect::<Vec<_>>(),
+            vec!["s:Helper", "Foo"]
+        );
+        assert!(diff.is_breaking());
+        let script_local_only = VimPlugin::new(vec![VimModule::new(vec![
+            VimNode::function("foo#Bar").doc("Old docs.").build(),
+            VimNode::command("Foo").build(),
+        ])]);
+        assert!(!old.diff(&script_local_only).is_breaking());
+    }
+}
@@ -0,0 +1,463 @@
+//! Renders a parsed plugin's docs for publishing, either into vim help format like the doc/*.txt
+//! files vimdoc generates from the same `""` doc comments ([render_help]) or as a Markdown API
+//! reference ([render_markdown]). [render_doc_report] renders a Markdown report of which symbols
+//! still need docs instead.
+//!
+//! The help has an introduction from the first module doc, any custom `@section`s, and sections
+//! for documented flags, commands, functions and mappings, arranged by the plugin's `@order`
+//! directive if it has one (see [VimPlugin::doc_outline]).
+
+use crate::{VimNode, VimPlugin};
+
+/// The text width vim help files are formatted to.
+const TEXT_WIDTH: usize = 78;
+
+/// Built-in sections the generator fills from nodes, as (id, title) pairs in default order.
+const NODE_SECTIONS: [(&str, &str); 4] = [
+    ("config", "Configuration"),
+    ("commands", "Commands"),
+    ("functions", "Functions"),
+    ("mappings", "Mappings"),
+];
+
+struct HelpSection {
+    id: String,
+    title: String,
+    body: String,
+}
+
+/// Renders the plugin's docs as the contents of a `doc/{plugin_name}.txt` help file.
+///
+/// Only documented nodes are included, and script-local functions are left out. Doc lines with
+/// `@` directives like `@usage` are dropped from the rendered text.
+pub fn render_help(plugin: &VimPlugin, plugin_name: &str) -> String {
+    let outline = plugin.doc_outline();
+    let mut sections = vec![];
+    if let Some(intro) = intro_doc(plugin) {
+        sections.push(HelpSection {
+            id: "intro".into(),
+            title: "Introduction".into(),
+            body: format!("{}\n", strip_directives(intro)),
+        });
+    }
+    for section in &outline.sections {
+        sections.push(HelpSection {
+            id: section.id.clone(),
+            title: section.title.clone(),
+            body: format!("{}\n", strip_directives(&section.doc)),
+        });
+    }
+    for (id, title) in NODE_SECTIONS {
+        let entries: Vec<String> = plugin
+            .content
+            .iter()
+            .flat_map(|module| &module.nodes)
+            .filter(|node| section_id(node) == Some(id))
+            .filter_map(|node| render_node(node, plugin_name))
+            .collect();
+        if !entries.is_empty() {
+            sections.push(HelpSection {
+                id: id.into(),
+                title: title.into(),
+                body: entries.join("\n"),
+            });
+        }
+    }
+    // Stable sort, so sections missing from `@order` keep their default order at the end.
+    sections.sort_by_key(|section| {
+        outline
+            .order
+            .iter()
+            .position(|id| *id == section.id)
+            .unwrap_or(usize::MAX)
+    });
+
+    let mut help = format!("*{plugin_name}.txt*");
+    if let Some(description) = &plugin.metadata.description {
+        help.push('\t');
+        help.push_str(description);
+    }
+    help.push('\n');
+    help.push_str(&section_heading(
+        "Contents",
+        &format!("{plugin_name}-contents"),
+    ));
+    for (i, section) in sections.iter().enumerate() {
+        let link = format!("|{plugin_name}-{}|", section.id);
+        let entry = format!("  {}. {} ", i + 1, section.title);
+        let dots = TEXT_WIDTH
+            .saturating_sub(entry.len() + link.len() + 1)
+            .max(1);
+        help.push_str(&format!("{entry}{} {link}\n", ".".repeat(dots)));
+    }
+    for section in &sections {
+        help.push('\n');
+        help.push_str(&section_heading(
+            &section.title,
+            &format!("{plugin_name}-{}", section.id),
+        ));
+        help.push_str(&section.body);
+    }
+    help.push_str("\n\nvim:tw=78:ts=8:ft=help:norl:\n");
+    help
+}
+
+/// Renders a README-style API reference in Markdown, with a heading per module followed by its
+/// doc and tables of its public functions, commands and flags.
+///
+/// Modules without a doc or any of those are left out.
+pub fn render_markdown(plugin: &VimPlugin, plugin_name: &str) -> String {
+    let mut markdown = format!("# {plugin_name}\n");
+    if let Some(description) = &plugin.metadata.description {
+        markdown.push_str(&format!("\n{description}\n"));
+    }
+    for module in &plugin.content {
+        let nodes = || module.nodes.iter().filter(|node| node.is_public());
+        let tables = [
+            ("Functions", &["Function", "Description"][..]),
+            ("Commands", &["Command", "Description"][..]),
+            ("Flags", &["Flag", "Default", "Description"][..]),
+        ]
+        .map(|(title, columns)| {
+            let rows: Vec<Vec<String>> = nodes()
+                .filter_map(|node| {
+                    let doc = table_cell(node.get_doc().unwrap_or_default());
+                    match (title, node) {
+                        ("Functions", VimNode::Function { .. })
+                        | ("Commands", VimNode::Command { .. }) => {
+                            Some(vec![format!("`{}`", node.signature()?), doc])
+                        }
+                        (
+                            "Flags",
+                            VimNode::Flag {
+                                name,
+                                default_value_token,
+                                ..
+                            },
+                        ) => Some(vec![
+                            format!("`{name}`"),
+                            default_value_token
+                                .as_ref()
+                                .map(|default| format!("`{}`", table_cell(default)))
+                                .unwrap_or_default(),
+                            doc,
+                        ]),
+                        _ => None,
+                    }
+                })
+                .collect();
+            (title, columns, rows)
+        });
+        let module_doc = module.doc.as_deref().map(strip_directives);
+        if module_doc.as_deref().unwrap_or_default().is_empty()
+            && tables.iter().all(|(_, _, rows)| rows.is_empty())
+        {
+            continue;
+        }
+        let heading = module.path.as_deref().map_or_else(
+            || "(unknown module)".into(),
+            |path| path.display().to_string(),
+        );
+        markdown.push_str(&format!("\n## {heading}\n"));
+        if let Some(doc) = module_doc.filter(|doc| !doc.is_empty()) {
+            markdown.push_str(&format!("\n{doc}\n"));
+        }
+        for (title, columns, rows) in tables {
+            if rows.is_empty() {
+                continue;
+            }
+            markdown.push_str(&format!("\n### {title}\n\n| {} |\n", columns.join(" | ")));
+            markdown.push_str(&format!("|{}\n", " --- |".repeat(columns.len())));
+            for row in rows {
+                markdown.push_str(&format!("| {} |\n", row.join(" | ")));
+            }
+        }
+    }
+    markdown
+}
+
+/// Renders a Markdown report of the plugin's public API and internal helpers, split by
+/// [Visibility], with whether each function, command, variable and flag is documented.
+///
+/// Each part starts with how much of it is documented, and lists undocumented items first so
+/// the doc debt is at the top.
+pub fn render_doc_report(plugin: &VimPlugin, plugin_name: &str) -> String {
+    let mut report = format!(
+        "# {plugin_name} doc report
+"
+    );
+    for (title, public) in [("Public API", true), ("Internal helpers", false)] {
+        let mut symbols: Vec<_> = plugin
+            .symbols()
+            .filter(|symbol| {
+                matches!(
+                    symbol.node,
+                    VimNode::Function { .. }
+                        | VimNode::Command { .. }
+                        | VimNode::Variable { .. }
+                        | VimNode::Flag { .. }
+                ) && symbol.node.is_public() == public
+            })
+            .collect();
+        report.push_str(&format!("\n## {title}\n\n"));
+        if symbols.is_empty() {
+            report.push_str("None.\n");
+            continue;
+        }
+        // Stable sort, so each group keeps its module order.
+        symbols.sort_by_key(|symbol| symbol.node.get_doc().is_some());
+        let documented = symbols
+            .iter()
+            .filter(|symbol| symbol.node.get_doc().is_some())
+            .count();
+        report.push_str(&format!(
+            "{documented} of {} documented ({}%).\n\n",
+            symbols.len(),
+            documented * 100 / symbols.len()
+        ));
+        report.push_str("| Symbol | Kind | Module | Docs |\n| --- | --- | --- | --- |\n");
+        for symbol in symbols {
+            let name = symbol
+                .node
+                .signature()
+                .unwrap_or_else(|| symbol.name().to_string());
+            let module = symbol
+                .module
+                .path
+                .as_deref()
+                .map_or_else(String::new, |path| path.display().to_string());
+            let docs = match symbol.node.get_doc() {
+                Some(_) => "documented",
+                None => "**missing**",
+            };
+            report.push_str(&format!(
+                "| `{}` | {:?} | {} | {docs} |\n",
+                table_cell(&name),
+                symbol.node.kind(),
+                table_cell(&module)
+            ));
+        }
+    }
+    report
+}
+
+/// Formats doc text to fit in a Markdown table cell, escaping pipes and joining lines.
+fn table_cell(text: &str) -> String {
+    strip_directives(text)
+        .replace('|', "\\|")
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("<br>")
+}
+
+/// The doc of the first module that has one and doesn't define a custom section.
+fn intro_doc(plugin: &VimPlugin) -> Option<&str> {
+    plugin
+        .content
+        .iter()
+        .filter_map(|module| module.doc.as_deref())
+        .find(|doc| {
+            !doc.lines()
+                .any(|line| line.trim_start().starts_with("@section"))
+        })
+        .or(plugin.metadata.description.as_deref())
+}
+
+/// Which built-in section a node is listed in, if any.
+fn section_id(node: &VimNode) -> Option<&'static str> {
+    node.get_doc()?;
+    match node {
+        VimNode::Flag { .. } => Some("config"),
+        VimNode::Command { .. } => Some("commands"),
+        VimNode::Function { .. } if node.is_public() => Some("functions"),
+        VimNode::Mapping { .. } => Some("mappings"),
+        _ => None,
+    }
+}
+
+/// The heading line for a node in rendered docs, like a function signature or a flag with its
+/// default.
+pub(crate) fn node_title(node: &VimNode) -> Option<String> {
+    match node {
+        VimNode::Flag {
+            name,
+            default_value_token,
+            ..
+        } => match default_value_token {
+            Some(default) => Some(format!("{name} (default: {default})")),
+            None => Some(name.clone()),
+        },
+        VimNode::Mapping { mode, lhs, .. } => Some(format!("{mode}map {lhs}")),
+        node => node.signature(),
+    }
+}
+
+fn render_node(node: &VimNode, plugin_name: &str) -> Option<String> {
+    let title = node_title(node)?;
+    let tag = match node {
+        VimNode::Mapping { lhs, .. } if lhs.starts_with("<Plug>") => Some(lhs.clone()),
+        node => node.help_tag(plugin_name),
+    };
+    let mut rendered = match tag {
+        Some(tag) => right_aligned(&title, &format!("*{tag}*")),
+        None => format!("{title}\n"),
+    };
+    for line in strip_directives(node.get_doc()?).lines() {
+        if !line.is_empty() {
+            rendered.push_str("  ");
+        }
+        rendered.push_str(line);
+        rendered.push('\n');
+    }
+    Some(rendered)
+}
+
+fn section_heading(title: &str, tag: &str) -> String {
+    format!(
+        "{}\n{}\n",
+        "=".repeat(TEXT_WIDTH),
+        right_aligned(&title.to_uppercase(), &format!("*{tag}*")).trim_end()
+    )
+}
+
+/// Formats a line with `left` text and `right` aligned to the text width, putting `right` on
+/// its own line above if they don't fit together.
+fn right_aligned(left: &str, right: &str) -> String {
+    let width = left.chars().count() + right.chars().count() + 1;
+    if width > TEXT_WIDTH {
+        let padding = TEXT_WIDTH.saturating_sub(right.chars().count());
+        format!("{}{right}\n{left}\n", " ".repeat(padding))
+    } else {
+        format!("{left}{}{right}\n", " ".repeat(TEXT_WIDTH - width + 1))
+    }
+}
+
+pub(crate) fn strip_directives(doc: &str) -> String {
+    doc.lines()
+        .filter(|line| !line.trim_start().starts_with('@'))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_matches('\n')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VimModule;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn render_help_sections() {
+        let plugin = VimPlugin::new(vec![VimModule::new(vec![
+            VimNode::command("Frob").doc("Frobs the buffer.").into(),
+            VimNode::Flag {
+                name: "verbose".into(),
+                default_value_token: Some("0".into()),
+                doc: Some("Whether to log.".into()),
+                span: None,
+            },
+            VimNode::function("s:Helper").doc("Internal.").into(),
+            VimNode::Variable {
+                name: "g:loaded_foo".into(),
+                init_value_token: "1".into(),
+                doc: None,
+                span: None,
+            },
+            VimNode::function("foo#Frob")
+                .args(["path", "..."])
+                .doc("Frobs {path}.\n@usage {path} [count]")
+                .into(),
+        ])
+        .with_path("plugin/foo.vim")
+        .with_doc("Frobs things.\n@order intro commands")]);
+        assert_eq!(
+            render_markdown(&plugin, "foo"),
+            r#"# foo
+
+## plugin/foo.vim
+
+Frobs things.
+
+### Functions
+
+| Function | Description |
+| --- | --- |
+| `foo#Frob({path}, [count])` | Frobs {path}. |
+
+### Commands
+
+| Command | Description |
+| --- | --- |
+| `:Frob` | Frobs the buffer. |
+
+### Flags
+
+| Flag | Default | Description |
+| --- | --- | --- |
+| `verbose` | `0` | Whether to log. |
+"#
+        );
+        assert_eq!(
+            render_doc_report(&plugin, "foo"),
+            r#"# foo doc report
+
+## Public API
+
+3 of 4 documented (75%).
+
+| Symbol | Kind | Module | Docs |
+| --- | --- | --- | --- |
+| `g:loaded_foo` | Variable | plugin/foo.vim | **missing** |
+| `:Frob` | Command | plugin/foo.vim | documented |
+| `verbose` | Flag | plugin/foo.vim | documented |
+| `foo#Frob({path}, [count])` | Function | plugin/foo.vim | documented |
+
+## Internal helpers
+
+1 of 1 documented (100%).
+
+| Symbol | Kind | Module | Docs |
+| --- | --- | --- | --- |
+| `s:Helper()` | Function | plugin/foo.vim | documented |
+"#
+        );
+        assert_eq!(
+            render_help(&plugin, "foo"),
+            format!(
+                "*foo.txt*
+{eq}
+CONTENTS                                                        *foo-contents*
+  1. Introduction ................................................ |foo-intro|
+  2. Commands ................................................. |foo-commands|
+  3. Configuration .............................................. |foo-config|
+  4. Functions ............................................... |foo-functions|
+
+{eq}
+INTRODUCTION                                                       *foo-intro*
+Frobs things.
+
+{eq}
+COMMANDS                                                        *foo-commands*
+:Frob                                                                  *:Frob*
+  Frobs the buffer.
+
+{eq}
+CONFIGURATION                                                     *foo-config*
+verbose (default: 0)                                             *foo:verbose*
+  Whether to log.
+
+{eq}
+FUNCTIONS                                                      *foo-functions*
+foo#Frob({{path}}, [count])                                         *foo#Frob()*
+  Frobs {{path}}.
+
+
+vim:tw=78:ts=8:ft=help:norl:
+",
+                eq = "=".repeat(78)
+            )
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use crate::parser::read_source;
+use crate::{ModuleLanguage, Span, VimParser, VimPlugin};
+use std::path::PathBuf;
+
+/// Functions with fewer body tokens than this are too small to be worth consolidating, like
+/// one-line getters.
+const MIN_BODY_TOKENS: usize = 12;
+
+/// Functions within a plugin whose bodies are identical apart from formatting, comments and the
+/// names of arguments and local variables.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DuplicateFunctions {
+    /// Every copy, in module order.
+    pub definitions: Vec<FunctionDefinition>,
+}
+
+/// A function definition located in a plugin's source.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionDefinition {
+    /// Path of the module, relative to the plugin root.
+    pub file: PathBuf,
+    pub name: String,
+    pub range: Span,
+}
+
+impl VimPlugin {
+    /// Finds groups of functions with near-identical bodies, which are likely copy-pasted
+    /// helpers that could be consolidated.
+    ///
+    /// Sources are re-read from the plugin's [VimPlugin::root], so this finds nothing for
+    /// plugins without one.
+    pub fn duplicate_functions(&self) -> crate::Result<Vec<DuplicateFunctions>> {
+        let Some(root) = &self.root else {
+            return Ok(vec![]);
+        };
+        let parser = VimParser::new()?;
+        let mut groups: Vec<(u64, DuplicateFunctions)> = vec![];
+        for module in &self.content {
+            let Some(path) = module
+                .path
+                .as_ref()
+                .filter(|_| module.language != ModuleLanguage::Lua)
+            else {
+                continue;
+            };
+            let code = read_source(&root.join(path))?;
+            for body in parser.function_bodies_str(&code)? {
+                if body.token_count < MIN_BODY_TOKENS {
+                    continue;
+                }
+                let definition = FunctionDefinition {
+                    file: path.clone(),
+                    name: body.name,
+                    range: body.span,
+                };
+                match groups.iter_mut().find(|(f, _)| *f == body.fingerprint) {
+                    Some((_, group)) => group.definitions.push(definition),
+                    None => groups.push((
+                        body.fingerprint,
+                        DuplicateFunctions {
+                            definitions: vec![definition],
+                        },
+                    )),
+                }
+            }
+        }
+        Ok(groups
+            .into_iter()
+            .map(|(_, group)| group)
+            .filter(|group| group.definitions.len() > 1)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn duplicate_functions_ignore_names_and_formatting() {
+        let tmp_dir = tempdir().unwrap();
+        for (path, code) in [
+            (
+                "plugin/foo.vim",
+                "func! foo#Trim(text) abort\n  return substitute(a:text, '^\\s*', '', '')\nendfunc\n\
+                 func! foo#Small() abort\n  return 1\nendfunc\n",
+            ),
+            (
+                "autoload/bar.vim",
+                "\" Copied from foo.\nfunc! bar#Strip(s) abort\n  \" Leading space.\n  return \
+                 substitute(a:s,   '^\\s*', '', '')\nendfunc\n\
+                 func! bar#Other(s) abort\n  return substitute(a:s, '\\s*$', '', '')\nendfunc\n\
+                 func! bar#Small() abort\n  return 1\nendfunc\n",
+            ),
+        ] {
+            fs::create_dir_all(tmp_dir.path().join(path).parent().unwrap()).unwrap();
+            fs::write(tmp_dir.path().join(path), code).unwrap();
+        }
+        let plugin = VimParser::new()
+            .unwrap()
+            .parse_plugin_dir(tmp_dir.path())
+            .unwrap();
+        assert_eq!(
+            plugin
+                .duplicate_functions()
+                .unwrap()
+                .iter()
+                .map(|group| group
+                    .definitions
+                    .iter()
+                    .map(|d| (d.file.to_str().unwrap(), d.name.as_str(), d.range.start))
+                    .collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            vec![vec![
+                ("plugin/foo.vim", "foo#Trim", (0, 0)),
+                ("autoload/bar.vim", "bar#Strip", (1, 0)),
+            ]]
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{SectionKind, VimModule, VimNode, VimPlugin};
+
+/// The sections vim sources when a buffer's filetype is set, in the order `:filetype plugin
+/// indent on` loads them.
+pub(crate) const FILETYPE_SECTIONS: [SectionKind; 3] = [
+    SectionKind::Ftplugin,
+    SectionKind::Indent,
+    SectionKind::Syntax,
+];
+
+/// What a plugin changes in buffers of one filetype, combining its ftplugin/, indent/ and
+/// syntax/ modules for it, including those under after/ (see [VimPlugin::filetype_profiles]).
+#[derive(Clone, Debug, PartialEq)]
+pub struct FiletypeProfile<'a> {
+    pub filetype: String,
+    /// The modules vim sources for the filetype, in load order: each section's modules before
+    /// its after/ modules, and ftplugin/ before indent/ before syntax/.
+    pub modules: Vec<&'a VimModule>,
+    /// [VimNode::SetOption] nodes, in load order.
+    pub options: Vec<&'a VimNode>,
+    /// [VimNode::Mapping] nodes, in load order.
+    pub mappings: Vec<&'a VimNode>,
+    /// [VimNode::Command] nodes, in load order.
+    pub commands: Vec<&'a VimNode>,
+}
+
+impl VimPlugin {
+    /// Builds a profile for each filetype the plugin has ftplugin/, indent/ or syntax/ modules
+    /// for, sorted by filetype.
+    pub fn filetype_profiles(&self) -> Vec<FiletypeProfile<'_>> {
+        let mut filetypes: Vec<String> = self
+            .content
+            .iter()
+            .filter_map(VimModule::filetype)
+            .collect();
+        filetypes.sort();
+        filetypes.dedup();
+        filetypes
+            .into_iter()
+            .filter_map(|filetype| self.filetype_profile(&filetype))
+            .collect()
+    }
+
+    /// Builds the profile of what the plugin changes when a buffer of `filetype` is opened, or
+    /// None if it has no modules for the filetype.
+    pub fn filetype_profile(&self, filetype: &str) -> Option<FiletypeProfile<'_>> {
+        let mut modules: Vec<&VimModule> = self
+            .content
+            .iter()
+            .filter(|module| module.filetype().as_deref() == Some(filetype))
+            .collect();
+        if modules.is_empty() {
+            return None;
+        }
+        // Stable sort, so modules in the same section keep their plugin order.
+        modules.sort_by_key(|module| {
+            module.section().map(|section| {
+                let kind = FILETYPE_SECTIONS.iter().position(|k| *k == section.kind);
+                (kind, section.after)
+            })
+        });
+        let nodes = || modules.iter().flat_map(|module| &module.nodes);
+        Some(FiletypeProfile {
+            filetype: filetype.to_string(),
+            options: nodes()
+                .filter(|node| matches!(node, VimNode::SetOption { .. }))
+                .collect(),
+            mappings: nodes()
+                .filter(|node| matches!(node, VimNode::Mapping { .. }))
+                .collect(),
+            commands: nodes()
+                .filter(|node| matches!(node, VimNode::Command { .. }))
+                .collect(),
+            modules,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VimParser;
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+
+    #[test]
+    fn filetype_profile_combines_sections() {
+        let parser = VimParser::new().unwrap();
+        let module =
+            |path: &str, code: &str| parser.parse_module_str(code).unwrap().with_path(path);
+        let plugin = VimPlugin::new(vec![
+            module("after/ftplugin/python.vim", "setlocal sw=4"),
+            module("indent/python.vim", "setlocal indentexpr=foo#Indent()"),
+            module(
+                "ftplugin/python_foo.vim",
+                "setlocal tw=79\nnnoremap <buffer> <F5> :FooRun<CR>\ncommand! -buffer FooRun echo",
+            ),
+            module("plugin/foo.vim", "set nowrap"),
+            module("syntax/go.vim", "syn keyword goFoo foo"),
+        ]);
+        let profile = plugin.filetype_profile("python").unwrap();
+        assert_eq!(
+            profile
+                .modules
+                .iter()
+                .map(|m| m.path.as_deref().unwrap())
+                .collect::<Vec<_>>(),
+            vec![
+                Path::new("ftplugin/python_foo.vim"),
+                Path::new("after/ftplugin/python.vim"),
+                Path::new("indent/python.vim"),
+            ]
+        );
+        assert_eq!(
+            profile
+                .options
+                .iter()
+                .map(|node| match node {
+                    VimNode::SetOption { item, .. } => item.as_str(),
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<_>>(),
+            vec!["tw=79", "sw=4", "indentexpr=foo#Indent()"]
+        );
+        assert_eq!(profile.mappings.len(), 1);
+        assert_eq!(
+            profile
+                .commands
+                .iter()
+                .filter_map(|node| node.name())
+                .collect::<Vec<_>>(),
+            vec!["FooRun"]
+        );
+        assert_eq!(
+            plugin
+                .filetype_profiles()
+                .iter()
+                .map(|p| p.filetype.as_str())
+                .collect::<Vec<_>>(),
+            vec!["go", "python"]
+        );
+        assert_eq!(plugin.filetype_profile("vim"), None);
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{Span, Symbol, VimPlugin, VimPluginSet};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A help tag generated for more than one documented symbol, which makes `:helptags` fail with
+/// "E154: Duplicate tag".
+#[derive(Clone, Debug, PartialEq)]
+pub struct DuplicateHelpTag<'a> {
+    pub tag: String,
+    /// Every documented symbol producing the tag, in plugin and module order.
+    pub definitions: Vec<HelpTagDefinition<'a>>,
+}
+
+/// A documented symbol producing a help tag, along with the name of the plugin defining it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HelpTagDefinition<'a> {
+    pub plugin: &'a str,
+    pub symbol: Symbol<'a>,
+}
+
+/// Where the symbol a generated help tag documents was defined, for linking from help back to
+/// source (like "view source" links on documentation sites).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HelpTagSource {
+    pub tag: String,
+    /// Path of the module, relative to the plugin root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    /// The definition's location within the file, if the plugin was parsed with spans recorded
+    /// (see [crate::VimParser::set_record_spans]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+}
+
+impl VimPlugin {
+    /// Maps each help tag generated for this plugin's documented symbols back to where the
+    /// symbol was defined, in module order.
+    ///
+    /// Duplicate tags (see [VimPlugin::duplicate_help_tags]) are listed once per definition.
+    pub fn help_tag_sources(&self, plugin_name: &str) -> Vec<HelpTagSource> {
+        self.content
+            .iter()
+            .flat_map(|module| {
+                module
+                    .nodes
+                    .iter()
+                    .filter(|node| node.get_doc().is_some())
+                    .filter_map(|node| {
+                        Some(HelpTagSource {
+                            tag: node.help_tag(plugin_name)?,
+                            file: module.path.clone(),
+                            span: node.span().cloned(),
+                        })
+                    })
+            })
+            .collect()
+    }
+
+    /// Finds help tags produced by more than one documented symbol in this plugin (see
+    /// [crate::VimNode::help_tag]).
+    ///
+    /// Only symbols with doc comments are considered, since undocumented ones don't end up in
+    /// generated help.
+    pub fn duplicate_help_tags<'a>(&'a self, plugin_name: &'a str) -> Vec<DuplicateHelpTag<'a>> {
+        find_duplicates([(plugin_name, self)])
+    }
+}
+
+impl VimPluginSet {
+    /// Finds help tags produced by more than one documented symbol, whether within a single
+    /// plugin or across different plugins in the set.
+    pub fn duplicate_help_tags(&self) -> Vec<DuplicateHelpTag<'_>> {
+        find_duplicates(self.iter())
+    }
+}
+
+fn find_duplicates<'a, I: IntoIterator<Item = (&'a str, &'a VimPlugin)>>(
+    plugins: I,
+) -> Vec<DuplicateHelpTag<'a>> {
+    let mut tags: Vec<DuplicateHelpTag> = vec![];
+    for (plugin_name, plugin) in plugins {
+        for symbol in plugin.symbols() {
+            if symbol.node.get_doc().is_none() {
+                continue;
+            }
+            let Some(tag) = symbol.node.help_tag(plugin_name) else {
+                continue;
+            };
+            let definition = HelpTagDefinition {
+                plugin: plugin_name,
+                symbol,
+            };
+            match tags.iter_mut().find(|t| t.tag == tag) {
+                Some(existing) => existing.definitions.push(definition),
+                None => tags.push(DuplicateHelpTag {
+                    tag,
+                    definitions: vec![definition],
+                }),
+            }
+        }
+    }
+    tags.retain(|t| t.definitions.len() > 1);
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{VimModule, VimNode, VimParser};
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn help_tag_sources_with_spans() {
+        let tmp_dir = tempdir().unwrap();
+        let code =
+            "\"\"\n\" Does foo.\ncommand Foo echo 1\n\nfunction! Undocumented()\nendfunction\n";
+        fs::create_dir_all(tmp_dir.path().join("plugin")).unwrap();
+        fs::write(tmp_dir.path().join("plugin/foo.vim"), code).unwrap();
+        let plugin = VimParser::builder()
+            .record_spans(true)
+            .build()
+            .unwrap()
+            .parse_plugin_dir(tmp_dir.path())
+            .unwrap();
+        let sources = plugin.help_tag_sources("foo");
+        assert_eq!(
+            sources
+                .iter()
+                .map(|s| (s.tag.as_str(), s.file.as_ref().unwrap().to_str().unwrap()))
+                .collect::<Vec<_>>(),
+            vec![(":Foo", "plugin/foo.vim")]
+        );
+        assert_eq!(
+            &code[sources[0].span.as_ref().unwrap().byte_range.clone()],
+            "command Foo echo 1"
+        );
+    }
+
+    #[test]
+    fn duplicate_help_tags_within_and_across_plugins() {
+        let foo = VimPlugin::new(vec![
+            VimModule::new(vec![
+                VimNode::command("Foo").doc("Does foo.").into(),
+                VimNode::function("Shared").doc("Foo's version.").into(),
+            ])
+            .with_path("plugin/foo.vim"),
+            VimModule::new(vec![
+                VimNode::command("Foo").doc("Does foo again.").into(),
+                // Undocumented, so no tag is generated for it.
+                VimNode::function("Shared").into(),
+            ])
+            .with_path("plugin/foo2.vim"),
+        ]);
+        let bar = VimPlugin::new(vec![VimModule::new(vec![VimNode::function("Shared")
+            .doc("Bar's version.")
+            .into()])
+        .with_path("plugin/bar.vim")]);
+        assert_eq!(
+            foo.duplicate_help_tags("foo")
+                .iter()
+                .map(|d| d.tag.as_str())
+                .collect::<Vec<_>>(),
+            vec![":Foo"]
+        );
+        let plugin_set: VimPluginSet = [("foo", foo), ("bar", bar)].into_iter().collect();
+        assert_eq!(
+            plugin_set
+                .duplicate_help_tags()
+                .iter()
+                .map(|d| (
+                    d.tag.as_str(),
+                    d.definitions.iter().map(|def| def.plugin).collect()
+                ))
+                .collect::<Vec<(_, Vec<_>)>>(),
+            vec![
+                (":Foo", vec!["foo", "foo"]),
+                ("Shared()", vec!["foo", "bar"])
+            ]
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use crate::{SectionKind, VimModule, VimNode, VimPlugin};
+use std::collections::BTreeMap;
+
+/// Highlight groups vim defines by default, which colorschemes are expected to cover (see
+/// `:help highlight-default` and `:help group-name`).
+pub const STANDARD_HIGHLIGHT_GROUPS: [&str; 80] = [
+    // UI groups.
+    "ColorColumn",
+    "Conceal",
+    "Cursor",
+    "CursorColumn",
+    "CursorLine",
+    "CursorLineNr",
+    "DiffAdd",
+    "DiffChange",
+    "DiffDelete",
+    "DiffText",
+    "Directory",
+    "EndOfBuffer",
+    "ErrorMsg",
+    "FoldColumn",
+    "Folded",
+    "IncSearch",
+    "LineNr",
+    "MatchParen",
+    "ModeMsg",
+    "MoreMsg",
+    "NonText",
+    "Normal",
+    "Pmenu",
+    "PmenuSbar",
+    "PmenuSel",
+    "PmenuThumb",
+    "Question",
+    "QuickFixLine",
+    "Search",
+    "SignColumn",
+    "SpecialKey",
+    "SpellBad",
+    "SpellCap",
+    "SpellLocal",
+    "SpellRare",
+    "StatusLine",
+    "StatusLineNC",
+    "TabLine",
+    "TabLineFill",
+    "TabLineSel",
+    "Title",
+    "VertSplit",
+    "Visual",
+    "WarningMsg",
+    "WildMenu",
+    // Syntax groups.
+    "Comment",
+    "Constant",
+    "String",
+    "Character",
+    "Number",
+    "Boolean",
+    "Float",
+    "Identifier",
+    "Function",
+    "Statement",
+    "Conditional",
+    "Repeat",
+    "Label",
+    "Operator",
+    "Keyword",
+    "Exception",
+    "PreProc",
+    "Include",
+    "Define",
+    "Macro",
+    "PreCondit",
+    "Type",
+    "StorageClass",
+    "Structure",
+    "Typedef",
+    "Special",
+    "SpecialChar",
+    "Tag",
+    "Delimiter",
+    "SpecialComment",
+    "Debug",
+    "Underlined",
+    "Ignore",
+    "Error",
+    "Todo",
+];
+
+/// The links between highlight groups, as left by running a plugin's `:highlight` commands in
+/// order.
+///
+/// Group names are case-insensitive in vim, so lookups ignore case and groups are keyed by the
+/// name as first written.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HighlightLinks {
+    /// Each group defined, with the group it links to if any.
+    groups: BTreeMap<String, Option<String>>,
+}
+
+impl HighlightLinks {
+    /// Builds the link graph from highlight nodes in definition order.
+    ///
+    /// Like vim, a later link replaces an earlier one, setting attributes on a group removes its
+    /// link, and `:hi default` definitions only apply to groups not defined yet. Linking to
+    /// `NONE` removes the link.
+    pub fn from_nodes<'a, I: IntoIterator<Item = &'a VimNode>>(nodes: I) -> Self {
+        let mut links = Self::default();
+        for node in nodes {
+            let VimNode::Highlight {
+                name,
+                default,
+                link,
+                attributes,
+                ..
+            } = node
+            else {
+                continue;
+            };
+            if link.is_none() && attributes.is_empty() {
+                continue;
+            }
+            let key = links.key(name).unwrap_or(name).to_string();
+            if *default && links.groups.contains_key(&key) {
+                continue;
+            }
+            let link = match link {
+                Some(link) if !link.eq_ignore_ascii_case("NONE") => Some(link.clone()),
+                _ => None,
+            };
+            links.groups.insert(key, link);
+        }
+        links
+    }
+
+    /// Whether the group is defined, either with attributes or by linking to another group.
+    pub fn is_defined(&self, group: &str) -> bool {
+        self.key(group).is_some()
+    }
+
+    /// The group `group` links to directly, if any.
+    pub fn link(&self, group: &str) -> Option<&str> {
+        self.groups.get(self.key(group)?)?.as_deref()
+    }
+
+    /// Follows links from `group` and returns the chain of groups visited, starting with `group`
+    /// itself. Stops before revisiting a group if the links form a cycle.
+    pub fn resolve<'s>(&'s self, group: &'s str) -> Vec<&'s str> {
+        let mut chain = vec![group];
+        let mut current = group;
+        while let Some(next) = self.link(current) {
+            if chain.iter().any(|g| g.eq_ignore_ascii_case(next)) {
+                break;
+            }
+            chain.push(next);
+            current = next;
+        }
+        chain
+    }
+
+    /// Groups that link directly to `group`, sorted by name.
+    pub fn linked_from(&self, group: &str) -> Vec<&str> {
+        self.edges()
+            .filter(|(_, to)| to.eq_ignore_ascii_case(group))
+            .map(|(from, _)| from)
+            .collect()
+    }
+
+    /// Every link as a `(from, to)` pair, sorted by the group linking from.
+    pub fn edges(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.groups
+            .iter()
+            .filter_map(|(from, to)| Some((from.as_str(), to.as_deref()?)))
+    }
+
+    fn key(&self, group: &str) -> Option<&str> {
+        self.groups
+            .keys()
+            .find(|key| key.eq_ignore_ascii_case(group))
+            .map(String::as_str)
+    }
+}
+
+/// Which [STANDARD_HIGHLIGHT_GROUPS] a colorscheme defines (see
+/// [VimPlugin::colorscheme_coverage]).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorschemeCoverage<'a> {
+    /// The colorscheme's name, from its file name like `colors/<name>.vim`.
+    pub name: String,
+    pub module: &'a VimModule,
+    /// Standard groups it defines, in [STANDARD_HIGHLIGHT_GROUPS] order.
+    pub defined: Vec<&'static str>,
+    /// Standard groups it leaves to vim's defaults.
+    pub missing: Vec<&'static str>,
+}
+
+impl VimPlugin {
+    /// Builds the link graph between highlight groups defined anywhere in the plugin, in module
+    /// order.
+    pub fn highlight_links(&self) -> HighlightLinks {
+        HighlightLinks::from_nodes(self.content.iter().flat_map(|module| &module.nodes))
+    }
+
+    /// Reports which standard highlight groups each colorscheme under colors/ defines, in module
+    /// order.
+    pub fn colorscheme_coverage(&self) -> Vec<ColorschemeCoverage<'_>> {
+        self.content
+            .iter()
+            .filter_map(|module| {
+                let path = module.path.as_deref()?;
+                if module.section()?.kind != SectionKind::Colors {
+                    return None;
+                }
+                let name = path.file_stem()?.to_string_lossy().into_owned();
+                let links = HighlightLinks::from_nodes(&module.nodes);
+                let (defined, missing) = STANDARD_HIGHLIGHT_GROUPS
+                    .into_iter()
+                    .partition(|group| links.is_defined(group));
+                Some(ColorschemeCoverage {
+                    name,
+                    module,
+                    defined,
+                    missing,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VimParser;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn highlight_links_follow_vim_semantics() {
+        let code = r#"
+hi link fooError Error
+hi link fooWarning fooError
+hi default link fooWarning Todo
+hi link fooLoop fooLoop2
+hi link fooLoop2 fooLoop
+hi link fooUnlinked Comment
+hi fooUnlinked guifg=#ff0000
+"#;
+        let module = VimParser::new().unwrap().parse_module_str(code).unwrap();
+        let links = HighlightLinks::from_nodes(&module.nodes);
+        assert_eq!(
+            links.resolve("FOOWARNING"),
+            vec!["FOOWARNING", "fooError", "Error"]
+        );
+        assert_eq!(links.resolve("fooLoop"), vec!["fooLoop", "fooLoop2"]);
+        assert_eq!(links.link("fooUnlinked"), None);
+        assert!(links.is_defined("fooUnlinked"));
+        assert_eq!(links.linked_from("fooerror"), vec!["fooWarning"]);
+    }
+
+    #[test]
+    fn colorscheme_coverage_standard_groups() {
+        let code = r#"
+hi Normal guifg=#ffffff guibg=#000000
+hi Comment ctermfg=8
+hi link String Constant
+"#;
+        let module = VimParser::new().unwrap().parse_module_str(code).unwrap();
+        let plugin = VimPlugin::new(vec![
+            module.with_path("colors/mono.vim"),
+            VimModule::default().with_path("plugin/mono.vim"),
+        ]);
+        let coverage = plugin.colorscheme_coverage();
+        assert_eq!(
+            coverage
+                .iter()
+                .map(|c| (c.name.as_str(), c.defined.clone(), c.missing.len()))
+                .collect::<Vec<_>>(),
+            vec![(
+                "mono",
+                vec!["Normal", "Comment", "String"],
+                STANDARD_HIGHLIGHT_GROUPS.len() - 3
+            )]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use crate::docgen::{node_title, strip_directives};
+use crate::{Span, VimNode, VimPlugin};
+use std::path::Path;
+
+/// What to show for a definition the cursor is over in an editor (see
+/// [VimPlugin::hover_info]).
+#[derive(Clone, Debug, PartialEq)]
+pub struct HoverInfo<'a> {
+    pub node: &'a VimNode,
+    /// Where the node is defined in its file.
+    pub span: &'a Span,
+    /// A one-line summary of the node, like `foo#Bar({path}, [count])`, `:Foo` or
+    /// `g:foo_enabled (default: 1)`.
+    pub signature: String,
+    /// The node's doc comment without `@` directives, if it has one.
+    pub doc: Option<String>,
+}
+
+impl HoverInfo<'_> {
+    /// Renders the hover as markdown, with the signature in a vim code block above the doc.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("```vim\n{}\n```\n", self.signature);
+        if let Some(doc) = &self.doc {
+            markdown.push('\n');
+            markdown.push_str(doc);
+            markdown.push('\n');
+        }
+        markdown
+    }
+}
+
+impl VimPlugin {
+    /// Finds the node defined at a (row, column) position in the module at `file` (relative to
+    /// the plugin root), along with its signature and docs to show on hover.
+    ///
+    /// Positions are 0-based with columns in bytes, like [Span]. The innermost node containing
+    /// the position wins. Only finds anything if the plugin was parsed with spans recorded (see
+    /// [crate::VimParser::set_record_spans]).
+    pub fn hover_info<P: AsRef<Path>>(
+        &self,
+        file: P,
+        position: (usize, usize),
+    ) -> Option<HoverInfo<'_>> {
+        let module = self
+            .content
+            .iter()
+            .find(|module| module.path.as_deref() == Some(file.as_ref()))?;
+        let (node, span) = module
+            .nodes
+            .iter()
+            .filter_map(|node| Some((node, node.span()?)))
+            .filter(|(_, span)| span.contains(position))
+            .min_by_key(|(_, span)| span.byte_range.len())?;
+        let signature = node_title(node).or_else(|| node.name().map(str::to_string))?;
+        Some(HoverInfo {
+            node,
+            span,
+            signature,
+            doc: node.get_doc().map(strip_directives),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VimParser;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn hover_info_at_positions() {
+        let code = r#"
+""
+" Runs foo on {path}.
+" @usage {path} [count]
+function! foo#Run(path, ...) abort
+  return a:path
+endfunction
+
+command Foo call foo#Run('.')
+"#;
+        let module = VimParser::builder()
+            .record_spans(true)
+            .build()
+            .unwrap()
+            .parse_module_str(code)
+            .unwrap()
+            .with_path("autoload/foo.vim");
+        let plugin = VimPlugin::new(vec![module]);
+        let hover = plugin.hover_info("autoload/foo.vim", (5, 4)).unwrap();
+        assert_eq!(hover.signature, "foo#Run({path}, [count])");
+        assert_eq!(
+            hover.to_markdown(),
+            "```vim\nfoo#Run({path}, [count])\n```\n\nRuns foo on {path}.\n"
+        );
+        assert_eq!(
+            plugin
+                .hover_info("autoload/foo.vim", (8, 9))
+                .map(|hover| hover.signature),
+            Some(":Foo".into())
+        );
+        assert_eq!(plugin.hover_info("autoload/foo.vim", (7, 0)), None);
+        assert_eq!(plugin.hover_info("plugin/foo.vim", (5, 4)), None);
+    }
+}
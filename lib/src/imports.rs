@@ -0,0 +1,180 @@
+use crate::{Vim9Import, VimModule, VimPlugin};
+use std::path::{Component, Path, PathBuf};
+
+/// A Vim9 script's import of another script, along with the module in the plugin it resolves to
+/// (see [VimPlugin::vim9_import_graph]).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vim9ImportEdge<'a> {
+    /// The importing module.
+    pub module: &'a VimModule,
+    pub import: &'a Vim9Import,
+    /// The imported module, if the plugin has it.
+    pub target: Option<&'a VimModule>,
+}
+
+/// An item exported from a Vim9 script that no importer in the plugin uses (see
+/// [VimPlugin::unused_vim9_exports]).
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnusedVim9Export<'a> {
+    pub module: &'a VimModule,
+    pub name: &'a str,
+}
+
+impl VimPlugin {
+    /// Lists every Vim9 `import` in the plugin with the module it resolves to, in module order.
+    ///
+    /// Like vim, paths starting with `./` or `../` are relative to the importing script, and
+    /// others are looked up under import/, or autoload/ for `import autoload`. Absolute paths
+    /// and scripts from other plugins don't resolve.
+    pub fn vim9_import_graph(&self) -> Vec<Vim9ImportEdge<'_>> {
+        self.content
+            .iter()
+            .flat_map(|module| {
+                module
+                    .vim9_imports
+                    .iter()
+                    .map(move |import| Vim9ImportEdge {
+                        module,
+                        import,
+                        target: self.resolve_vim9_import(module, import),
+                    })
+            })
+            .collect()
+    }
+
+    /// Finds exports of scripts the plugin imports that none of their importers use, in module
+    /// order.
+    ///
+    /// Scripts nothing in the plugin imports are skipped, since their exports are presumably
+    /// meant for other plugins.
+    pub fn unused_vim9_exports(&self) -> Vec<UnusedVim9Export<'_>> {
+        let graph = self.vim9_import_graph();
+        self.content
+            .iter()
+            .filter_map(|module| {
+                let importers: Vec<&Vim9ImportEdge> = graph
+                    .iter()
+                    .filter(|edge| {
+                        edge.target
+                            .is_some_and(|target| std::ptr::eq(target, module))
+                    })
+                    .collect();
+                (!importers.is_empty()).then_some((module, importers))
+            })
+            .flat_map(|(module, importers)| {
+                module
+                    .vim9_exports
+                    .iter()
+                    .filter(move |name| {
+                        !importers
+                            .iter()
+                            .any(|edge| edge.import.used_items.contains(name))
+                    })
+                    .map(move |name| UnusedVim9Export { module, name })
+            })
+            .collect()
+    }
+
+    fn resolve_vim9_import(&self, module: &VimModule, import: &Vim9Import) -> Option<&VimModule> {
+        let path = Path::new(&import.path);
+        let resolved = if import.path.starts_with("./") || import.path.starts_with("../") {
+            module.path.as_deref()?.parent()?.join(path)
+        } else if path.is_relative() {
+            Path::new(if import.autoload {
+                "autoload"
+            } else {
+                "import"
+            })
+            .join(path)
+        } else {
+            return None;
+        };
+        let resolved = normalize(&resolved);
+        self.content
+            .iter()
+            .find(|m| m.path.as_deref().is_some_and(|p| normalize(p) == resolved))
+    }
+}
+
+/// Lexically resolves `.` and `..` components, so `plugin/../import/x.vim` matches
+/// `import/x.vim`.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VimParser;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn vim9_import_graph_and_unused_exports() {
+        let parser = VimParser::new().unwrap();
+        let module =
+            |path: &str, code: &str| parser.parse_module_str(code).unwrap().with_path(path);
+        let plugin = VimPlugin::new(vec![
+            module(
+                "plugin/foo.vim",
+                r#"vim9script
+import autoload "foo.vim"
+import "foo/util.vim" as u
+import "../import/foo/util.vim"
+import "other.vim"
+command Foo foo.Run(u.Trim(util.sep))
+"#,
+            ),
+            module(
+                "autoload/foo.vim",
+                "vim9script\nexport def Run(s: string)\nenddef\nexport def Unused()\nenddef\n",
+            ),
+            module(
+                "import/foo/util.vim",
+                "vim9script\nexport const sep = ','\nexport def Trim(s: string): string\n  return s\nenddef\nexport def Split()\nenddef\n",
+            ),
+            module("import/standalone.vim", "vim9script\nexport def Lonely()\nenddef\n"),
+        ]);
+        assert_eq!(
+            plugin
+                .vim9_import_graph()
+                .iter()
+                .map(|edge| (
+                    edge.import.path.as_str(),
+                    edge.target
+                        .and_then(|t| t.path.as_deref())
+                        .map(|p| p.to_str().unwrap())
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("foo.vim", Some("autoload/foo.vim")),
+                ("foo/util.vim", Some("import/foo/util.vim")),
+                ("../import/foo/util.vim", Some("import/foo/util.vim")),
+                ("other.vim", None),
+            ]
+        );
+        assert_eq!(
+            plugin
+                .unused_vim9_exports()
+                .iter()
+                .map(|export| (
+                    export.module.path.as_deref().unwrap().to_str().unwrap(),
+                    export.name
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("autoload/foo.vim", "Unused"),
+                ("import/foo/util.vim", "Split"),
+            ]
+        );
+    }
+}
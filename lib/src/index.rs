@@ -0,0 +1,159 @@
+//! A compact, serializable index of symbols and docs from one or more plugins.
+//!
+//! Indexes can be built once from parsed plugins, written to disk, and loaded later for fast
+//! lookups without re-parsing any vimscript.
+
+use crate::{Span, VimNodeKind, VimPlugin};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+/// A searchable index of the symbols defined across one or more plugins.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PluginIndex {
+    pub entries: Vec<IndexEntry>,
+}
+
+/// A single symbol recorded in a [PluginIndex].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IndexEntry {
+    /// Name of the plugin the symbol came from.
+    pub plugin: String,
+    /// Path of the module defining the symbol, relative to the plugin root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module: Option<PathBuf>,
+    pub kind: VimNodeKind,
+    pub name: String,
+    /// The vim help tag for the symbol (see [crate::VimNode::help_tag]).
+    pub tag: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+    /// Where the symbol is defined in its module, if the plugin was parsed with spans recorded
+    /// (see [crate::VimParser::set_record_spans]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+    /// Index of the node within its module's nodes.
+    pub node_index: usize,
+}
+
+impl PluginIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds an index from several plugins, each paired with its name.
+    pub fn from_plugins<'a, I: IntoIterator<Item = (&'a str, &'a VimPlugin)>>(plugins: I) -> Self {
+        let mut index = Self::new();
+        for (name, plugin) in plugins {
+            index.add_plugin(name, plugin);
+        }
+        index
+    }
+
+    /// Adds all symbols from the given plugin to the index.
+    pub fn add_plugin(&mut self, plugin_name: &str, plugin: &VimPlugin) {
+        for module in &plugin.content {
+            for (node_index, node) in module.nodes.iter().enumerate() {
+                let (Some(name), Some(tag)) = (node.name(), node.help_tag(plugin_name)) else {
+                    continue;
+                };
+                self.entries.push(IndexEntry {
+                    plugin: plugin_name.to_string(),
+                    module: module.path.clone(),
+                    kind: node.kind(),
+                    name: name.to_string(),
+                    tag,
+                    doc: node.get_doc().map(str::to_string),
+                    span: node.span().cloned(),
+                    node_index,
+                });
+            }
+        }
+    }
+
+    /// Finds all entries whose symbol name or help tag is exactly `name`.
+    pub fn lookup<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a IndexEntry> + 'a {
+        self.entries
+            .iter()
+            .filter(move |e| e.name == name || e.tag == name)
+    }
+
+    /// Serializes the index as compact JSON.
+    pub fn write_to<W: Write>(&self, writer: W) -> crate::Result<()> {
+        serde_json::to_writer(writer, self)?;
+        Ok(())
+    }
+
+    /// Loads an index previously written with [PluginIndex::write_to].
+    pub fn read_from<R: Read>(reader: R) -> crate::Result<Self> {
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{VimModule, VimNode};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn index_round_trip() {
+        let plugin = VimPlugin::new(vec![VimModule::new(vec![
+            VimNode::doc_comment("Ignored"),
+            VimNode::function("foo#Bar")
+                .doc("Does bar.")
+                .build()
+                .with_span(Span {
+                    start: (1, 0),
+                    end: (2, 11),
+                    byte_range: 13..48,
+                }),
+            VimNode::flag("verbose").into(),
+        ])
+        .with_path("autoload/foo.vim")]);
+        let index = PluginIndex::from_plugins([("foo", &plugin)]);
+        let mut buf = vec![];
+        index.write_to(&mut buf).unwrap();
+        let loaded = PluginIndex::read_from(buf.as_slice()).unwrap();
+        assert_eq!(loaded, index);
+        assert_eq!(
+            loaded.entries,
+            vec![
+                IndexEntry {
+                    plugin: "foo".into(),
+                    module: Some("autoload/foo.vim".into()),
+                    kind: VimNodeKind::Function,
+                    name: "foo#Bar".into(),
+                    tag: "foo#Bar()".into(),
+                    doc: Some("Does bar.".into()),
+                    span: Some(Span {
+                        start: (1, 0),
+                        end: (2, 11),
+                        byte_range: 13..48,
+                    }),
+                    node_index: 1,
+                },
+                IndexEntry {
+                    plugin: "foo".into(),
+                    module: Some("autoload/foo.vim".into()),
+                    kind: VimNodeKind::Flag,
+                    name: "verbose".into(),
+                    tag: "foo:verbose".into(),
+                    doc: None,
+                    span: None,
+                    node_index: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn index_lookup_by_tag() {
+        let plugin = VimPlugin::new(vec![VimModule::new(vec![VimNode::command("Foo").into()])]);
+        let index = PluginIndex::from_plugins([("foo", &plugin)]);
+        assert_eq!(
+            index.lookup(":Foo").map(|e| &e.name).collect::<Vec<_>>(),
+            vec!["Foo"]
+        );
+    }
+}
@@ -4,22 +4,153 @@
 //!
 //! The main use case is to instantiate a [VimParser], configure it, and point
 //! it to a plugin dir or file to parse.
+//!
+//! The [tree_sitter] crate is re-exported for consumers of raw syntax trees (see
+//! [VimParser::parse_module_with_tree]), so they use the same version as this crate.
+
+pub mod analysis;
+pub mod docgen;
+pub mod index;
+pub mod lint;
 
+mod autoload;
+mod call_graph;
+mod completion;
+mod corpus;
+mod coverage;
 mod data;
+mod dependencies;
+mod diff;
+mod duplicates;
+mod filetypes;
+mod helptags;
+mod highlights;
+mod hover;
+mod imports;
+mod naming;
 mod parser;
+mod plugin_set;
+mod redact;
+mod rename;
+mod report;
+mod runtime;
+mod search;
+mod symbols;
 
-pub use crate::data::{VimModule, VimNode, VimPlugin};
-pub use crate::parser::VimParser;
+pub use crate::autoload::{AutoloadCaller, AutoloadNamespaceUse};
+pub use crate::call_graph::CallEdge;
+pub use crate::completion::{CompletionCandidate, CompletionKind};
+pub use crate::corpus::CorpusStats;
+pub use crate::coverage::ModuleDocCoverage;
+pub use crate::data::{
+    AugroupBuilder, AutocmdBuilder, CommandAttributes, CommandBuilder, ConfigAutocmd,
+    ConfigMapping, ConfigSetting, DocOutline, DocSection, FlagBuilder, FunctionBuilder,
+    FunctionCall, FunctionModifiers, HelpEntry, HelpSection, HighlightBuilder, MapArguments,
+    MappingBuilder, Modeline, ModuleError, ModuleHeader, ModuleLanguage, ModuleProfile,
+    ModuleStats, ParseInfo, ParseProfile, PluginDeclaration, PluginManager, RemotePlugin,
+    RemoteRegistration, SetOptionBuilder, SignBuilder, Span, TextEdit, TreeSitterQuery,
+    VariableBuilder, VendoredPlugin, Vim9Import, VimConfig, VimHelpDoc, VimModule, VimNode,
+    VimNodeKind, VimNodeTree, VimPlugin, VimPluginMetadata, Visibility, Warning, WarningKind,
+};
+pub use crate::dependencies::{DependencySource, PluginDependency};
+pub use crate::diff::{diff_plugins, PluginDiff};
+pub use crate::duplicates::{DuplicateFunctions, FunctionDefinition};
+pub use crate::filetypes::FiletypeProfile;
+pub use crate::helptags::{DuplicateHelpTag, HelpTagDefinition, HelpTagSource};
+pub use crate::highlights::{ColorschemeCoverage, HighlightLinks, STANDARD_HIGHLIGHT_GROUPS};
+pub use crate::hover::HoverInfo;
+pub use crate::imports::{UnusedVim9Export, Vim9ImportEdge};
+pub use crate::parser::{
+    classify_runtime_path, classify_runtime_path_with, detect_dialect, detect_plugin_roots,
+    detect_vendored_plugins, Dialect, DuplicateModules, EncodingFallback, ErrorPolicy,
+    FunctionBodyCapture, IncrementalModule, ParseEvent, PluginFile, PluginModules, Section,
+    SectionKind, VendoredPlugins, VimParser, VimParserBuilder,
+};
+pub use crate::plugin_set::VimPluginSet;
+pub use crate::redact::{ValueRedaction, REDACTED};
+pub use crate::rename::rename_symbol;
+pub use crate::report::{
+    ConflictsReport, CoverageReport, HelpTagConflict, ModuleCoverageReport, Report, ReportSection,
+    StatsReport, REPORT_SCHEMA_VERSION,
+};
+pub use crate::runtime::{RuntimeModule, RuntimeSymbol, RuntimeView, ShadowedModule};
+pub use crate::search::{DocMatch, FuzzyMatch};
+pub use crate::symbols::{SidCommandHandler, Symbol};
+pub use tree_sitter;
 
 use core::fmt;
+use std::path::{Path, PathBuf};
 use std::{error, io};
 
+/// Errors from parsing and analyzing plugins. New variants can be added without a breaking
+/// release.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     UnknownError(Box<dyn error::Error>),
     GrammarError(tree_sitter::LanguageError),
     ParsingFailure,
     IOError(io::Error),
+    InvalidPattern(regex::Error),
+    SerializationError(serde_json::Error),
+    /// A module path that's the same file as an earlier one, with
+    /// [DuplicateModules::Error](crate::DuplicateModules::Error).
+    DuplicateModule {
+        path: PathBuf,
+        duplicate_of: PathBuf,
+    },
+    /// A [TextEdit](crate::TextEdit) whose range isn't within the code being edited or splits a
+    /// character.
+    InvalidEdit {
+        byte_range: std::ops::Range<usize>,
+        code_len: usize,
+    },
+    /// Another error that happened while reading or parsing a specific file, like one module of
+    /// a plugin dir.
+    InFile {
+        path: PathBuf,
+        /// The (row, column) in the file where the problem is, when it can be pinned down.
+        position: Option<(usize, usize)>,
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// The file the error happened in, if known.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::InFile { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// The (row, column) in [Error::path] where the error happened, if known.
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            Self::InFile { position, .. } => *position,
+            _ => None,
+        }
+    }
+
+    /// The underlying error, without the file context from [Error::InFile].
+    pub fn inner(&self) -> &Error {
+        match self {
+            Self::InFile { source, .. } => source.inner(),
+            err => err,
+        }
+    }
+
+    /// Attaches the path of the file being processed, unless the error already has one.
+    pub(crate) fn in_file<P: Into<PathBuf>>(self, path: P) -> Self {
+        match self {
+            Self::InFile { .. } => self,
+            err => Self::InFile {
+                path: path.into(),
+                position: None,
+                source: Box::new(err),
+            },
+        }
+    }
 }
 
 impl From<tree_sitter::LanguageError> for Error {
@@ -30,10 +161,15 @@ impl From<tree_sitter::LanguageError> for Error {
 
 impl From<walkdir::Error> for Error {
     fn from(err: walkdir::Error) -> Self {
-        if err.io_error().is_some() {
+        let path = err.path().map(Path::to_owned);
+        let err = if err.io_error().is_some() {
             err.into_io_error().unwrap().into()
         } else {
             Self::UnknownError(err.into())
+        };
+        match path {
+            Some(path) => err.in_file(path),
+            None => err,
         }
     }
 }
@@ -44,6 +180,18 @@ impl From<io::Error> for Error {
     }
 }
 
+impl From<regex::Error> for Error {
+    fn from(err: regex::Error) -> Self {
+        Self::InvalidPattern(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Self::SerializationError(err)
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -53,10 +201,44 @@ impl fmt::Display for Error {
                 write!(f, "General failure from tree-sitter while parsing syntax")
             }
             Self::IOError(err) => write!(f, "I/O error: {err}"),
+            Self::InvalidPattern(err) => write!(f, "Invalid regex pattern: {err}"),
+            Self::SerializationError(err) => write!(f, "Serialization error: {err}"),
+            Self::DuplicateModule { path, duplicate_of } => write!(
+                f,
+                "Module {} is the same file as {}",
+                path.display(),
+                duplicate_of.display()
+            ),
+            Self::InvalidEdit {
+                byte_range,
+                code_len,
+            } => write!(
+                f,
+                "Edit range {}..{} isn't valid for code of length {code_len}",
+                byte_range.start, byte_range.end
+            ),
+            Self::InFile {
+                path,
+                position,
+                source,
+            } => {
+                write!(f, "{}", path.display())?;
+                if let Some((row, column)) = position {
+                    write!(f, ":{}:{}", row + 1, column + 1)?;
+                }
+                write!(f, ": {source}")
+            }
         }
     }
 }
 
-impl error::Error for Error {}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::InFile { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
 
 type Result<T> = core::result::Result<T, Error>;
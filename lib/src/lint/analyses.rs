@@ -0,0 +1,271 @@
+//! Adapters from the results of whole-plugin analyses to [Finding]s, so frontends can render and
+//! baseline them like lint findings.
+
+use super::conventions::MISSING_DOC;
+use super::{Finding, Rule, Severity};
+use crate::{
+    AutoloadNamespaceUse, ColorschemeCoverage, DuplicateHelpTag, Symbol, UnusedVim9Export,
+};
+use std::path::Path;
+
+pub const UNUSED_SCRIPT_LOCAL_FUNCTION: Rule = Rule {
+    id: "unused-script-local-function",
+    default_severity: Severity::Warning,
+};
+pub const DUPLICATE_HELP_TAG: Rule = Rule {
+    id: "duplicate-help-tag",
+    default_severity: Severity::Error,
+};
+pub const UNRESOLVED_AUTOLOAD_NAMESPACE: Rule = Rule {
+    id: "unresolved-autoload-namespace",
+    default_severity: Severity::Warning,
+};
+pub const INCOMPLETE_COLORSCHEME: Rule = Rule {
+    id: "incomplete-colorscheme",
+    default_severity: Severity::Info,
+};
+pub const UNUSED_VIM9_EXPORT: Rule = Rule {
+    id: "unused-vim9-export",
+    default_severity: Severity::Warning,
+};
+
+impl Finding {
+    fn from_rule(rule: &Rule, message: String) -> Self {
+        Self {
+            rule_id: rule.id.to_string(),
+            severity: rule.default_severity,
+            message,
+            file: None,
+            range: None,
+            suggestion: None,
+            edits: vec![],
+        }
+    }
+
+    /// A finding for a function from [crate::analysis::find_unused_script_local_functions].
+    pub fn unused_script_local_function(symbol: Symbol) -> Self {
+        Self {
+            file: symbol.module.path.clone(),
+            range: symbol.node.span().cloned(),
+            suggestion: Some("Remove it if nothing calls it dynamically.".into()),
+            ..Self::from_rule(
+                &UNUSED_SCRIPT_LOCAL_FUNCTION,
+                format!("{}() is never referenced in its script", symbol.name()),
+            )
+        }
+    }
+
+    /// A finding for a symbol from [crate::VimPlugin::undocumented_symbols], with the same rule
+    /// as the lint check for missing docs.
+    pub fn undocumented_symbol(symbol: Symbol) -> Self {
+        Self {
+            file: symbol.module.path.clone(),
+            range: symbol.node.span().cloned(),
+            suggestion: Some("Add a `\"\"` doc comment above it.".into()),
+            ..Self::from_rule(
+                &MISSING_DOC,
+                format!("Public {} has no doc comment", symbol.name()),
+            )
+        }
+    }
+}
+
+impl From<&DuplicateHelpTag<'_>> for Finding {
+    /// Reported at the first definition producing the tag.
+    fn from(duplicate: &DuplicateHelpTag<'_>) -> Self {
+        let first = duplicate.definitions.first();
+        let definitions: Vec<String> = duplicate
+            .definitions
+            .iter()
+            .map(|definition| {
+                let path = &definition.symbol.module.path;
+                format!(
+                    "{} ({}:{})",
+                    definition.symbol.name(),
+                    definition.plugin,
+                    path.as_deref().unwrap_or(Path::new("")).display()
+                )
+            })
+            .collect();
+        Self {
+            file: first.and_then(|definition| definition.symbol.module.path.clone()),
+            range: first.and_then(|definition| definition.symbol.node.span().cloned()),
+            suggestion: Some("Rename or undocument all but one of them.".into()),
+            ..Self::from_rule(
+                &DUPLICATE_HELP_TAG,
+                format!(
+                    "Help tag *{}* is generated for more than one symbol: {}",
+                    duplicate.tag,
+                    definitions.join(", ")
+                ),
+            )
+        }
+    }
+}
+
+impl From<&AutoloadNamespaceUse<'_>> for Finding {
+    /// Meant for uses from [crate::VimPlugin::unresolved_autoload_namespaces]. Optional
+    /// integrations are only reported as info.
+    fn from(namespace_use: &AutoloadNamespaceUse<'_>) -> Self {
+        let mut finding = Self::from_rule(
+            &UNRESOLVED_AUTOLOAD_NAMESPACE,
+            format!(
+                "Calls {}() from the {} autoload namespace, which the plugin doesn't provide",
+                namespace_use.functions.join("(), "),
+                namespace_use.namespace
+            ),
+        );
+        if namespace_use.optional {
+            finding.severity = Severity::Info;
+        }
+        Self {
+            file: namespace_use.module.path.clone(),
+            suggestion: Some(format!(
+                "Declare the plugin providing {}# as a dependency.",
+                namespace_use.root()
+            )),
+            ..finding
+        }
+    }
+}
+
+impl From<&UnusedVim9Export<'_>> for Finding {
+    fn from(export: &UnusedVim9Export<'_>) -> Self {
+        Self {
+            file: export.module.path.clone(),
+            ..Self::from_rule(
+                &UNUSED_VIM9_EXPORT,
+                format!(
+                    "{} is exported, but none of the script's importers use it",
+                    export.name
+                ),
+            )
+        }
+    }
+}
+
+impl ColorschemeCoverage<'_> {
+    /// A finding for the standard highlight groups the colorscheme leaves to vim's defaults, or
+    /// None if it defines them all.
+    pub fn to_finding(&self) -> Option<Finding> {
+        if self.missing.is_empty() {
+            return None;
+        }
+        Some(Finding {
+            file: self.module.path.clone(),
+            ..Finding::from_rule(
+                &INCOMPLETE_COLORSCHEME,
+                format!(
+                    "Colorscheme {} doesn't define {} of the standard highlight groups: {}",
+                    self.name,
+                    self.missing.len(),
+                    self.missing.join(", ")
+                ),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::find_unused_script_local_functions;
+    use crate::{VimModule, VimNode, VimPlugin};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn analysis_results_to_findings() {
+        let plugin = VimPlugin::new(vec![
+            VimModule::new(vec![
+                VimNode::function("s:Unused").into(),
+                VimNode::command("Foo").doc("Does foo.").into(),
+                VimNode::function("foo#Bar").into(),
+            ])
+            .with_path("plugin/foo.vim"),
+            VimModule::new(vec![VimNode::command("Foo").doc("Also foo.").into()])
+                .with_path("plugin/other.vim"),
+            VimModule::new(vec![]).with_path("colors/dim.vim"),
+        ]);
+        let mut findings: Vec<Finding> = find_unused_script_local_functions(&plugin)
+            .into_iter()
+            .map(Finding::unused_script_local_function)
+            .chain(
+                plugin
+                    .undocumented_symbols()
+                    .into_iter()
+                    .map(Finding::undocumented_symbol),
+            )
+            .chain(plugin.duplicate_help_tags("foo").iter().map(Finding::from))
+            .chain(
+                plugin
+                    .colorscheme_coverage()
+                    .iter()
+                    .filter_map(ColorschemeCoverage::to_finding),
+            )
+            .collect();
+        assert_eq!(
+            findings
+                .iter()
+                .map(|f| (
+                    f.rule_id.as_str(),
+                    f.file.as_ref().unwrap().to_str().unwrap()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("unused-script-local-function", "plugin/foo.vim"),
+                ("missing-doc", "plugin/foo.vim"),
+                ("duplicate-help-tag", "plugin/foo.vim"),
+                ("incomplete-colorscheme", "colors/dim.vim"),
+            ]
+        );
+        findings.truncate(3);
+        assert_eq!(
+            findings
+                .iter()
+                .map(|f| f.message.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "s:Unused() is never referenced in its script",
+                "Public foo#Bar has no doc comment",
+                "Help tag *:Foo* is generated for more than one symbol: Foo (foo:plugin/foo.vim), \
+                 Foo (foo:plugin/other.vim)",
+            ]
+        );
+    }
+
+    #[test]
+    fn import_analysis_results_to_findings() {
+        let module = VimModule::new(vec![]).with_path("plugin/foo.vim");
+        let namespace_use = AutoloadNamespaceUse {
+            module: &module,
+            namespace: "bar#util".into(),
+            functions: vec!["bar#util#A", "bar#util#B"],
+            provider: None,
+            optional: true,
+        };
+        let export = UnusedVim9Export {
+            module: &module,
+            name: "Helper",
+        };
+        let findings = [Finding::from(&namespace_use), Finding::from(&export)];
+        assert_eq!(
+            findings
+                .iter()
+                .map(|f| (f.rule_id.as_str(), f.severity, f.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "unresolved-autoload-namespace",
+                    Severity::Info,
+                    "Calls bar#util#A(), bar#util#B() from the bar#util autoload namespace, \
+                     which the plugin doesn't provide"
+                ),
+                (
+                    "unused-vim9-export",
+                    Severity::Warning,
+                    "Helper is exported, but none of the script's importers use it"
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use super::Finding;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+/// A snapshot of known findings, used to report only findings introduced since it was taken.
+///
+/// Findings are matched by rule, file and message rather than position so unrelated edits
+/// don't invalidate the baseline. Each baseline entry absorbs at most one matching finding, so
+/// new copies of an existing problem are still reported.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub entries: Vec<BaselineEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub rule_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    pub message: String,
+}
+
+impl Baseline {
+    pub fn from_findings<'a, I: IntoIterator<Item = &'a Finding>>(findings: I) -> Self {
+        Self {
+            entries: findings
+                .into_iter()
+                .map(|f| BaselineEntry {
+                    rule_id: f.rule_id.clone(),
+                    file: f.file.clone(),
+                    message: f.message.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Filters out findings already recorded in the baseline.
+    pub fn new_findings(&self, findings: Vec<Finding>) -> Vec<Finding> {
+        let mut unmatched: Vec<&BaselineEntry> = self.entries.iter().collect();
+        findings
+            .into_iter()
+            .filter(|finding| {
+                let matched = unmatched.iter().position(|e| {
+                    e.rule_id == finding.rule_id
+                        && e.file == finding.file
+                        && e.message == finding.message
+                });
+                match matched {
+                    Some(i) => {
+                        unmatched.swap_remove(i);
+                        false
+                    }
+                    None => true,
+                }
+            })
+            .collect()
+    }
+
+    /// Serializes the baseline as pretty-printed JSON, friendly to checking into version control.
+    pub fn write_to<W: Write>(&self, writer: W) -> crate::Result<()> {
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    /// Loads a baseline previously written with [Baseline::write_to].
+    pub fn read_from<R: Read>(reader: R) -> crate::Result<Self> {
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint::Severity;
+    use pretty_assertions::assert_eq;
+
+    fn finding(rule_id: &str, message: &str) -> Finding {
+        Finding {
+            rule_id: rule_id.into(),
+            severity: Severity::Warning,
+            message: message.into(),
+            file: Some(PathBuf::from("plugin/foo.vim")),
+            range: None,
+            suggestion: None,
+            edits: vec![],
+        }
+    }
+
+    #[test]
+    fn baseline_round_trip_new_findings() {
+        let baseline = Baseline::from_findings(&[finding("too-many-args", "Old")]);
+        let mut json = vec![];
+        baseline.write_to(&mut json).unwrap();
+        let baseline = Baseline::read_from(json.as_slice()).unwrap();
+        assert_eq!(
+            baseline.new_findings(vec![
+                finding("too-many-args", "Old"),
+                finding("too-many-args", "Old"),
+                finding("too-many-args", "New"),
+            ]),
+            vec![
+                finding("too-many-args", "Old"),
+                finding("too-many-args", "New"),
+            ]
+        );
+    }
+}
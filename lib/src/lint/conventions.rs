@@ -0,0 +1,173 @@
+use super::{Fix, Rule, Severity, Violation};
+use crate::{ModuleLanguage, SectionKind, VimModule, VimNode};
+use std::path::Path;
+
+pub const MISSING_ABORT: Rule = Rule {
+    id: "missing-abort",
+    default_severity: Severity::Warning,
+};
+pub const MISSING_DOC: Rule = Rule {
+    id: "missing-doc",
+    default_severity: Severity::Info,
+};
+pub const AUTOLOAD_NAMESPACE_MISMATCH: Rule = Rule {
+    id: "autoload-namespace-mismatch",
+    default_severity: Severity::Error,
+};
+pub const AUTOCMD_WITHOUT_GROUP: Rule = Rule {
+    id: "autocmd-without-group",
+    default_severity: Severity::Warning,
+};
+
+/// Checks a node against common vimscript conventions that have mechanical fixes.
+pub fn check_node(module: &VimModule, node: &VimNode) -> Vec<Violation> {
+    let mut violations = vec![];
+    if let Some(modifiers) = node.function_modifiers() {
+        // Vim9 defs always abort, and Lua functions have no equivalent.
+        if !modifiers.abort && module.language == ModuleLanguage::Vim {
+            violations.push(Violation {
+                rule: &MISSING_ABORT,
+                message: format!(
+                    "{}() doesn't abort on errors, so failures can cascade",
+                    node.name().unwrap_or_default()
+                ),
+                suggestion: Some("Add the `abort` modifier.".into()),
+                fix: Some(Fix::AppendModifier("abort")),
+            });
+        }
+    }
+    if let Some(name) = node.name() {
+        if node.get_doc().is_none() && is_public(node) {
+            violations.push(Violation {
+                rule: &MISSING_DOC,
+                message: format!("Public {name} has no doc comment"),
+                suggestion: Some("Add a `\"\"` doc comment above it.".into()),
+                fix: Some(Fix::InsertDocStub),
+            });
+        }
+    }
+    if let (VimNode::Function { name, .. }, Some(expected)) =
+        (node, module.path.as_deref().and_then(autoload_namespace))
+    {
+        if let Some((namespace, basename)) = name.rsplit_once('#') {
+            if namespace != expected {
+                let fixed_name = format!("{expected}#{basename}");
+                violations.push(Violation {
+                    rule: &AUTOLOAD_NAMESPACE_MISMATCH,
+                    message: format!(
+                        "{name}() is in the wrong file to autoload, expected {fixed_name}()"
+                    ),
+                    suggestion: Some(format!("Rename it to {fixed_name}.")),
+                    fix: Some(Fix::Rename(fixed_name)),
+                });
+            }
+        }
+    }
+    if let VimNode::Autocmd {
+        group: None,
+        events,
+        ..
+    } = node
+    {
+        // Autocmds outside a group can't be cleared, so they pile up each time the script is
+        // sourced again.
+        violations.push(Violation {
+            rule: &AUTOCMD_WITHOUT_GROUP,
+            message: format!(
+                "Autocmd for {} isn't in an augroup, so it's duplicated if the script is re-sourced",
+                events.join(",")
+            ),
+            suggestion: Some(
+                "Define it inside an `augroup` that starts with `autocmd!`, or name a group.".into(),
+            ),
+            fix: None,
+        });
+    }
+    violations
+}
+
+/// Whether a function or command is callable from outside its script.
+fn is_public(node: &VimNode) -> bool {
+    match node {
+        VimNode::Function { name, .. } => {
+            name.contains('#') || name.starts_with("g:") || name.starts_with(char::is_uppercase)
+        }
+        VimNode::Command { .. } => true,
+        _ => false,
+    }
+}
+
+/// Maps an autoload file path like `autoload/foo/bar.vim` to its namespace `foo#bar`.
+fn autoload_namespace(path: &Path) -> Option<String> {
+    if crate::classify_runtime_path(path)?.kind != SectionKind::Autoload {
+        return None;
+    }
+    let relative = path
+        .strip_prefix("after/autoload")
+        .or_else(|_| path.strip_prefix("autoload"))
+        .ok()?
+        .with_extension("");
+    let parts: Option<Vec<&str>> = relative.iter().map(|p| p.to_str()).collect();
+    Some(parts?.join("#"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn check_node_conventions() {
+        let module = VimModule::default().with_path("autoload/foo/bar.vim");
+        let node = VimNode::function("foo#Baz").doc("Does baz.").build();
+        assert_eq!(
+            check_node(&module, &node)
+                .into_iter()
+                .map(|v| (v.rule.id, v.fix))
+                .collect::<Vec<_>>(),
+            vec![
+                (MISSING_ABORT.id, Some(Fix::AppendModifier("abort"))),
+                (
+                    AUTOLOAD_NAMESPACE_MISMATCH.id,
+                    Some(Fix::Rename("foo#bar#Baz".into()))
+                ),
+            ]
+        );
+        let node = VimNode::function("s:Helper").modifiers(["abort"]).build();
+        assert_eq!(check_node(&module, &node), vec![]);
+        let node = VimNode::command("Foo").build();
+        assert_eq!(
+            check_node(&module, &node)
+                .into_iter()
+                .map(|v| v.rule.id)
+                .collect::<Vec<_>>(),
+            vec![MISSING_DOC.id]
+        );
+    }
+
+    #[test]
+    fn check_autocmd_groups() {
+        let module = VimModule::default().with_path("plugin/foo.vim");
+        let autocmd = |group: Option<&str>| VimNode::Autocmd {
+            group: group.map(str::to_string),
+            events: vec!["BufRead".into(), "BufNewFile".into()],
+            patterns: vec!["*.foo".into()],
+            command: "setfiletype foo".into(),
+            doc: None,
+            span: None,
+        };
+        assert_eq!(check_node(&module, &autocmd(Some("foo"))), vec![]);
+        assert_eq!(
+            check_node(&module, &autocmd(None))
+                .into_iter()
+                .map(|v| (v.rule.id, v.message))
+                .collect::<Vec<_>>(),
+            vec![(
+                AUTOCMD_WITHOUT_GROUP.id,
+                "Autocmd for BufRead,BufNewFile isn't in an augroup, so it's duplicated if the \
+                 script is re-sourced"
+                    .to_string()
+            )]
+        );
+    }
+}
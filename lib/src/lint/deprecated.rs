@@ -0,0 +1,254 @@
+use super::{Rule, Severity, SourceViolation, Violation};
+use crate::parser::span_of;
+use crate::TextEdit;
+use tree_sitter::{Node, Tree};
+use tree_sitter_traversal::{traverse, Order};
+
+/// Version of the curated deprecation lists below, bumped whenever entries are added or changed
+/// so tools can tell when upgrading may surface new findings.
+pub const DEPRECATIONS_VERSION: u32 = 1;
+
+pub const LET_OPTION_LITERAL: Rule = Rule {
+    id: "let-option-literal",
+    default_severity: Severity::Info,
+};
+pub const UNGUARDED_FUNCTION_REDEFINITION: Rule = Rule {
+    id: "unguarded-function-redefinition",
+    default_severity: Severity::Warning,
+};
+pub const OBSOLETE_FUNCTION: Rule = Rule {
+    id: "obsolete-function",
+    default_severity: Severity::Warning,
+};
+pub const OBSOLETE_OPTION: Rule = Rule {
+    id: "obsolete-option",
+    default_severity: Severity::Warning,
+};
+
+/// Builtin functions kept only as obsolete aliases (see `:help builtin-function-list`), with
+/// the names that replaced them.
+const OBSOLETE_FUNCTIONS: [(&str, &str); 6] = [
+    ("buffer_exists", "bufexists"),
+    ("buffer_name", "bufname"),
+    ("buffer_number", "bufnr"),
+    ("file_readable", "filereadable"),
+    ("highlight_exists", "hlexists"),
+    ("highlightID", "hlID"),
+];
+
+/// Options that only exist for backwards compatibility, with the options that replaced them.
+const OBSOLETE_OPTIONS: [(&str, &str); 2] =
+    [("textmode", "fileformat"), ("textauto", "fileformats")];
+
+/// Checks source for legacy constructs that have better modern replacements.
+pub fn check_source(tree: &Tree, source: &[u8]) -> Vec<SourceViolation> {
+    let text = |node: &Node| node.utf8_text(source).unwrap_or_default();
+    let mut violations = vec![];
+    for node in traverse(tree.walk(), Order::Pre) {
+        match node.kind() {
+            "let_statement" => {
+                if let Some(violation) = check_let_option(&node, source) {
+                    violations.push(violation);
+                }
+            }
+            "function_definition" => {
+                let has_bang = node.children(&mut node.walk()).any(|c| c.kind() == "bang");
+                let Some(name) = node
+                    .children(&mut node.walk())
+                    .find(|c| c.kind() == "function_declaration")
+                    .and_then(|d| d.child_by_field_name("name"))
+                else {
+                    continue;
+                };
+                // Only bare global names can clobber another script's function.
+                if !has_bang || name.kind() != "identifier" || text(&name).contains('#') {
+                    continue;
+                }
+                if is_inside_if(&node) {
+                    continue;
+                }
+                let name = text(&name);
+                violations.push(SourceViolation {
+                    violation: Violation {
+                        rule: &UNGUARDED_FUNCTION_REDEFINITION,
+                        message: format!(
+                            "function! {name}() silently replaces any global function with the \
+                             same name"
+                        ),
+                        suggestion: Some(format!(
+                            "Make it script-local (s:{name}) or autoloaded, or define it inside \
+                             `if !exists('*{name}')`."
+                        )),
+                        fix: None,
+                    },
+                    range: span_of(&node),
+                    edits: vec![],
+                });
+            }
+            "call_expression" => {
+                let Some(function) = node
+                    .child_by_field_name("function")
+                    .filter(|f| f.kind() == "identifier")
+                else {
+                    continue;
+                };
+                let Some((name, replacement)) = OBSOLETE_FUNCTIONS
+                    .iter()
+                    .find(|(name, _)| *name == text(&function))
+                else {
+                    continue;
+                };
+                violations.push(SourceViolation {
+                    violation: Violation {
+                        rule: &OBSOLETE_FUNCTION,
+                        message: format!("{name}() is an obsolete name for {replacement}()"),
+                        suggestion: Some(format!("Call {replacement}() instead.")),
+                        fix: None,
+                    },
+                    range: span_of(&function),
+                    edits: vec![TextEdit {
+                        file: None,
+                        range: span_of(&function),
+                        new_text: replacement.to_string(),
+                    }],
+                });
+            }
+            "option_name" => {
+                let Some((name, replacement)) = OBSOLETE_OPTIONS
+                    .iter()
+                    .find(|(name, _)| *name == text(&node))
+                else {
+                    continue;
+                };
+                violations.push(SourceViolation {
+                    violation: Violation {
+                        rule: &OBSOLETE_OPTION,
+                        message: format!("'{name}' is obsolete"),
+                        suggestion: Some(format!("Use '{replacement}' instead.")),
+                        fix: None,
+                    },
+                    range: span_of(&node),
+                    edits: vec![],
+                });
+            }
+            _ => {}
+        }
+    }
+    violations
+}
+
+/// Checks for `let &opt = value` with a literal value, which `:set` says more directly.
+fn check_let_option(node: &Node, source: &[u8]) -> Option<SourceViolation> {
+    let text = |node: &Node| node.utf8_text(source).unwrap_or_default();
+    let mut cursor = node.walk();
+    let mut named = node.named_children(&mut cursor);
+    let option = named.next().filter(|n| n.kind() == "option")?;
+    let value = named.next()?;
+    let name = text(
+        &option
+            .children(&mut option.walk())
+            .find(|c| c.kind() == "option_name")?,
+    );
+    let set_command = match option
+        .children(&mut option.walk())
+        .find(|c| c.kind() == "scope")
+        .map(|s| text(&s))
+    {
+        Some("l:") => "setlocal",
+        Some("g:") => "setglobal",
+        _ => "set",
+    };
+    let literal = match value.kind() {
+        "integer_literal" => text(&value),
+        "string_literal" => {
+            let quoted = text(&value);
+            quoted.strip_prefix('\'')?.strip_suffix('\'')?
+        }
+        _ => return None,
+    };
+    // Values that would need escaping for :set are clearer with :let.
+    if literal.is_empty()
+        || literal
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '\\' | '|' | '"' | '\''))
+    {
+        return None;
+    }
+    let replacement = format!("{set_command} {name}={literal}");
+    Some(SourceViolation {
+        violation: Violation {
+            rule: &LET_OPTION_LITERAL,
+            message: format!(
+                "`{}` sets an option to a literal, which `{replacement}` says directly",
+                text(node)
+            ),
+            suggestion: Some(format!("Use `{replacement}`.")),
+            fix: None,
+        },
+        range: span_of(node),
+        edits: vec![TextEdit {
+            file: None,
+            range: span_of(node),
+            new_text: replacement,
+        }],
+    })
+}
+
+fn is_inside_if(node: &Node) -> bool {
+    let mut parent = node.parent();
+    while let Some(p) = parent {
+        if p.kind() == "if_statement" {
+            return true;
+        }
+        parent = p.parent();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VimParser;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn check_source_legacy_constructs() {
+        let code = r#"
+let &l:sw = 2
+let &tw = s:width
+let &fdm = 'marker'
+set textmode
+if buffer_exists(1) | endif
+function! Global()
+endfunction
+function! s:Local()
+endfunction
+if !exists('*Guarded')
+  function! Guarded()
+  endfunction
+endif
+"#;
+        let tree = VimParser::new().unwrap().parse_tree(code).unwrap();
+        let violations = check_source(&tree, code.as_bytes());
+        assert_eq!(
+            violations
+                .iter()
+                .map(|v| (v.violation.rule.id, v.range.start.0))
+                .collect::<Vec<_>>(),
+            vec![
+                (LET_OPTION_LITERAL.id, 1),
+                (LET_OPTION_LITERAL.id, 3),
+                (OBSOLETE_OPTION.id, 4),
+                (OBSOLETE_FUNCTION.id, 5),
+                (UNGUARDED_FUNCTION_REDEFINITION.id, 6),
+            ]
+        );
+        assert_eq!(
+            violations
+                .iter()
+                .flat_map(|v| v.edits.iter().map(|e| e.new_text.as_str()))
+                .collect::<Vec<_>>(),
+            vec!["setlocal sw=2", "set fdm=marker", "bufexists"]
+        );
+    }
+}
@@ -0,0 +1,434 @@
+//! Checks for common problems in parsed plugins, like docs that drifted out of sync with code.
+//!
+//! Each check is a rule with a stable id, and [lint_plugin] runs all of them. Findings can be
+//! suppressed with an `@suppress RULE_ID…` directive in a node's doc comment, or in the module
+//! doc to cover the whole module. A [Baseline] of existing findings can be recorded to only
+//! report new ones.
+//!
+//! Linting source code directly with [lint_source] also locates findings in the source and
+//! attaches [TextEdit]s for the ones with mechanical fixes. It also runs checks that need the
+//! full syntax, like the ones for deprecated constructs (see [DEPRECATIONS_VERSION]) and for
+//! autoload scripts doing more than defining functions.
+
+use crate::data::doc_directives;
+use crate::parser::DefinitionLocation;
+use crate::{Span, TextEdit, VimModule, VimNode, VimParser, VimPlugin};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+mod analyses;
+mod baseline;
+mod conventions;
+mod deprecated;
+mod side_effects;
+mod signatures;
+
+pub use baseline::{Baseline, BaselineEntry};
+pub use deprecated::DEPRECATIONS_VERSION;
+
+/// A problem reported by a lint rule.
+///
+/// This is the common output format for all analyses: lint rules produce findings directly, and
+/// whole-plugin analyses like [crate::VimPlugin::duplicate_help_tags] convert to them with
+/// adapters like `Finding::from` or [Finding::unused_script_local_function], each with its own
+/// stable rule id. Its serialized form is kept stable so different frontends can render the same
+/// data.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Finding {
+    /// Stable id of the rule that produced the finding, like `doc-param-not-in-signature`.
+    pub rule_id: String,
+    pub severity: Severity,
+    pub message: String,
+    /// Path of the module the finding is in, relative to the plugin root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    /// Location of the finding within the file, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<Span>,
+    /// A human-readable hint for how to address the finding.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+    /// Edits that fix the finding when applied to the file, if it has a mechanical fix.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edits: Vec<TextEdit>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// Options for which rules [lint_plugin_with_config] runs and how severe their findings are.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LintConfig {
+    /// Severities to use instead of each rule's default, keyed by rule id.
+    pub severity_overrides: HashMap<String, Severity>,
+    /// Ids of rules to skip entirely.
+    pub disabled_rules: HashSet<String>,
+}
+
+impl LintConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_severity<S: Into<String>>(mut self, rule_id: S, severity: Severity) -> Self {
+        self.severity_overrides.insert(rule_id.into(), severity);
+        self
+    }
+
+    pub fn disable<S: Into<String>>(mut self, rule_id: S) -> Self {
+        self.disabled_rules.insert(rule_id.into());
+        self
+    }
+
+    fn severity(&self, rule: &Rule) -> Severity {
+        self.severity_overrides
+            .get(rule.id)
+            .copied()
+            .unwrap_or(rule.default_severity)
+    }
+}
+
+/// A lint rule's stable id and the severity of its findings unless overridden.
+#[derive(Debug, PartialEq)]
+struct Rule {
+    id: &'static str,
+    default_severity: Severity,
+}
+
+/// A rule violation found by one of the checks, before config and suppressions are applied.
+#[derive(Debug, PartialEq)]
+struct Violation {
+    rule: &'static Rule,
+    message: String,
+    suggestion: Option<String>,
+    fix: Option<Fix>,
+}
+
+/// A violation found directly in source, which already knows its location and any fix edits.
+#[derive(Debug, PartialEq)]
+struct SourceViolation {
+    violation: Violation,
+    range: Span,
+    edits: Vec<TextEdit>,
+}
+
+/// A mechanical fix for a violation, resolved to [TextEdit]s once source positions are known.
+#[derive(Debug, PartialEq)]
+enum Fix {
+    /// Append a modifier like `abort` to a function signature.
+    AppendModifier(&'static str),
+    /// Insert a placeholder doc comment above the definition.
+    InsertDocStub,
+    /// Rename the definition (not its references) to the given name.
+    Rename(String),
+}
+
+impl Fix {
+    fn text_edits(
+        &self,
+        node: &VimNode,
+        location: &DefinitionLocation,
+        code: &str,
+    ) -> Vec<TextEdit> {
+        match self {
+            Fix::AppendModifier(modifier) => location
+                .signature_end
+                .iter()
+                .map(|range| TextEdit {
+                    file: None,
+                    range: range.clone(),
+                    new_text: format!(" {modifier}"),
+                })
+                .collect(),
+            Fix::InsertDocStub => {
+                let Some(span) = node.span() else {
+                    return vec![];
+                };
+                let start = span.byte_range.start;
+                let line_start = start - span.start.1;
+                let indent = code
+                    .get(line_start..start)
+                    .filter(|prefix| prefix.trim().is_empty())
+                    .unwrap_or_default();
+                let name = match node {
+                    VimNode::Function { name, .. } => format!("{name}()"),
+                    _ => format!(":{}", node.name().unwrap_or_default()),
+                };
+                vec![TextEdit {
+                    file: None,
+                    range: Span {
+                        start: span.start,
+                        end: span.start,
+                        byte_range: start..start,
+                    },
+                    new_text: format!("\"\" TODO: Document {name}.\n{indent}"),
+                }]
+            }
+            Fix::Rename(new_name) => vec![TextEdit {
+                file: None,
+                range: location.name_span.clone(),
+                new_text: new_name.clone(),
+            }],
+        }
+    }
+}
+
+/// Runs all lint rules over the plugin with default config (see [lint_plugin_with_config]).
+pub fn lint_plugin(plugin: &VimPlugin) -> Vec<Finding> {
+    lint_plugin_with_config(plugin, &LintConfig::default())
+}
+
+/// Runs enabled lint rules over the plugin and returns their unsuppressed findings in module
+/// order.
+pub fn lint_plugin_with_config(plugin: &VimPlugin, config: &LintConfig) -> Vec<Finding> {
+    plugin
+        .content
+        .iter()
+        .flat_map(|module| lint_module(module, config, None))
+        .collect()
+}
+
+/// Parses and lints a single file's source, attaching ranges and fix edits to the findings.
+///
+/// The path (relative to the plugin root) is used by rules that depend on the file's location,
+/// like autoload naming checks.
+pub fn lint_source(
+    parser: &VimParser,
+    code: &str,
+    path: Option<&Path>,
+    config: &LintConfig,
+) -> crate::Result<Vec<Finding>> {
+    let (module, locations) = parser.parse_module_str_with_locations(code)?;
+    let module = VimModule {
+        path: path.map(Path::to_path_buf),
+        ..module
+    };
+    let mut findings = lint_module(&module, config, Some((&locations, code)));
+    let tree = parser.parse_tree(code)?;
+    let source_violations = deprecated::check_source(&tree, code.as_bytes())
+        .into_iter()
+        .chain(side_effects::check_source(
+            &tree,
+            code.as_bytes(),
+            module.path.as_deref(),
+        ));
+    for SourceViolation {
+        violation,
+        range,
+        edits,
+    } in source_violations
+    {
+        let rule = violation.rule;
+        if config.disabled_rules.contains(rule.id) || is_suppressed(rule, &module, None) {
+            continue;
+        }
+        findings.push(Finding {
+            rule_id: rule.id.to_string(),
+            severity: config.severity(rule),
+            message: violation.message,
+            file: module.path.clone(),
+            range: Some(range),
+            suggestion: violation.suggestion,
+            edits,
+        });
+    }
+    Ok(findings)
+}
+
+fn lint_module(
+    module: &VimModule,
+    config: &LintConfig,
+    source: Option<(&[DefinitionLocation], &str)>,
+) -> Vec<Finding> {
+    let mut findings = vec![];
+    for node in &module.nodes {
+        let location = node.span().and_then(|span| {
+            source?
+                .0
+                .iter()
+                .find(|l| l.start_byte == span.byte_range.start)
+        });
+        let violations = signatures::check_node(node)
+            .into_iter()
+            .chain(conventions::check_node(module, node));
+        for Violation {
+            rule,
+            message,
+            suggestion,
+            fix,
+        } in violations
+        {
+            if config.disabled_rules.contains(rule.id) || is_suppressed(rule, module, Some(node)) {
+                continue;
+            }
+            let edits = match (&fix, location, source) {
+                (Some(fix), Some(location), Some((_, code))) => {
+                    fix.text_edits(node, location, code)
+                }
+                _ => vec![],
+            };
+            findings.push(Finding {
+                rule_id: rule.id.to_string(),
+                severity: config.severity(rule),
+                message,
+                file: module.path.clone(),
+                range: node.span().cloned(),
+                suggestion,
+                edits,
+            });
+        }
+    }
+    findings
+}
+
+fn is_suppressed(rule: &Rule, module: &VimModule, node: Option<&VimNode>) -> bool {
+    [module.doc.as_deref(), node.and_then(VimNode::get_doc)]
+        .into_iter()
+        .flatten()
+        .flat_map(doc_directives)
+        .filter(|(directive, _)| *directive == "suppress")
+        .flat_map(|(_, rule_ids)| rule_ids.split([',', ' ']))
+        .any(|rule_id| rule_id == rule.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn lint_source_fix_edits() {
+        let code = "  function! foo#Bar(x) range\n  endfunction\n";
+        let parser = VimParser::new().unwrap();
+        let findings = lint_source(
+            &parser,
+            code,
+            Some(Path::new("autoload/baz.vim")),
+            &LintConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            findings
+                .iter()
+                .map(|f| f.rule_id.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "missing-abort",
+                "missing-doc",
+                "autoload-namespace-mismatch"
+            ]
+        );
+        assert_eq!(findings[0].range.as_ref().map(|r| r.start), Some((0, 2)));
+        let mut edits: Vec<_> = findings.iter().flat_map(|f| f.edits.clone()).collect();
+        // Apply back to front so earlier byte offsets stay valid.
+        edits.sort_by_key(|e| std::cmp::Reverse(e.range.byte_range.start));
+        let mut fixed = code.to_string();
+        for edit in edits {
+            fixed.replace_range(edit.range.byte_range, &edit.new_text);
+        }
+        assert_eq!(
+            fixed,
+            "  \"\" TODO: Document foo#Bar().\n  function! baz#Bar(x) range abort\n  endfunction\n"
+        );
+    }
+
+    #[test]
+    fn lint_plugin_ranges_from_node_spans() {
+        let parser = VimParser::builder().record_spans(true).build().unwrap();
+        let module = parser
+            .parse_module_str(
+                "\"\" Does foo.\ncommand Foo echo\n\nfunction foo#Bar() abort\nendfunction\n",
+            )
+            .unwrap()
+            .with_path("autoload/foo.vim");
+        let findings = lint_plugin(&VimPlugin::new(vec![module]));
+        assert_eq!(
+            findings
+                .iter()
+                .map(|f| (
+                    f.rule_id.as_str(),
+                    f.range.as_ref().map(|r| (r.start, r.end))
+                ))
+                .collect::<Vec<_>>(),
+            vec![("missing-doc", Some(((3, 0), (4, 11))))]
+        );
+    }
+
+    #[test]
+    fn finding_json_format() {
+        let finding = Finding {
+            rule_id: "too-many-args".into(),
+            severity: Severity::Info,
+            message: "Too many".into(),
+            file: Some(PathBuf::from("plugin/foo.vim")),
+            range: Some(Span {
+                start: (1, 0),
+                end: (1, 4),
+                byte_range: 10..14,
+            }),
+            suggestion: None,
+            edits: vec![],
+        };
+        let json = serde_json::to_string(&finding).unwrap();
+        assert_eq!(
+            json,
+            r#"{"rule_id":"too-many-args","severity":"info","message":"Too many","file":"plugin/foo.vim","range":{"start":[1,0],"end":[1,4],"byte_range":{"start":10,"end":14}}}"#
+        );
+        assert_eq!(serde_json::from_str::<Finding>(&json).unwrap(), finding);
+    }
+
+    #[test]
+    fn lint_plugin_suppression_and_severity() {
+        let plugin = VimPlugin::new(vec![
+            VimModule::new(vec![
+                VimNode::function("foo#A")
+                    .args(["..."])
+                    .modifiers(["abort"])
+                    .doc("Does A.")
+                    .into(),
+                VimNode::function("foo#B")
+                    .args(["..."])
+                    .modifiers(["abort"])
+                    .doc("Does B.\n@suppress varargs-without-usage")
+                    .into(),
+            ])
+            .with_path("autoload/foo.vim"),
+            VimModule::new(vec![VimNode::function("bar#A")
+                .args(["..."])
+                .modifiers(["abort"])
+                .doc("Does A.")
+                .into()])
+            .with_path("autoload/bar.vim")
+            .with_doc("@suppress varargs-without-usage"),
+        ]);
+        assert_eq!(
+            lint_plugin(&plugin),
+            vec![Finding {
+                rule_id: "varargs-without-usage".into(),
+                severity: Severity::Warning,
+                message: "foo#A() takes optional args (...) but has no @usage documenting them"
+                    .into(),
+                file: Some(PathBuf::from("autoload/foo.vim")),
+                range: None,
+                suggestion: Some(r#"Add a line like "@usage [...]" to the doc comment."#.into()),
+                edits: vec![],
+            }]
+        );
+        let config = LintConfig::new().with_severity("varargs-without-usage", Severity::Error);
+        assert_eq!(
+            lint_plugin_with_config(&plugin, &config)
+                .iter()
+                .map(|f| f.severity)
+                .collect::<Vec<_>>(),
+            vec![Severity::Error]
+        );
+        let config = LintConfig::new().disable("varargs-without-usage");
+        assert_eq!(lint_plugin_with_config(&plugin, &config), vec![]);
+    }
+}
@@ -0,0 +1,107 @@
+use super::{Rule, Severity, SourceViolation, Violation};
+use crate::parser::span_of;
+use crate::SectionKind;
+use std::path::Path;
+use tree_sitter::{Node, Tree};
+
+pub const AUTOLOAD_SIDE_EFFECT: Rule = Rule {
+    id: "autoload-side-effect",
+    default_severity: Severity::Warning,
+};
+
+/// Checks an autoload script for statements that do something when it's sourced, outside any
+/// function. Scripts outside autoload/ are skipped.
+///
+/// Autoload scripts are sourced the first time one of their functions is called, so anything
+/// besides definitions there happens at a time the user can't predict. Assigning script
+/// variables is allowed, since that's how autoload scripts set up their own state.
+pub fn check_source(tree: &Tree, source: &[u8], path: Option<&Path>) -> Vec<SourceViolation> {
+    let is_autoload = path
+        .and_then(crate::classify_runtime_path)
+        .is_some_and(|section| section.kind == SectionKind::Autoload);
+    let mut violations = vec![];
+    if is_autoload {
+        check_statements(tree.root_node(), source, &mut violations);
+    }
+    violations
+}
+
+/// Checks the statements under a node, including ones nested in blocks like `if` or `try` that
+/// still run when the script is sourced.
+fn check_statements(node: Node, source: &[u8], violations: &mut Vec<SourceViolation>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let effect = match child.kind() {
+            "function_definition" => continue,
+            "call_statement" => "calls a function",
+            "set_statement" | "setlocal_statement" => "sets an option",
+            "map_statement" => "defines a mapping",
+            "autocmd_statement" => "defines an autocmd",
+            "command_statement" => "defines a command",
+            "highlight_statement" => "changes highlighting",
+            "execute_statement" | "normal_statement" => "runs commands",
+            _ => {
+                check_statements(child, source, violations);
+                continue;
+            }
+        };
+        let text = child.utf8_text(source).unwrap_or_default();
+        let first_line = text.lines().next().unwrap_or_default();
+        violations.push(SourceViolation {
+            violation: Violation {
+                rule: &AUTOLOAD_SIDE_EFFECT,
+                message: format!(
+                    "`{first_line}` {effect} at the top level of an autoload script, so it runs whenever the script happens to be autoloaded"
+                ),
+                suggestion: Some(
+                    "Move it into a function, or into plugin/ if it should run at startup."
+                        .into(),
+                ),
+                fix: None,
+            },
+            range: span_of(&child),
+            edits: vec![],
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VimParser;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn check_source_autoload_side_effects() {
+        let code = r#"
+if exists('g:loaded_foo_autoload')
+  finish
+endif
+let s:cache = {}
+call foo#util#Init()
+silent! nnoremap <Leader>f :call foo#Run()<CR>
+augroup foo
+  autocmd BufEnter * call foo#Refresh()
+augroup END
+function! foo#Run() abort
+  setlocal shiftwidth=2
+endfunction
+"#;
+        let tree = VimParser::new().unwrap().parse_tree(code).unwrap();
+        let messages = |path: &str| {
+            check_source(&tree, code.as_bytes(), Some(Path::new(path)))
+                .into_iter()
+                .map(|v| (v.range.start.0, v.violation.message))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            messages("autoload/foo.vim"),
+            vec![
+                (5, "`call foo#util#Init()` calls a function at the top level of an autoload script, so it runs whenever the script happens to be autoloaded".to_string()),
+                (6, "`nnoremap <Leader>f :call foo#Run()<CR>` defines a mapping at the top level of an autoload script, so it runs whenever the script happens to be autoloaded".to_string()),
+                (8, "`autocmd BufEnter * call foo#Refresh()` defines an autocmd at the top level of an autoload script, so it runs whenever the script happens to be autoloaded".to_string()),
+            ]
+        );
+        assert_eq!(messages("plugin/foo.vim"), vec![]);
+    }
+}
@@ -0,0 +1,223 @@
+use super::{Rule, Severity, Violation};
+use crate::data::doc_directives;
+use crate::VimNode;
+use regex::Regex;
+use std::sync::OnceLock;
+
+pub const DOC_PARAM_NOT_IN_SIGNATURE: Rule = Rule {
+    id: "doc-param-not-in-signature",
+    default_severity: Severity::Error,
+};
+pub const TOO_MANY_ARGS: Rule = Rule {
+    id: "too-many-args",
+    default_severity: Severity::Info,
+};
+pub const VARARGS_WITHOUT_USAGE: Rule = Rule {
+    id: "varargs-without-usage",
+    default_severity: Severity::Warning,
+};
+pub const OPTIONAL_ARG_WITHOUT_DEFAULT: Rule = Rule {
+    id: "optional-arg-without-default",
+    default_severity: Severity::Warning,
+};
+
+/// Named arg count above which a function is reported as hard to call correctly. Vim itself
+/// allows up to 20.
+const MAX_NAMED_ARGS: usize = 6;
+
+/// A parameter mentioned in a function's doc comment.
+#[derive(Debug, PartialEq)]
+struct DocParam<'a> {
+    name: &'a str,
+    /// Whether it's documented as optional, like `[count]` in a `@usage` line.
+    optional: bool,
+}
+
+/// Checks a function's signature and its documented parameters against each other.
+///
+/// `a:` references in the body aren't checked yet since the parser doesn't capture bodies.
+pub fn check_node(node: &VimNode) -> Vec<Violation> {
+    let VimNode::Function {
+        name, args, doc, ..
+    } = node
+    else {
+        return vec![];
+    };
+    let mut findings = vec![];
+    let named_args = args.iter().filter(|a| *a != "...").count();
+    if named_args > MAX_NAMED_ARGS {
+        findings.push(Violation {
+            rule: &TOO_MANY_ARGS,
+            fix: None,
+            message: format!("{name}() takes {named_args} args (more than {MAX_NAMED_ARGS})"),
+            suggestion: Some("Consider passing a dict of options instead.".into()),
+        });
+    }
+    // Undocumented functions are covered by coverage checks instead.
+    let Some(doc) = doc else {
+        return findings;
+    };
+    let has_varargs = args.iter().any(|a| a == "...");
+    let params = doc_params(doc);
+    if has_varargs && !doc_directives(doc).any(|(directive, _)| directive == "usage") {
+        findings.push(Violation {
+            rule: &VARARGS_WITHOUT_USAGE,
+            fix: None,
+            message: format!(
+                "{name}() takes optional args (...) but has no @usage documenting them"
+            ),
+            suggestion: Some(format!(
+                "Add a line like \"@usage {}\" to the doc comment.",
+                usage_for(args)
+            )),
+        });
+    }
+    let defaults: Vec<&str> = doc_directives(doc)
+        .filter(|(directive, _)| *directive == "default")
+        .filter_map(|(_, rest)| rest.split_once('=').map(|(param, _)| param.trim()))
+        .collect();
+    findings.extend(
+        params
+            .iter()
+            .filter(|param| param.optional && !defaults.contains(&param.name))
+            .map(|param| Violation {
+                rule: &OPTIONAL_ARG_WITHOUT_DEFAULT,
+                fix: None,
+                message: format!(
+                    "{name}() has optional arg {:?} with no @default {}=…",
+                    param.name, param.name
+                ),
+                suggestion: None,
+            }),
+    );
+    let mismatched = params
+        .into_iter()
+        .filter(|param| {
+            let declared = args.iter().any(|a| a == param.name);
+            // Optional params can be passed positionally through `...`.
+            !(declared || ((param.optional || param.name == "...") && has_varargs))
+        })
+        .map(|param| Violation {
+            rule: &DOC_PARAM_NOT_IN_SIGNATURE,
+            fix: None,
+            message: format!(
+                "{name}() documents parameter {:?}, which isn't in its signature ({})",
+                param.name,
+                args.join(", ")
+            ),
+            suggestion: None,
+        });
+    findings.extend(mismatched);
+    findings
+}
+
+/// Builds a vimdoc-style usage string for the args, like `{path} [...]`.
+fn usage_for(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| match arg.as_str() {
+            "..." => "[...]".to_string(),
+            arg => format!("{{{arg}}}"),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Finds params documented with `@param NAME …` or as `{name}`/`[name]` in a `@usage` line.
+fn doc_params(doc: &str) -> Vec<DocParam<'_>> {
+    static USAGE_PARAM: OnceLock<Regex> = OnceLock::new();
+    let usage_param =
+        USAGE_PARAM.get_or_init(|| Regex::new(r"\{(\w+|\.\.\.)\}|\[(\w+|\.\.\.)\]").unwrap());
+    let mut params = vec![];
+    for (directive, rest) in doc_directives(doc) {
+        if directive == "param" {
+            if let Some(name) = rest.split_whitespace().next() {
+                params.push(DocParam {
+                    name,
+                    optional: false,
+                });
+            }
+        } else if directive == "usage" {
+            params.extend(usage_param.captures_iter(rest).filter_map(|c| {
+                let (m, optional) = match (c.get(1), c.get(2)) {
+                    (Some(m), _) => (m, false),
+                    (_, Some(m)) => (m, true),
+                    _ => return None,
+                };
+                Some(DocParam {
+                    name: m.as_str(),
+                    optional,
+                })
+            }));
+        }
+    }
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn check_node_stale_doc_params() {
+        let node = VimNode::function("foo#Bar")
+            .args(["path", "..."])
+            .doc("@usage {path} [count]\n@default count=1\n@param path The file.\n@param oldname Removed.")
+            .build();
+        assert_eq!(
+            check_node(&node),
+            vec![Violation {
+                rule: &DOC_PARAM_NOT_IN_SIGNATURE,
+                fix: None,
+                message: r#"foo#Bar() documents parameter "oldname", which isn't in its signature (path, ...)"#
+                    .to_string(),
+                suggestion: None,
+            }]
+        );
+        let node = VimNode::function("foo#Baz")
+            .args(["path"])
+            .doc("@usage {path} [count]\n@default count=1")
+            .build();
+        assert_eq!(
+            check_node(&node)
+                .iter()
+                .map(|v| v.rule.id)
+                .collect::<Vec<_>>(),
+            vec![DOC_PARAM_NOT_IN_SIGNATURE.id]
+        );
+    }
+
+    #[test]
+    fn check_node_signature_docs_quality() {
+        let node = VimNode::function("foo#Many")
+            .args(["a", "b", "c", "d", "e", "f", "g", "..."])
+            .doc("Does too much.")
+            .build();
+        assert_eq!(
+            check_node(&node)
+                .iter()
+                .map(|v| v.rule.id)
+                .collect::<Vec<_>>(),
+            vec![TOO_MANY_ARGS.id, VARARGS_WITHOUT_USAGE.id]
+        );
+        let node = VimNode::function("foo#Opt")
+            .args(["..."])
+            .doc("@usage [count]")
+            .build();
+        assert_eq!(
+            check_node(&node),
+            vec![Violation {
+                rule: &OPTIONAL_ARG_WITHOUT_DEFAULT,
+                fix: None,
+                message: r#"foo#Opt() has optional arg "count" with no @default count=…"#
+                    .to_string(),
+                suggestion: None,
+            }]
+        );
+        // Undocumented functions only get signature checks.
+        assert_eq!(
+            check_node(&VimNode::function("s:Helper").args(["..."]).build()),
+            vec![]
+        );
+    }
+}
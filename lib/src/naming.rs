@@ -0,0 +1,113 @@
+use crate::{VimNode, VimPlugin};
+use std::path::Component;
+
+impl VimPlugin {
+    /// Infers the conventional naming prefix of the plugin (e.g. `codefmt` for a plugin with
+    /// `autoload/codefmt.vim` and `g:loaded_codefmt`).
+    ///
+    /// Uses votes from autoload paths, autoload function names, and `g:loaded_*` guard variables,
+    /// falling back to the common prefix of the plugin's command names when none of those exist.
+    pub fn infer_prefix(&self) -> Option<String> {
+        let mut votes: Vec<(String, usize)> = vec![];
+        let mut vote = |prefix: &str, weight: usize| {
+            if prefix.is_empty() {
+                return;
+            }
+            match votes.iter_mut().find(|(p, _)| p == prefix) {
+                Some((_, count)) => *count += weight,
+                None => votes.push((prefix.to_string(), weight)),
+            }
+        };
+        for module in &self.content {
+            if let Some(path) = &module.path {
+                let mut components = path.components().map(Component::as_os_str);
+                if components.next().is_some_and(|c| c == "autoload") {
+                    if let Some(first) = components.next().and_then(|c| c.to_str()) {
+                        vote(first.strip_suffix(".vim").unwrap_or(first), 2);
+                    }
+                }
+            }
+            for node in &module.nodes {
+                match node {
+                    VimNode::Function { name, .. } => {
+                        if let Some((prefix, _)) = name.split_once('#') {
+                            vote(prefix, 1);
+                        }
+                    }
+                    VimNode::Variable { name, .. } => {
+                        if let Some(prefix) = name.strip_prefix("g:loaded_") {
+                            vote(prefix, 3);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        // Stable sort keeps the first-seen prefix for ties.
+        votes.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        votes
+            .into_iter()
+            .next()
+            .map(|(prefix, _)| prefix)
+            .or_else(|| self.common_command_prefix())
+    }
+
+    fn common_command_prefix(&self) -> Option<String> {
+        let mut names = self.symbols().filter_map(|s| match s.node {
+            VimNode::Command { name, .. } => Some(name.as_str()),
+            _ => None,
+        });
+        let first = names.next()?;
+        let mut prefix_len = first.len();
+        let mut count = 1;
+        for name in names {
+            prefix_len = first
+                .char_indices()
+                .zip(name.chars())
+                .take_while(|((i, a), b)| *i < prefix_len && a == b)
+                .map(|((i, a), _)| i + a.len_utf8())
+                .last()
+                .unwrap_or(0);
+            count += 1;
+        }
+        // A single command is its own "prefix", which isn't meaningful.
+        (count > 1 && prefix_len > 0).then(|| first[..prefix_len].to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{VimModule, VimNode, VimPlugin};
+
+    #[test]
+    fn infer_prefix_autoload_and_loaded_var() {
+        let plugin = VimPlugin::new(vec![
+            VimModule::new(vec![
+                VimNode::variable("g:loaded_codefmt", "1").into(),
+                VimNode::function("s:Helper").into(),
+            ])
+            .with_path("plugin/codefmt.vim"),
+            VimModule::new(vec![VimNode::function("codefmt#FormatLines").into()])
+                .with_path("autoload/codefmt.vim"),
+            VimModule::new(vec![VimNode::function("maktaba#ensure#IsString").into()])
+                .with_path("autoload/codefmt/vendored.vim"),
+        ]);
+        assert_eq!(plugin.infer_prefix(), Some("codefmt".into()));
+    }
+
+    #[test]
+    fn infer_prefix_command_fallback() {
+        let plugin = VimPlugin::new(vec![VimModule::new(vec![
+            VimNode::command("GitStatus").into(),
+            VimNode::command("GitBlame").into(),
+        ])
+        .with_path("plugin/git.vim")]);
+        assert_eq!(plugin.infer_prefix(), Some("git".into()));
+    }
+
+    #[test]
+    fn infer_prefix_none() {
+        let plugin = VimPlugin::new(vec![VimModule::new(vec![VimNode::command("Foo").into()])]);
+        assert_eq!(plugin.infer_prefix(), None);
+    }
+}
@@ -0,0 +1,148 @@
+use super::locations::span_of;
+use super::treenodes::get_treenode_text;
+use crate::data::Fingerprint;
+use crate::{Span, VimNode};
+use std::collections::HashMap;
+use tree_sitter::{Node, Tree};
+use tree_sitter_traversal::{traverse, Order};
+
+/// Whether and how [crate::VimParser] captures function bodies on [VimNode::Function] nodes, for
+/// analyses that need more than signatures.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FunctionBodyCapture {
+    /// Don't capture bodies.
+    #[default]
+    Off,
+    /// Capture each body's source as written.
+    Source,
+    /// Capture each body as its tokens separated by spaces, without comments or formatting and
+    /// with argument and local variable names replaced by `$0`, `$1`… in order of first use, so
+    /// bodies that only differ in those compare equal.
+    Tokens,
+}
+
+/// Captures the bodies of all function definitions in the tree, keyed by the start byte of each
+/// definition.
+pub fn capture_function_bodies(
+    tree: &Tree,
+    source: &[u8],
+    capture: FunctionBodyCapture,
+) -> HashMap<usize, String> {
+    if capture == FunctionBodyCapture::Off {
+        return HashMap::new();
+    }
+    traverse(tree.walk(), Order::Pre)
+        .filter(|node| node.kind() == "function_definition")
+        .map(|node| {
+            let mut cursor = node.walk();
+            let children: Vec<Node> = node.children(&mut cursor).collect();
+            let body = children.iter().find(|c| c.kind() == "body");
+            let body = match (body, capture) {
+                (None, _) => String::new(),
+                (Some(body), FunctionBodyCapture::Tokens) => {
+                    normalized_tokens(body, source).join(" ")
+                }
+                (Some(body), _) => {
+                    // Start from the beginning of the line to keep the first line's indent.
+                    let start = body.start_byte() - body.start_position().column;
+                    String::from_utf8_lossy(&source[start..body.end_byte()]).into_owned()
+                }
+            };
+            (node.start_byte(), body)
+        })
+        .collect()
+}
+
+/// Stores a captured body on a function node defined where it was captured from. Needs the
+/// node's span.
+pub fn attach_function_body(node: &mut VimNode, bodies: &HashMap<usize, String>) {
+    if let VimNode::Function {
+        body,
+        span: Some(span),
+        ..
+    } = node
+    {
+        *body = bodies.get(&span.byte_range.start).cloned();
+    }
+}
+
+/// A function definition's body reduced to a fingerprint of its normalized tokens, for finding
+/// copy-pasted code.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionBody {
+    pub name: String,
+    /// The whole definition, like `function! …` through `endfunction`.
+    pub span: Span,
+    pub fingerprint: u64,
+    pub token_count: usize,
+}
+
+/// Fingerprints the bodies of all function definitions in the tree.
+///
+/// Bodies are compared as token sequences, ignoring whitespace and comments, with arguments and
+/// local variables numbered by first use so renaming them doesn't hide a duplicate.
+pub fn function_bodies(tree: &Tree, source: &[u8]) -> Vec<FunctionBody> {
+    traverse(tree.walk(), Order::Pre)
+        .filter(|node| node.kind() == "function_definition")
+        .filter_map(|node| {
+            let mut cursor = node.walk();
+            let children: Vec<Node> = node.children(&mut cursor).collect();
+            let name = children
+                .iter()
+                .find(|c| c.kind() == "function_declaration")?
+                .child_by_field_name("name")?;
+            let body = children.iter().find(|c| c.kind() == "body");
+            let tokens = body
+                .map(|b| normalized_tokens(b, source))
+                .unwrap_or_default();
+            let mut fingerprint = Fingerprint::new();
+            for token in &tokens {
+                fingerprint.update(token.as_bytes());
+            }
+            Some(FunctionBody {
+                name: get_treenode_text(&name, source).to_string(),
+                span: span_of(&node),
+                fingerprint: fingerprint.finish(),
+                token_count: tokens.len(),
+            })
+        })
+        .collect()
+}
+
+fn normalized_tokens(body: &Node, source: &[u8]) -> Vec<String> {
+    let mut locals: HashMap<&str, usize> = HashMap::new();
+    let mut tokens = vec![];
+    for node in traverse(body.walk(), Order::Pre) {
+        if node.child_count() > 0 || node.kind() == "comment" {
+            continue;
+        }
+        let text = get_treenode_text(&node, source);
+        if node.kind() == "scope" && text == "l:" {
+            // `l:x` and `x` are the same variable inside a function.
+            continue;
+        }
+        if node.kind() == "identifier" && is_local(&node, source) {
+            let next = locals.len();
+            tokens.push(format!("${}", locals.entry(text).or_insert(next)));
+        } else {
+            tokens.push(text.to_string());
+        }
+    }
+    tokens
+}
+
+/// Whether an identifier names an argument or local variable rather than something global like
+/// a function.
+fn is_local(node: &Node, source: &[u8]) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    match parent.kind() {
+        "argument" => true,
+        "scoped_identifier" => parent
+            .child(0)
+            .is_some_and(|scope| get_treenode_text(&scope, source) == "l:"),
+        "call_expression" => parent.child_by_field_name("function") != Some(*node),
+        _ => !get_treenode_text(node, source).contains('#'),
+    }
+}
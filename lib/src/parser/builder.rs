@@ -0,0 +1,133 @@
+use super::{
+    DuplicateModules, EncodingFallback, ErrorPolicy, FunctionBodyCapture, VendoredPlugins,
+    VimParser, WalkOptions,
+};
+use crate::{SectionKind, ValueRedaction};
+
+/// Builder for a [VimParser] with custom settings, created by [VimParser::builder].
+///
+/// ```
+/// use vim_plugin_metadata::{SectionKind, VimParser};
+///
+/// let parser = VimParser::builder()
+///     .sections([SectionKind::Plugin, SectionKind::Autoload])
+///     .include_after(false)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct VimParserBuilder {
+    walk: WalkOptions,
+    vendored_plugins: VendoredPlugins,
+    record_parse_info: bool,
+    record_spans: bool,
+    value_redaction: Option<ValueRedaction>,
+    record_profile: bool,
+    function_body_capture: FunctionBodyCapture,
+    encoding_fallback: EncodingFallback,
+    error_policy: ErrorPolicy,
+}
+
+impl VimParserBuilder {
+    /// The runtime dirs [VimParser::parse_plugin_dir] scans, in the order their modules are
+    /// parsed. Defaults to every known section.
+    ///
+    /// The root menu.vim file is always included.
+    pub fn sections<I: IntoIterator<Item = SectionKind>>(mut self, sections: I) -> Self {
+        self.walk.sections = sections.into_iter().collect();
+        self
+    }
+
+    /// Whether to scan the same sections under after/, parsed after all the others. On by
+    /// default.
+    pub fn include_after(mut self, include_after: bool) -> Self {
+        self.walk.include_after = include_after;
+        self
+    }
+
+    /// Whether to follow symlinks to files and dirs. On by default.
+    pub fn follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.walk.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    /// Whether to match runtime dir names and file extensions case-insensitively, like
+    /// `Plugin/Foo.VIM`, as vim would on Windows or macOS filesystems. Off by default.
+    pub fn case_insensitive_paths(mut self, case_insensitive: bool) -> Self {
+        self.walk.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// How to handle the same file found at several module paths, like through symlinks. Defaults
+    /// to [DuplicateModules::FirstWins].
+    ///
+    /// Files are compared by canonical path, ignoring case with
+    /// [VimParserBuilder::case_insensitive_paths].
+    pub fn duplicate_modules(mut self, duplicate_modules: DuplicateModules) -> Self {
+        self.walk.duplicate_modules = duplicate_modules;
+        self
+    }
+
+    /// See [VimParser::set_vendored_plugins].
+    pub fn vendored_plugins(mut self, vendored_plugins: VendoredPlugins) -> Self {
+        self.vendored_plugins = vendored_plugins;
+        self
+    }
+
+    /// See [VimParser::set_record_parse_info].
+    pub fn record_parse_info(mut self, record_parse_info: bool) -> Self {
+        self.record_parse_info = record_parse_info;
+        self
+    }
+
+    /// See [VimParser::set_record_spans].
+    pub fn record_spans(mut self, record_spans: bool) -> Self {
+        self.record_spans = record_spans;
+        self
+    }
+
+    /// See [VimParser::set_value_redaction].
+    pub fn value_redaction(mut self, value_redaction: ValueRedaction) -> Self {
+        self.value_redaction = Some(value_redaction);
+        self
+    }
+
+    /// See [VimParser::set_record_profile].
+    pub fn record_profile(mut self, record_profile: bool) -> Self {
+        self.record_profile = record_profile;
+        self
+    }
+
+    /// See [VimParser::set_function_body_capture].
+    pub fn function_body_capture(mut self, function_body_capture: FunctionBodyCapture) -> Self {
+        self.function_body_capture = function_body_capture;
+        self
+    }
+
+    /// See [VimParser::set_encoding_fallback].
+    pub fn encoding_fallback(mut self, encoding_fallback: EncodingFallback) -> Self {
+        self.encoding_fallback = encoding_fallback;
+        self
+    }
+
+    /// See [VimParser::set_error_policy].
+    pub fn error_policy(mut self, error_policy: ErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
+
+    pub fn build(self) -> crate::Result<VimParser> {
+        let mut parser = VimParser::new()?;
+        parser.walk = self.walk;
+        parser
+            .set_vendored_plugins(self.vendored_plugins)
+            .set_record_parse_info(self.record_parse_info)
+            .set_record_spans(self.record_spans)
+            .set_value_redaction(self.value_redaction)
+            .set_record_profile(self.record_profile)
+            .set_function_body_capture(self.function_body_capture)
+            .set_encoding_fallback(self.encoding_fallback)
+            .set_error_policy(self.error_policy);
+        Ok(parser)
+    }
+}
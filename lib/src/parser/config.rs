@@ -0,0 +1,328 @@
+use super::treenodes::{get_treenode_text, unquote_string_literal};
+use crate::{ConfigAutocmd, ConfigMapping, ConfigSetting, PluginDeclaration, PluginManager};
+use regex::Regex;
+use std::sync::OnceLock;
+use tree_sitter::{Node, Tree};
+use tree_sitter_traversal::{traverse, Order};
+
+/// Config statements found anywhere in a tree, including nested inside blocks like `if`.
+#[derive(Default)]
+pub struct ConfigStatements {
+    pub settings: Vec<ConfigSetting>,
+    pub mappings: Vec<ConfigMapping>,
+    pub autocmds: Vec<ConfigAutocmd>,
+    pub plugin_declarations: Vec<PluginDeclaration>,
+}
+
+pub fn extract_config_statements(tree: &Tree, source: &[u8]) -> ConfigStatements {
+    let mut statements = ConfigStatements::default();
+    let mut current_augroup: Option<String> = None;
+    // Pre-order traversal visits statements in source order, needed to track augroup scope.
+    for node in traverse(tree.walk(), Order::Pre) {
+        match node.kind() {
+            "set_statement" | "setlocal_statement" => {
+                let command = node.child(0).map(|c| c.kind()).unwrap_or_default();
+                let mut cursor = node.walk();
+                for item in node.named_children(&mut cursor) {
+                    if let Some(setting) = setting_from_item(command, &item, source) {
+                        statements.settings.push(setting);
+                    }
+                }
+            }
+            "let_statement" => {
+                if let Some(setting) = setting_from_let(&node, source) {
+                    statements.settings.push(setting);
+                }
+            }
+            "map_statement" => {
+                if let Some(mapping) = mapping_from_node(&node, source) {
+                    statements.mappings.push(mapping);
+                }
+            }
+            "augroup_statement" => {
+                current_augroup = node
+                    .named_child(0)
+                    .map(|n| get_treenode_text(&n, source))
+                    .filter(|name| !name.eq_ignore_ascii_case("END"))
+                    .map(str::to_string);
+            }
+            "autocmd_statement" => {
+                if let Some(autocmd) = autocmd_from_node(&node, source, &current_augroup) {
+                    statements.autocmds.push(autocmd);
+                }
+            }
+            "user_command" | "unknown_builtin_statement" => {
+                if let Some(declaration) = plugin_declaration_from_node(&node, source) {
+                    statements.plugin_declarations.push(declaration);
+                }
+            }
+            "call_expression" => {
+                if let Some(declaration) = plugin_declaration_from_call(&node, source) {
+                    statements.plugin_declarations.push(declaration);
+                }
+            }
+            "lua_statement" => {
+                statements
+                    .plugin_declarations
+                    .extend(packer_declarations(get_treenode_text(&node, source)));
+            }
+            _ => {}
+        }
+    }
+    statements
+}
+
+pub(crate) fn setting_from_item(
+    command: &str,
+    item: &Node,
+    source: &[u8],
+) -> Option<ConfigSetting> {
+    if item.kind() != "set_item" {
+        return None;
+    }
+    let option = item.child_by_field_name("option")?;
+    let name_node = if option.kind() == "option_name" {
+        option
+    } else {
+        // no_option or inv_option wrapping the option_name.
+        option.named_child(0)?
+    };
+    Some(ConfigSetting {
+        command: command.to_string(),
+        name: get_treenode_text(&name_node, source).to_string(),
+        item: get_treenode_text(item, source).to_string(),
+        value: item
+            .child_by_field_name("value")
+            .map(|v| get_treenode_text(&v, source).to_string()),
+    })
+}
+
+fn setting_from_let(node: &Node, source: &[u8]) -> Option<ConfigSetting> {
+    // Matches nodes like: (let_statement (option (option_name)) "=" VALUE)
+    let mut cursor = node.walk();
+    let children: Vec<_> = node.children(&mut cursor).collect();
+    match children[..] {
+        [_, lhs, op, rhs, ..] if lhs.kind() == "option" && op.kind() == "=" => {
+            let name_node = lhs.named_child(0)?;
+            Some(ConfigSetting {
+                command: "let".to_string(),
+                name: get_treenode_text(&name_node, source).to_string(),
+                item: get_treenode_text(&lhs, source).to_string(),
+                value: Some(get_treenode_text(&rhs, source).to_string()),
+            })
+        }
+        _ => None,
+    }
+}
+
+pub(crate) fn mapping_from_node(node: &Node, source: &[u8]) -> Option<ConfigMapping> {
+    let command = node.child_by_field_name("cmd")?;
+    let lhs = node.child_by_field_name("lhs")?;
+    let rhs = node.child_by_field_name("rhs");
+    if lhs.start_byte() == command.end_byte() && get_treenode_text(&lhs, source) == "!" {
+        // The grammar parses the bang of `map! x y` as the lhs, so split the real lhs back
+        // out of the rhs.
+        let rhs = rhs.map(|rhs| get_treenode_text(&rhs, source))?;
+        let (lhs, rhs) = rhs.split_once(char::is_whitespace).unwrap_or((rhs, ""));
+        return Some(ConfigMapping {
+            command: format!("{}!", get_treenode_text(&command, source)),
+            arguments: vec![],
+            lhs: lhs.to_string(),
+            rhs: rhs.trim_start().to_string(),
+        });
+    }
+    let mut cursor = node.walk();
+    let arguments = node
+        .children(&mut cursor)
+        .filter(|c| c.start_byte() >= command.end_byte() && c.end_byte() <= lhs.start_byte())
+        .map(|c| get_treenode_text(&c, source).to_string())
+        .collect();
+    Some(ConfigMapping {
+        command: get_treenode_text(&command, source).to_string(),
+        arguments,
+        lhs: get_treenode_text(&lhs, source).to_string(),
+        rhs: rhs
+            .map(|rhs| get_treenode_text(&rhs, source).to_string())
+            .unwrap_or_default(),
+    })
+}
+
+/// The mode letters of a map statement (empty for `:map`/`:noremap` or `!` for `:map!`) and
+/// whether it's one of the `noremap` variants.
+pub(crate) fn map_mode(node: &Node, mapping: &ConfigMapping) -> Option<(String, bool)> {
+    // The command node's kind is the full command name, even when abbreviated like `nn`.
+    let command = node.child_by_field_name("cmd")?.kind();
+    let (mode, noremap) = match command.strip_suffix("noremap") {
+        Some(mode) => (mode, true),
+        None => (command.strip_suffix("map")?, false),
+    };
+    let mode = if mode.is_empty() && mapping.command.ends_with('!') {
+        "!"
+    } else {
+        mode
+    };
+    Some((mode.to_string(), noremap))
+}
+
+pub(crate) fn autocmd_from_node(
+    node: &Node,
+    source: &[u8],
+    current_augroup: &Option<String>,
+) -> Option<ConfigAutocmd> {
+    let mut cursor = node.walk();
+    let mut group = current_augroup.clone();
+    let mut events = vec![];
+    let mut patterns = vec![];
+    for child in node.named_children(&mut cursor) {
+        match child.kind() {
+            "augroup_name" => group = Some(get_treenode_text(&child, source).to_string()),
+            "au_event_list" => {
+                let mut events_cursor = child.walk();
+                events.extend(
+                    child
+                        .named_children(&mut events_cursor)
+                        .map(|e| get_treenode_text(&e, source).to_string()),
+                );
+            }
+            "pattern" => patterns.push(get_treenode_text(&child, source).to_string()),
+            _ => {}
+        }
+    }
+    // Skip `autocmd!` and other forms that remove or list autocmds instead of defining one.
+    let command = node.child_by_field_name("command")?;
+    if events.is_empty() {
+        return None;
+    }
+    Some(ConfigAutocmd {
+        group,
+        events,
+        patterns,
+        command: get_treenode_text(&command, source).to_string(),
+    })
+}
+
+fn plugin_declaration_from_node(node: &Node, source: &[u8]) -> Option<PluginDeclaration> {
+    let command_name = node.named_child(0)?;
+    let manager = match get_treenode_text(&command_name, source) {
+        "Plug" => PluginManager::VimPlug,
+        "Plugin" | "Bundle" => PluginManager::Vundle,
+        "NeoBundle" => PluginManager::NeoBundle,
+        "packadd" => PluginManager::Packadd,
+        _ => return None,
+    };
+    let mut cursor = node.walk();
+    let arguments = node
+        .named_children(&mut cursor)
+        .find(|c| c.kind() == "arguments")?;
+    let first_arg = arguments.named_child(0)?;
+    let first_arg_text = get_treenode_text(&first_arg, source);
+    let name = match first_arg.named_child(0) {
+        Some(literal) if literal.kind() == "string_literal" => {
+            unquote_string_literal(get_treenode_text(&literal, source))
+        }
+        _ => first_arg_text.to_string(),
+    };
+    // Command arguments aren't parsed as expressions, so pick apart any options dict from the
+    // raw text following the name.
+    let options_text = source
+        .get(first_arg.end_byte()..arguments.end_byte())
+        .map(String::from_utf8_lossy)
+        .unwrap_or_default();
+    Some(PluginDeclaration {
+        manager,
+        name,
+        options: vim_dict_options(&options_text),
+    })
+}
+
+/// Handles function-style declarations like `call dein#add('foo/bar', {'rev': 'main'})`.
+fn plugin_declaration_from_call(node: &Node, source: &[u8]) -> Option<PluginDeclaration> {
+    let function = node.child_by_field_name("function")?;
+    let manager = match get_treenode_text(&function, source) {
+        "dein#add" => PluginManager::Dein,
+        "minpac#add" => PluginManager::Minpac,
+        _ => return None,
+    };
+    let mut cursor = node.walk();
+    let args: Vec<_> = node
+        .named_children(&mut cursor)
+        .filter(|c| c.id() != function.id())
+        .collect();
+    let name_node = args.first().filter(|n| n.kind() == "string_literal")?;
+    let mut options = vec![];
+    if let Some(dict) = args.get(1).filter(|n| n.kind() == "dictionnary") {
+        let mut dict_cursor = dict.walk();
+        for entry in dict.named_children(&mut dict_cursor) {
+            let (Some(key), Some(value)) = (
+                entry.child_by_field_name("key"),
+                entry.child_by_field_name("value"),
+            ) else {
+                continue;
+            };
+            let key = get_treenode_text(&key, source);
+            let key = if key.starts_with(['\'', '"']) {
+                unquote_string_literal(key)
+            } else {
+                key.to_string()
+            };
+            options.push((key, get_treenode_text(&value, source).to_string()));
+        }
+    }
+    Some(PluginDeclaration {
+        manager,
+        name: unquote_string_literal(get_treenode_text(name_node, source)),
+        options,
+    })
+}
+
+/// Finds packer.nvim `use 'foo/bar'` or `use {'foo/bar', ft = 'lua'}` calls in lua code.
+fn packer_declarations(lua_code: &str) -> Vec<PluginDeclaration> {
+    static PACKER_USE: OnceLock<Regex> = OnceLock::new();
+    let packer_use = PACKER_USE.get_or_init(|| {
+        Regex::new(
+            r#"(?m)^\s*use\s*\(?\s*(?:\{\s*['"]([^'"]+)['"]((?:[^{}]|\{[^{}]*\})*)\}|['"]([^'"]+)['"])"#,
+        )
+        .unwrap()
+    });
+    packer_use
+        .captures_iter(lua_code)
+        .filter_map(|c| {
+            let name = c.get(1).or_else(|| c.get(3))?.as_str().to_string();
+            let options = c
+                .get(2)
+                .map(|table| lua_table_options(table.as_str()))
+                .unwrap_or_default();
+            Some(PluginDeclaration {
+                manager: PluginManager::Packer,
+                name,
+                options,
+            })
+        })
+        .collect()
+}
+
+/// Option values: strings, lists, tables/dicts (not nested) or bare words like `v:true`.
+const VALUE_PATTERN: &str = r#"'[^']*'|"[^"]*"|\[[^\]]*\]|\{[^}]*\}|[\w#.:-]+"#;
+
+/// Finds `'key': value` entries in the text of a vim dict literal.
+fn vim_dict_options(text: &str) -> Vec<(String, String)> {
+    static ENTRY: OnceLock<Regex> = OnceLock::new();
+    let entry = ENTRY
+        .get_or_init(|| Regex::new(&format!(r#"['"](\w+)['"]\s*:\s*({VALUE_PATTERN})"#)).unwrap());
+    options_matching(entry, text)
+}
+
+/// Finds `key = value` entries in the text of a lua table.
+fn lua_table_options(text: &str) -> Vec<(String, String)> {
+    static ENTRY: OnceLock<Regex> = OnceLock::new();
+    let entry =
+        ENTRY.get_or_init(|| Regex::new(&format!(r"(\w+)\s*=\s*({VALUE_PATTERN})")).unwrap());
+    options_matching(entry, text)
+}
+
+fn options_matching(entry: &Regex, text: &str) -> Vec<(String, String)> {
+    entry
+        .captures_iter(text)
+        .map(|c| (c[1].to_string(), c[2].to_string()))
+        .collect()
+}
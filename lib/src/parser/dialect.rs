@@ -0,0 +1,115 @@
+use super::vim9::is_vim9script;
+use serde::{Deserialize, Serialize};
+
+/// The kind of code in a file, as guessed by [detect_dialect].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Dialect {
+    /// Legacy vimscript.
+    Vim,
+    /// Vim9 script, starting with `vim9script`.
+    Vim9,
+    Lua,
+    /// Legacy vimscript that embeds another dialect, like `lua << EOF` blocks or Vim9 `def`
+    /// functions.
+    Mixed,
+}
+
+/// Guesses the dialect of some code from its contents alone, for routing files without a
+/// telling extension (like vimrc vs. init.lua contents, or code from stdin).
+///
+/// Code with no recognizable statements is assumed to be legacy vimscript.
+///
+/// ```
+/// use vim_plugin_metadata::{detect_dialect, Dialect};
+///
+/// assert_eq!(detect_dialect("vim9script\nvar x = 1\n"), Dialect::Vim9);
+/// assert_eq!(detect_dialect("local M = {}\nreturn M\n"), Dialect::Lua);
+/// ```
+pub fn detect_dialect(code: &str) -> Dialect {
+    if is_vim9script(code) {
+        return Dialect::Vim9;
+    }
+    let mut vim_score = 0;
+    let mut lua_score = 0;
+    let mut embeds_other_dialect = false;
+    // The end marker of a `lua << EOF` heredoc being skipped, if any.
+    let mut heredoc_end: Option<&str> = None;
+    for line in code.lines().map(str::trim) {
+        if let Some(end) = heredoc_end {
+            if line == end {
+                heredoc_end = None;
+            }
+            continue;
+        }
+        let first_word = line
+            .split(|c: char| !(c.is_alphanumeric() || c == '!' || c == '_'))
+            .next()
+            .unwrap_or_default();
+        if first_word == "lua" {
+            embeds_other_dialect = true;
+            vim_score += 1;
+            if let Some((_, marker)) = line.split_once("<<") {
+                // Skip an optional `trim` before the end marker.
+                heredoc_end = marker
+                    .split_whitespace()
+                    .find(|w| *w != "trim")
+                    .or(Some("."));
+            }
+            continue;
+        }
+        if line.starts_with("--") || line.starts_with("require") || line.ends_with(" then") {
+            lua_score += 1;
+            continue;
+        }
+        match first_word {
+            "local" | "elseif" | "until" | "repeat" => lua_score += 1,
+            "end" if line == "end" || line.starts_with("end)") || line.starts_with("end,") => {
+                lua_score += 1
+            }
+            "def" | "def!" | "enddef" => {
+                embeds_other_dialect = true;
+                vim_score += 1;
+            }
+            "let" | "set" | "setlocal" | "call" | "endif" | "endfunction" | "endfunc"
+            | "endfor" | "endwhile" | "augroup" | "autocmd" | "command" | "command!"
+            | "execute" | "exe" | "nnoremap" | "noremap" | "inoremap" | "vnoremap" | "xnoremap"
+            | "nmap" | "imap" | "xmap" | "map" | "syntax" | "highlight" | "hi" => vim_score += 1,
+            _ if line.starts_with('"') => vim_score += 1,
+            _ => {}
+        }
+    }
+    if lua_score > vim_score {
+        Dialect::Lua
+    } else if embeds_other_dialect {
+        Dialect::Mixed
+    } else {
+        Dialect::Vim
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn detect_dialects() {
+        assert_eq!(
+            detect_dialect("\" Legacy.\nlet g:x = 1\nfunction! Foo()\nendfunction\n"),
+            Dialect::Vim
+        );
+        assert_eq!(
+            detect_dialect(
+                "-- Config.\nlocal opts = require('foo').opts\nif opts.x then\n  vim.g.x = 1\nend\n"
+            ),
+            Dialect::Lua
+        );
+        assert_eq!(
+            detect_dialect(
+                "set nocompatible\nlua << EOF\nlocal x = 1\nif x then\nend\nEOF\nlet g:y = 2\n"
+            ),
+            Dialect::Mixed
+        );
+        assert_eq!(detect_dialect(""), Dialect::Vim);
+    }
+}
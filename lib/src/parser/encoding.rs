@@ -0,0 +1,167 @@
+use encoding_rs::{DecoderResult, Encoding, UTF_8, WINDOWS_1252};
+use regex::bytes::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::OnceLock;
+use std::{fs, io, str};
+
+/// How [crate::VimParser] reads files that don't decode cleanly, either from the encoding their
+/// `:scriptencoding` declares or as UTF-8 if they don't declare one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EncodingFallback {
+    /// Replace invalid bytes with U+FFFD.
+    #[default]
+    Lossy,
+    /// Read the file as latin1 instead, like vim's default 'fileencodings'. Suits old plugins
+    /// written before UTF-8 was common.
+    Latin1,
+    /// Fail with an error, for callers that would rather skip the file than guess.
+    Strict,
+}
+
+/// Reads a vimscript file, decoding it from the encoding its `:scriptencoding` declares.
+///
+/// Undeclared or unknown encodings are read as UTF-8, replacing any invalid bytes.
+pub fn read_source(path: &Path) -> io::Result<String> {
+    decode_source_with(&fs::read(path)?, EncodingFallback::Lossy)
+}
+
+/// Decodes a vimscript file's contents like [read_source], handling text that doesn't decode per
+/// `fallback`.
+pub fn decode_source_with(bytes: &[u8], fallback: EncodingFallback) -> io::Result<String> {
+    let encoding = source_encoding(bytes);
+    let (decoded, _, had_errors) = encoding.decode(bytes);
+    match fallback {
+        _ if !had_errors => Ok(decoded.into_owned()),
+        EncodingFallback::Lossy => Ok(decoded.into_owned()),
+        EncodingFallback::Latin1 => Ok(WINDOWS_1252.decode(bytes).0.into_owned()),
+        EncodingFallback::Strict => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("File isn't valid {} text", encoding.name()),
+        )),
+    }
+}
+
+/// The (row, column) of the first byte of a file that doesn't decode, if any.
+pub fn invalid_position(bytes: &[u8]) -> Option<(usize, usize)> {
+    let mut decoder = source_encoding(bytes).new_decoder();
+    let mut decoded =
+        String::with_capacity(decoder.max_utf8_buffer_length_without_replacement(bytes.len())?);
+    let offset = match decoder.decode_to_string_without_replacement(bytes, &mut decoded, true) {
+        (DecoderResult::Malformed(bad_len, unread_len), read) => {
+            read - bad_len as usize - unread_len as usize
+        }
+        _ => return None,
+    };
+    let line_start = bytes[..offset]
+        .iter()
+        .rposition(|&byte| byte == b'\n')
+        .map_or(0, |newline| newline + 1);
+    let row = bytes[..line_start]
+        .iter()
+        .filter(|&&byte| byte == b'\n')
+        .count();
+    Some((row, offset - line_start))
+}
+
+/// The encoding a file declares with `:scriptencoding`, or UTF-8 if it doesn't declare a known
+/// one.
+fn source_encoding(bytes: &[u8]) -> &'static Encoding {
+    script_encoding(bytes)
+        .and_then(|name| lookup_encoding(&name))
+        .unwrap_or(UTF_8)
+}
+
+/// Finds the encoding named by the first `:scriptencoding` statement, as written.
+pub fn script_encoding(source: &[u8]) -> Option<String> {
+    static SCRIPTENCODING: OnceLock<Regex> = OnceLock::new();
+    let pattern = SCRIPTENCODING
+        .get_or_init(|| Regex::new(r"(?m)^[ \t:]*scripte(?:ncoding)?[ \t]+([\w.:-]+)").unwrap());
+    let name = pattern.captures(source)?.get(1)?.as_bytes();
+    str::from_utf8(name).ok().map(str::to_string)
+}
+
+/// Maps a vim 'encoding' name (see `:help encoding-names`) to a decoder.
+fn lookup_encoding(name: &str) -> Option<&'static Encoding> {
+    let name = name.to_ascii_lowercase();
+    let label = match name.as_str() {
+        "utf8" => "utf-8",
+        "latin1" | "ansi" => "windows-1252",
+        "cp932" | "sjis" => "shift_jis",
+        "cp936" | "euc-cn" => "gbk",
+        "cp949" | "euc-kr" => "euc-kr",
+        "cp950" => "big5",
+        "utf-16le" | "ucs-2le" => "utf-16le",
+        "utf-16" | "ucs-2" => "utf-16be",
+        name => match name.strip_prefix("cp") {
+            Some(codepage) => return Encoding::for_label(format!("windows-{codepage}").as_bytes()),
+            None => name,
+        },
+    };
+    Encoding::for_label(label.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn decode_source_scriptencoding() {
+        assert_eq!(
+            decode_source_with(
+                b"scriptencoding latin1\nlet s:sep = '\xbb'\n",
+                EncodingFallback::Lossy
+            )
+            .unwrap(),
+            "scriptencoding latin1\nlet s:sep = '\u{bb}'\n"
+        );
+        assert_eq!(
+            decode_source_with(
+                b"  scripte cp1251\necho '\xc4\xe0'\n",
+                EncodingFallback::Lossy
+            )
+            .unwrap(),
+            "  scripte cp1251\necho '\u{414}\u{430}'\n"
+        );
+        // Undeclared encodings default to UTF-8.
+        assert_eq!(
+            decode_source_with("echo '\u{bb}'\n".as_bytes(), EncodingFallback::Lossy).unwrap(),
+            "echo '\u{bb}'\n"
+        );
+        assert_eq!(
+            script_encoding(b"\" scriptencoding is below\nscriptencoding utf-8\n"),
+            Some("utf-8".into())
+        );
+    }
+
+    #[test]
+    fn decode_source_with_fallbacks() {
+        let latin1 = b"\" Caf\xe9\nlet s:sep = '\xbb'\n";
+        assert_eq!(
+            decode_source_with(latin1, EncodingFallback::Lossy).unwrap(),
+            "\" Caf\u{fffd}\nlet s:sep = '\u{fffd}'\n"
+        );
+        assert_eq!(
+            decode_source_with(latin1, EncodingFallback::Latin1).unwrap(),
+            "\" Caf\u{e9}\nlet s:sep = '\u{bb}'\n"
+        );
+        assert_eq!(
+            decode_source_with(latin1, EncodingFallback::Strict)
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert_eq!(invalid_position(latin1), Some((0, 5)));
+        assert_eq!(
+            invalid_position(b"echo 1\nlet s:sep = '\xbb'\n"),
+            Some((1, 13))
+        );
+        assert_eq!(invalid_position("echo '\u{bb}'\n".as_bytes()), None);
+        // Valid UTF-8 is never reinterpreted.
+        assert_eq!(
+            decode_source_with("echo '\u{bb}'\n".as_bytes(), EncodingFallback::Latin1).unwrap(),
+            "echo '\u{bb}'\n"
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use super::patterns::{captures, NodePatterns};
+use super::span_of;
+use super::treenodes::{
+    get_treenode_text, let_assignments, unquote_string_literal, TreeNodeMetadata,
+};
+use crate::{Span, VimNode};
+use std::borrow::Cow;
+use tree_sitter::Node;
+
+/// A definition or doc comment found by [crate::VimParser::parse_module_events], with names and
+/// values borrowed from the source code where possible.
+///
+/// Statements inside a function's body (like its local variables or nested functions) are
+/// emitted between its [ParseEvent::FunctionStart] and [ParseEvent::FunctionEnd].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseEvent<'a> {
+    /// A `""` doc comment block. Doesn't say which definition it documents, if any.
+    DocComment {
+        doc: String,
+        span: Span,
+    },
+    FunctionStart {
+        name: &'a str,
+        args: Vec<&'a str>,
+        span: Span,
+    },
+    FunctionEnd {
+        name: &'a str,
+    },
+    Command {
+        name: &'a str,
+        span: Span,
+    },
+    Variable {
+        name: &'a str,
+        /// The assigned value, like `'a'` or `[1, 2][0]` for destructured lists.
+        value: Cow<'a, str>,
+        span: Span,
+    },
+    Flag {
+        name: String,
+        default_value_token: Option<&'a str>,
+        span: Span,
+    },
+}
+
+/// Walks the statements under `parent` in source order, passing events for each to `on_event`.
+pub(super) fn emit_events<'a, F: FnMut(ParseEvent<'a>)>(
+    parent: Node,
+    code: &'a str,
+    on_event: &mut F,
+) {
+    let source = code.as_bytes();
+    let mut cursor = parent.walk();
+    let mut statements = parent.named_children(&mut cursor).peekable();
+    while let Some(treenode) = statements.next() {
+        let span = span_of(&treenode);
+        match treenode.kind() {
+            "comment" => {
+                let mut comment = TreeNodeMetadata::from((treenode, source));
+                comment.absorb_comment_lines(&mut statements);
+                let span = comment.span();
+                for node in Vec::<VimNode>::from(comment) {
+                    if let VimNode::StandaloneDocComment { doc, .. } = node {
+                        on_event(ParseEvent::DocComment {
+                            doc,
+                            span: span.clone(),
+                        });
+                    }
+                }
+            }
+            "function_definition" => {
+                let mut name = None;
+                let mut args = vec![];
+                for (capture, node) in captures(&NodePatterns::get().function, treenode, source) {
+                    match capture {
+                        "name" => name = Some(get_treenode_text(&node, source)),
+                        "param" => args.push(get_treenode_text(&node, source)),
+                        _ => {}
+                    }
+                }
+                let Some(name) = name else {
+                    continue;
+                };
+                on_event(ParseEvent::FunctionStart { name, args, span });
+                let mut body_cursor = treenode.walk();
+                let body = treenode
+                    .named_children(&mut body_cursor)
+                    .find(|child| child.kind() == "body");
+                if let Some(body) = body {
+                    emit_events(body, code, on_event);
+                }
+                on_event(ParseEvent::FunctionEnd { name });
+            }
+            "command_statement" => {
+                let name = captures(&NodePatterns::get().command, treenode, source)
+                    .into_iter()
+                    .find(|(capture, _)| *capture == "name");
+                if let Some((_, name)) = name {
+                    let name = get_treenode_text(&name, source);
+                    on_event(ParseEvent::Command { name, span });
+                }
+            }
+            "let_statement" => {
+                for (name, value) in let_assignments(treenode, source) {
+                    on_event(ParseEvent::Variable {
+                        name,
+                        value,
+                        span: span.clone(),
+                    });
+                }
+            }
+            "call_statement" => {
+                let mut name = None;
+                let mut default_value_token = None;
+                for (capture, node) in captures(&NodePatterns::get().flag, treenode, source) {
+                    let text = get_treenode_text(&node, source);
+                    match capture {
+                        "name" => name = Some(unquote_string_literal(text)),
+                        "default" => default_value_token = Some(text),
+                        _ => {}
+                    }
+                }
+                if let Some(name) = name {
+                    on_event(ParseEvent::Flag {
+                        name,
+                        default_value_token,
+                        span,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VimParser;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_module_events_in_order() {
+        let code = r#"
+"" Frobs things.
+function! foo#Frob(a, ...) abort
+  let l:x = a:a
+endfunction
+let [g:foo_a, g:foo_b] = [1, 2]
+"#;
+        let mut events = vec![];
+        VimParser::new()
+            .unwrap()
+            .parse_module_events(code, |event| events.push(event))
+            .unwrap();
+        let summaries: Vec<_> = events
+            .into_iter()
+            .map(|event| match event {
+                ParseEvent::DocComment { doc, .. } => format!("doc {doc}"),
+                ParseEvent::FunctionStart { name, args, .. } => {
+                    format!("start {name}({})", args.join(", "))
+                }
+                ParseEvent::FunctionEnd { name } => format!("end {name}"),
+                ParseEvent::Variable { name, value, .. } => format!("let {name} = {value}"),
+                event => format!("{event:?}"),
+            })
+            .collect();
+        assert_eq!(
+            summaries,
+            vec![
+                "doc Frobs things.",
+                "start foo#Frob(a, ...)",
+                "let l:x = a:a",
+                "end foo#Frob",
+                "let g:foo_a = 1",
+                "let g:foo_b = 2",
+            ]
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use crate::data::ModuleHeader;
+use regex::Regex;
+use std::sync::OnceLock;
+
+pub(super) fn header_field_pattern() -> &'static Regex {
+    static HEADER_FIELD: OnceLock<Regex> = OnceLock::new();
+    HEADER_FIELD.get_or_init(|| {
+        Regex::new(
+            r"(?i)^\s*(author|maintainer|license|version|last (?:change|modified))\s*:\s*(.*?)\s*$",
+        )
+        .unwrap()
+    })
+}
+
+fn license_pattern() -> &'static Regex {
+    static LICENSE: OnceLock<Regex> = OnceLock::new();
+    LICENSE.get_or_init(|| {
+        Regex::new(
+            r"(?i)\bcopyright\b|licensed under|permission is hereby granted|spdx-license-identifier|gnu general public license|without warranties or conditions|provided .as is.",
+        )
+        .unwrap()
+    })
+}
+
+/// Whether a comment block is boilerplate license text rather than real documentation.
+pub fn is_license_text(text: &str) -> bool {
+    license_pattern().is_match(text)
+}
+
+/// Parses `Key: value` header fields from the comment lines at the top of a module, stopping at
+/// the first line of code.
+///
+/// Also picks out the first comment block that looks like license text (see
+/// [is_license_text]). Blocks are separated by blank lines or `""` doc comment starts.
+pub fn parse_header(code: &str) -> ModuleHeader {
+    let mut header = ModuleHeader::default();
+    let mut blocks: Vec<Vec<&str>> = vec![vec![]];
+    for line in code.lines() {
+        let line = line.trim_start();
+        if line.is_empty() || line.trim_end() == "\"\"" {
+            blocks.push(vec![]);
+            continue;
+        }
+        let Some(comment) = line.strip_prefix('"') else {
+            break;
+        };
+        let text = comment.trim_start_matches('"');
+        blocks
+            .last_mut()
+            .unwrap()
+            .push(text.strip_prefix(' ').unwrap_or(text).trim_end());
+        let Some(captures) = header_field_pattern().captures(comment.trim_start_matches('"'))
+        else {
+            continue;
+        };
+        let value = &captures[2];
+        if value.is_empty() {
+            continue;
+        }
+        let field = match captures[1].to_ascii_lowercase().as_str() {
+            "author" => &mut header.author,
+            "maintainer" => &mut header.maintainer,
+            "license" => &mut header.license,
+            "version" => &mut header.version,
+            _ => &mut header.last_change,
+        };
+        field.get_or_insert_with(|| value.to_string());
+    }
+    header.license_text = blocks
+        .into_iter()
+        .map(|lines| lines.join("\n").trim_matches('\n').to_string())
+        .find(|text| is_license_text(text));
+    header
+}
+
+/// Removes header field lines from a module doc, returning None if nothing else is left.
+pub fn strip_header_fields(doc: &str) -> Option<String> {
+    let lines: Vec<&str> = doc
+        .lines()
+        .filter(|line| !header_field_pattern().is_match(line))
+        .collect();
+    let doc = lines.join("\n");
+    let doc = doc.trim_matches('\n');
+    (!doc.is_empty()).then(|| doc.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_header_fields() {
+        let code = r#"
+" Vim plugin for frobbing.
+" Maintainer:  Jane Doe <jane@example.com>
+" Last Change: 2024 Sep 09
+" license: MIT
+" Version:
+""
+" Author: John Roe
+
+let g:loaded_frob = 1
+" Author: Too late
+"#;
+        assert_eq!(
+            parse_header(code),
+            ModuleHeader {
+                author: Some("John Roe".into()),
+                maintainer: Some("Jane Doe <jane@example.com>".into()),
+                license: Some("MIT".into()),
+                version: None,
+                last_change: Some("2024 Sep 09".into()),
+                license_text: None,
+            }
+        );
+        assert_eq!(
+            strip_header_fields("Frobs things.\nAuthor: Jane\n\nMore info."),
+            Some("Frobs things.\n\nMore info.".into())
+        );
+        assert_eq!(strip_header_fields("Author: Jane"), None);
+    }
+
+    #[test]
+    fn parse_header_license_text() {
+        let code = r#"
+" Copyright 2024 Jane Doe
+"
+" Licensed under the Apache License, Version 2.0.
+
+""
+" Frobs things.
+"#;
+        assert_eq!(
+            parse_header(code).license_text.as_deref(),
+            Some("Copyright 2024 Jane Doe\n\nLicensed under the Apache License, Version 2.0.")
+        );
+        assert!(!is_license_text("Frobs things under the hood."));
+    }
+}
@@ -0,0 +1,169 @@
+//! Parsing for vim help files (see `:help help-writing`).
+
+use crate::data::{HelpEntry, HelpSection, VimHelpDoc};
+use crate::parser::read_source;
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Parses every doc/*.txt help file of the plugin at `root`, sorted by path.
+///
+/// Unreadable files are skipped.
+pub(crate) fn read_help_docs(root: &Path) -> Vec<VimHelpDoc> {
+    let Ok(entries) = fs::read_dir(root.join("doc")) else {
+        return vec![];
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let help = read_source(&path).ok()?;
+            Some(VimHelpDoc {
+                path: path.strip_prefix(root).ok()?.to_owned(),
+                ..parse_help(&help)
+            })
+        })
+        .collect()
+}
+
+/// Parses the sections and tagged entries of a help file.
+pub(crate) fn parse_help(help: &str) -> VimHelpDoc {
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    // Tag definitions can't contain spaces, `|` or `*`, and are delimited by whitespace.
+    let tag = TAG.get_or_init(|| Regex::new(r"(?:^|\s)\*([^\s*|]+)\*(?:\s|$)").unwrap());
+
+    let mut doc = VimHelpDoc::default();
+    let mut entry: Option<(HelpEntry, Vec<String>)> = None;
+    let mut after_separator = false;
+    let mut in_example = false;
+    let finish = |entry: Option<(HelpEntry, Vec<String>)>, doc: &mut VimHelpDoc| {
+        if let Some((mut entry, lines)) = entry {
+            entry.text = lines.join("\n").trim_end().to_string();
+            doc.entries.push(entry);
+        }
+    };
+    for (row, line) in help.lines().enumerate() {
+        if in_example {
+            // Example blocks end at a `<` or any unindented line.
+            if line.starts_with('<') || !(line.is_empty() || line.starts_with([' ', '\t'])) {
+                in_example = false;
+            } else {
+                if let Some((_, lines)) = &mut entry {
+                    lines.push(line.to_string());
+                }
+                continue;
+            }
+        }
+        if line.len() >= 10 && line.chars().all(|c| c == '=') {
+            finish(entry.take(), &mut doc);
+            after_separator = true;
+            continue;
+        }
+        let tags: Vec<String> = tag
+            .captures_iter(line)
+            .map(|captures| captures[1].to_string())
+            .collect();
+        let text = tag.replace_all(line, " ");
+        let text = text.trim_end();
+        if after_separator && !line.trim().is_empty() {
+            after_separator = false;
+            doc.sections.push(HelpSection {
+                title: text.trim().to_string(),
+                tags: tags.clone(),
+                line: row,
+            });
+        }
+        if !tags.is_empty() {
+            match &mut entry {
+                // Consecutive tag lines define aliases for the same entry.
+                Some((current, lines)) if lines.iter().all(|l| l.trim().is_empty()) => {
+                    current.tags.extend(tags);
+                    lines.clear();
+                }
+                _ => {
+                    finish(entry.take(), &mut doc);
+                    entry = Some((
+                        HelpEntry {
+                            tags,
+                            text: String::new(),
+                            line: row,
+                        },
+                        vec![],
+                    ));
+                }
+            }
+            if !text.trim().is_empty() {
+                entry
+                    .as_mut()
+                    .unwrap()
+                    .1
+                    .push(text.trim_start().to_string());
+            }
+        } else if let Some((_, lines)) = &mut entry {
+            if !(lines.is_empty() && line.trim().is_empty()) {
+                lines.push(line.to_string());
+            }
+        }
+        in_example = line == ">" || line.ends_with(" >");
+    }
+    finish(entry, &mut doc);
+    doc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_help_sections_and_entries() {
+        let help = "*foo.txt*\tFrobs things
+
+==============================================================================
+1. Functions                                                 *foo-functions*
+
+                                                                 *foo#Bar()*
+                                                                   *foo-bar*
+foo#Bar({path})
+  Frobs {path}. Example: >
+    call foo#Bar('x')  *not-a-tag*
+<
+  Returns 1.
+
+:Foo                                                                  *:Foo*
+  Runs |foo#Bar()|.
+";
+        let doc = parse_help(help);
+        assert_eq!(
+            doc.sections,
+            vec![HelpSection {
+                title: "1. Functions".into(),
+                tags: vec!["foo-functions".into()],
+                line: 3,
+            }]
+        );
+        assert_eq!(
+            doc.entries
+                .iter()
+                .map(|e| (e.tags.clone(), e.text.as_str(), e.line))
+                .collect::<Vec<_>>(),
+            vec![
+                (vec!["foo.txt".into()], "Frobs things", 0),
+                (vec!["foo-functions".into()], "1. Functions", 3),
+                (
+                    vec!["foo#Bar()".into(), "foo-bar".into()],
+                    "foo#Bar({path})\n  Frobs {path}. Example: >\n    call foo#Bar('x')  \
+                     *not-a-tag*\n<\n  Returns 1.",
+                    5
+                ),
+                (vec![":Foo".into()], ":Foo\n  Runs |foo#Bar()|.", 13),
+            ]
+        );
+    }
+}
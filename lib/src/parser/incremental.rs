@@ -0,0 +1,167 @@
+use super::VimParser;
+use crate::{Error, ModuleLanguage, TextEdit, VimModule};
+use std::ops::Range;
+use tree_sitter::{InputEdit, Point, Tree};
+
+/// A parsed module that keeps its code and syntax tree around, so edits can be reparsed
+/// incrementally. Created by [VimParser::parse_incremental].
+///
+/// Tree-sitter reuses the parts of the old syntax tree outside the edited ranges, so reparsing
+/// stays cheap enough to do on every keystroke. The [VimModule] metadata is still rebuilt from the
+/// whole tree.
+pub struct IncrementalModule {
+    code: String,
+    tree: Tree,
+    module: VimModule,
+}
+
+impl IncrementalModule {
+    pub(super) fn new(parser: &VimParser, code: String) -> crate::Result<Self> {
+        let (module, tree) = parse(parser, &code, None)?;
+        Ok(Self { code, tree, module })
+    }
+
+    /// The module's current code, with all edits applied.
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// The metadata parsed from the current code.
+    pub fn module(&self) -> &VimModule {
+        &self.module
+    }
+
+    /// The syntax tree of the current code, like from [VimParser::parse_module_with_tree].
+    pub fn tree(&self) -> &Tree {
+        &self.tree
+    }
+
+    pub fn into_module(self) -> VimModule {
+        self.module
+    }
+
+    /// Applies an edit to the code and reparses it, returning the updated metadata.
+    ///
+    /// Should be called with the same parser the module was created with, since its settings
+    /// apply to the result.
+    pub fn edit(&mut self, parser: &VimParser, edit: &TextEdit) -> crate::Result<&VimModule> {
+        self.edit_all(parser, std::slice::from_ref(edit))
+    }
+
+    /// Applies several edits in order and reparses once, returning the updated metadata. Each
+    /// edit's range refers to the code as left by the edits before it, like a batch of changes
+    /// from an editor.
+    ///
+    /// Edits are located by [Span::byte_range](crate::Span::byte_range) alone, and their
+    /// [TextEdit::file] is ignored.
+    ///
+    /// If any edit is invalid or parsing fails, the module is left unchanged.
+    pub fn edit_all(
+        &mut self,
+        parser: &VimParser,
+        edits: &[TextEdit],
+    ) -> crate::Result<&VimModule> {
+        let mut code = self.code.clone();
+        let mut tree = self.tree.clone();
+        for edit in edits {
+            let Range { start, end } = edit.range.byte_range;
+            if start > end
+                || end > code.len()
+                || !code.is_char_boundary(start)
+                || !code.is_char_boundary(end)
+            {
+                return Err(Error::InvalidEdit {
+                    byte_range: edit.range.byte_range.clone(),
+                    code_len: code.len(),
+                });
+            }
+            let start_position = point_at(&code, start);
+            let old_end_position = point_at(&code, end);
+            code.replace_range(start..end, &edit.new_text);
+            let new_end_byte = start + edit.new_text.len();
+            tree.edit(&InputEdit {
+                start_byte: start,
+                old_end_byte: end,
+                new_end_byte,
+                start_position,
+                old_end_position,
+                new_end_position: point_at(&code, new_end_byte),
+            });
+        }
+        let (module, tree) = parse(parser, &code, Some(&tree))?;
+        self.code = code;
+        self.tree = tree;
+        self.module = module;
+        Ok(&self.module)
+    }
+}
+
+/// Parses code as vimscript, detecting whether it's Vim9 script again each time since edits can
+/// add or remove the `vim9script` line.
+///
+/// Unlike [VimParser::parse_module_with_tree], this reuses the unchanged parts of `old_tree`.
+fn parse(
+    parser: &VimParser,
+    code: &str,
+    old_tree: Option<&Tree>,
+) -> crate::Result<(VimModule, Tree)> {
+    let language = ModuleLanguage::detect(None, code);
+    let (module, tree) = parser.parse_source(code, None, language, old_tree)?;
+    Ok((module, tree.ok_or(Error::ParsingFailure)?))
+}
+
+/// The tree-sitter point of a byte offset in code.
+fn point_at(code: &str, byte: usize) -> Point {
+    let before = &code.as_bytes()[..byte];
+    let row = before.iter().filter(|&&b| b == b'\n').count();
+    let line_start = before
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |i| i + 1);
+    Point {
+        row,
+        column: byte - line_start,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Span, VimNode};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn edit_reparses_module() {
+        let parser = VimParser::new().unwrap();
+        let code = "\"\" Does foo.\ncommand Foo echo\n";
+        let mut module = parser.parse_incremental(code).unwrap();
+        assert_eq!(
+            module.module().nodes,
+            vec![VimNode::command("Foo").doc("Does foo.").repl("echo").into()]
+        );
+
+        let edit = |byte_range: Range<usize>, new_text: &str| TextEdit {
+            file: None,
+            range: Span {
+                byte_range,
+                ..Span::default()
+            },
+            new_text: new_text.to_string(),
+        };
+        let rename = edit(21..24, "Bar");
+        let append = edit(code.len()..code.len(), "func! foo#Baz() abort\nendfunc\n");
+        module.edit_all(&parser, &[rename, append]).unwrap();
+        assert_eq!(
+            module.code(),
+            "\"\" Does foo.\ncommand Bar echo\nfunc! foo#Baz() abort\nendfunc\n"
+        );
+        assert_eq!(
+            module.module(),
+            &parser.parse_module_str(module.code()).unwrap()
+        );
+
+        let err = module.edit(&parser, &edit(100..101, "")).unwrap_err();
+        assert!(matches!(err, Error::InvalidEdit { code_len: 60, .. }));
+        assert_eq!(module.module().nodes.len(), 2);
+    }
+}
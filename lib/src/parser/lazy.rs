@@ -0,0 +1,148 @@
+use super::VimParser;
+use crate::VimModule;
+use std::path::{Path, PathBuf};
+use std::vec;
+
+/// An iterator parsing the modules of a plugin one at a time, in the order
+/// [VimParser::parse_plugin_dir] would, created by [VimParser::iter_plugin_modules].
+pub struct PluginModules {
+    parser: VimParser,
+    root: PathBuf,
+    paths: vec::IntoIter<PathBuf>,
+}
+
+impl PluginModules {
+    pub(super) fn new(parser: VimParser, root: PathBuf, paths: Vec<PathBuf>) -> Self {
+        Self {
+            parser,
+            root,
+            paths: paths.into_iter(),
+        }
+    }
+
+    /// The plugin root dir the modules are found under.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Paths of the modules not parsed yet, relative to the plugin root.
+    pub fn remaining_paths(&self) -> impl Iterator<Item = &Path> {
+        self.paths
+            .as_slice()
+            .iter()
+            .map(|path| path.strip_prefix(&self.root).unwrap_or(path))
+    }
+
+    /// Parses the module at a path relative to the plugin root on its own, whether or not
+    /// iteration has reached it yet.
+    pub fn parse_module<P: AsRef<Path>>(&self, relative_path: P) -> crate::Result<VimModule> {
+        self.parser
+            .parse_plugin_module(&self.root, &self.root.join(relative_path))
+            .map(|(module, _, _)| module)
+    }
+
+    /// Gives back the parser, to reuse it once done iterating.
+    pub fn into_parser(self) -> VimParser {
+        self.parser
+    }
+}
+
+impl Iterator for PluginModules {
+    type Item = crate::Result<VimModule>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let module_path = self.paths.next()?;
+        Some(
+            self.parser
+                .parse_plugin_module(&self.root, &module_path)
+                .map(|(module, _, _)| module),
+        )
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.paths.size_hint()
+    }
+}
+
+impl ExactSizeIterator for PluginModules {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn iter_plugin_modules_lazily() {
+        let tmp_dir = tempdir().unwrap();
+        for (path, code) in [
+            ("plugin/foo.vim", "let g:foo = 1"),
+            ("autoload/foo.vim", "function! foo#Bar() abort\nendfunction"),
+        ] {
+            let path = tmp_dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, code).unwrap();
+        }
+        let mut modules = VimParser::new()
+            .unwrap()
+            .iter_plugin_modules(tmp_dir.path())
+            .unwrap();
+        assert_eq!(modules.len(), 2);
+        let first = modules.next().unwrap().unwrap();
+        assert_eq!(first.path, Some("plugin/foo.vim".into()));
+        // Deleting the next file shows it wasn't read up front.
+        fs::remove_file(tmp_dir.path().join("autoload/foo.vim")).unwrap();
+        assert_eq!(
+            modules.remaining_paths().collect::<Vec<_>>(),
+            vec![Path::new("autoload/foo.vim")]
+        );
+        assert!(modules.next().unwrap().is_err());
+        assert!(modules.next().is_none());
+    }
+
+    #[test]
+    fn plugin_modules_parse_module_out_of_order() {
+        let tmp_dir = tempdir().unwrap();
+        for (path, code) in [
+            ("plugin/foo.vim", "let g:foo = 1"),
+            ("autoload/foo.vim", "function! foo#Bar() abort\nendfunction"),
+        ] {
+            let path = tmp_dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, code).unwrap();
+        }
+        let modules = VimParser::new()
+            .unwrap()
+            .iter_plugin_modules(tmp_dir.path())
+            .unwrap();
+        // Deleting the first file shows parsing a later one doesn't read it.
+        fs::remove_file(tmp_dir.path().join("plugin/foo.vim")).unwrap();
+        let module = modules.parse_module("autoload/foo.vim").unwrap();
+        assert_eq!(module.path, Some("autoload/foo.vim".into()));
+        assert_eq!(module.nodes.len(), 1);
+        assert_eq!(modules.len(), 2);
+    }
+
+    #[test]
+    fn iter_plugin_dir_borrows_parser() {
+        let tmp_dir = tempdir().unwrap();
+        fs::create_dir_all(tmp_dir.path().join("plugin")).unwrap();
+        fs::write(tmp_dir.path().join("plugin/foo.vim"), "let g:foo = 1").unwrap();
+        let parser = VimParser::new().unwrap();
+        let paths = parser
+            .iter_plugin_dir(tmp_dir.path())
+            .unwrap()
+            .map(|module| module.unwrap().path)
+            .collect::<Vec<_>>();
+        assert_eq!(paths, vec![Some(PathBuf::from("plugin/foo.vim"))]);
+        assert_eq!(
+            parser
+                .parse_plugin_dir(tmp_dir.path())
+                .unwrap()
+                .content
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use crate::Span;
+use tree_sitter::{Node, Point, Tree};
+use tree_sitter_traversal::{traverse, Order};
+
+/// The positions fixes need to edit inside a function or command definition, beyond the whole
+/// definition's [crate::VimNode::span].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DefinitionLocation {
+    /// Byte offset where the definition starts, matching the start of its node's span.
+    pub start_byte: usize,
+    pub name_span: Span,
+    /// Empty span just past the signature and modifiers of a function, where another modifier
+    /// could be appended.
+    pub signature_end: Option<Span>,
+}
+
+pub fn span_of(node: &Node) -> Span {
+    Span {
+        start: (node.start_position().row, node.start_position().column),
+        end: (node.end_position().row, node.end_position().column),
+        byte_range: node.byte_range(),
+    }
+}
+
+fn empty_span_at(point: Point, byte: usize) -> Span {
+    Span {
+        start: (point.row, point.column),
+        end: (point.row, point.column),
+        byte_range: byte..byte,
+    }
+}
+
+/// Finds all function and command definitions in the tree, in source order.
+pub fn definition_locations(tree: &Tree) -> Vec<DefinitionLocation> {
+    traverse(tree.walk(), Order::Pre)
+        .filter_map(|node| match node.kind() {
+            "function_definition" => function_location(&node),
+            "command_statement" => {
+                let name = node.child_by_field_name("name")?;
+                Some(DefinitionLocation {
+                    start_byte: node.start_byte(),
+                    name_span: span_of(&name),
+                    signature_end: None,
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn function_location(node: &Node) -> Option<DefinitionLocation> {
+    let mut cursor = node.walk();
+    let mut name = None;
+    let mut signature_end = None;
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "function" | "bang" => {}
+            "body" | "endfunction" => break,
+            kind => {
+                if kind == "function_declaration" {
+                    name = child.child_by_field_name("name");
+                }
+                signature_end = Some(empty_span_at(child.end_position(), child.end_byte()));
+            }
+        }
+    }
+    Some(DefinitionLocation {
+        start_byte: node.start_byte(),
+        name_span: span_of(&name?),
+        signature_end,
+    })
+}
@@ -0,0 +1,578 @@
+//! Extraction for the Lua modules Neovim plugins ship under lua/ and other runtime dirs, from
+//! the tree-sitter-lua grammar.
+//!
+//! Definitions are recognized at the top level and inside blocks like `if` and `do`, but not
+//! inside function bodies: functions, fields assigned on the module's returned table (like
+//! `M.config = {…}`), functions in table constructors and `vim.g` globals. Docs come from `---`
+//! comment blocks, as used by LuaCATS annotations.
+
+use super::patterns::matches;
+use crate::data::ModuleLanguage;
+use crate::{ModuleStats, Span, VimModule, VimNode};
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::sync::OnceLock;
+use tree_sitter::{Node, Query, Tree};
+
+const FUNCTION_PATTERNS: &str = r#"
+(function_declaration "local"? @local name: (_) @name) @function
+(variable_declaration
+  "local" @local
+  (assignment_statement
+    (variable_list . name: (_) @name)
+    (expression_list . value: (function_definition) @function)))
+(assignment_statement
+  (variable_list . name: (_) @name)
+  (expression_list . value: (function_definition) @function))
+"#;
+
+// Functions in a table constructor assigned to a name, like `M = { setup = function() end }`.
+const TABLE_FUNCTION_PATTERNS: &str = r#"
+(variable_declaration
+  (assignment_statement
+    (variable_list . name: (_) @table)
+    (expression_list
+      . value: (table_constructor
+        (field name: (identifier) @field value: (function_definition) @function)))))
+(assignment_statement
+  (variable_list . name: (_) @table)
+  (expression_list
+    . value: (table_constructor
+      (field name: (identifier) @field value: (function_definition) @function))))
+"#;
+
+const FIELD_PATTERNS: &str = r#"
+(assignment_statement
+  (variable_list
+    . name: [(dot_index_expression) (bracket_index_expression)] @name)
+  (expression_list . value: (_) @value))
+"#;
+
+/// Compiled query patterns for each kind of Lua definition statement.
+struct LuaPatterns {
+    function: Query,
+    table_function: Query,
+    field: Query,
+}
+
+impl LuaPatterns {
+    fn new() -> Result<Self, tree_sitter::QueryError> {
+        let language = tree_sitter_lua::LANGUAGE.into();
+        Ok(Self {
+            function: Query::new(&language, FUNCTION_PATTERNS)?,
+            table_function: Query::new(&language, TABLE_FUNCTION_PATTERNS)?,
+            field: Query::new(&language, FIELD_PATTERNS)?,
+        })
+    }
+
+    fn get() -> &'static Self {
+        static PATTERNS: OnceLock<LuaPatterns> = OnceLock::new();
+        // The patterns are checked against the bundled grammar in tests.
+        PATTERNS.get_or_init(|| Self::new().expect("invalid Lua patterns"))
+    }
+}
+
+/// Whether a module path is a Lua file.
+pub(crate) fn is_lua_path(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "lua")
+}
+
+/// The name `require()` loads a module by, like `foo.bar` for lua/foo/bar.lua or `foo` for
+/// lua/foo/init.lua.
+fn require_name(path: &Path) -> Option<String> {
+    let path = path
+        .strip_prefix("lua")
+        .or_else(|_| path.strip_prefix("after/lua"))
+        .ok()?
+        .with_extension("");
+    let mut parts = path
+        .iter()
+        .map(|part| part.to_str())
+        .collect::<Option<Vec<_>>>()?;
+    if parts.len() > 1 && parts.last() == Some(&"init") {
+        parts.pop();
+    }
+    Some(parts.join("."))
+}
+
+/// Parses a Lua module from its syntax tree, using its path (if given) to qualify names with
+/// its require name.
+pub(crate) fn parse_lua_module(tree: &Tree, code: &str, path: Option<&Path>) -> VimModule {
+    let root = tree.root_node();
+    let mut extractor = Extractor {
+        code,
+        patterns: LuaPatterns::get(),
+        require_name: path.and_then(require_name),
+        returned_table: returned_table(root, code.as_bytes()),
+        module_doc: None,
+        doc_span: None,
+        nodes: vec![],
+    };
+    let mut cursor = root.walk();
+    extractor.add_statements(root.named_children(&mut cursor));
+    VimModule {
+        path: path.map(Path::to_owned),
+        doc: extractor.module_doc,
+        doc_span: extractor.doc_span,
+        nodes: extractor.nodes,
+        language: ModuleLanguage::Lua,
+        stats: count_lua_lines(code),
+        ..Default::default()
+    }
+}
+
+/// The name of the table a module returns, like `M` for `return M`.
+fn returned_table(root: Node, source: &[u8]) -> Option<String> {
+    let mut cursor = root.walk();
+    let statement = root
+        .named_children(&mut cursor)
+        .filter(|statement| statement.kind() == "return_statement")
+        .last()?;
+    let values = statement.named_child(0)?;
+    let value = values.named_child(0)?;
+    (values.named_child_count() == 1 && value.kind() == "identifier")
+        .then(|| text(value, source).to_string())
+}
+
+/// A block of consecutive `---` doc comment lines.
+struct DocBlock {
+    lines: Vec<String>,
+    rows: RangeInclusive<usize>,
+}
+
+impl DocBlock {
+    fn text(&self) -> Option<String> {
+        Some(self.lines.join("\n").trim().to_string()).filter(|doc| !doc.is_empty())
+    }
+}
+
+struct Extractor<'a> {
+    code: &'a str,
+    patterns: &'static LuaPatterns,
+    require_name: Option<String>,
+    returned_table: Option<String>,
+    module_doc: Option<String>,
+    doc_span: Option<Span>,
+    nodes: Vec<VimNode>,
+}
+
+impl Extractor<'_> {
+    /// Adds the definitions in a sequence of statements and comments, attaching doc comment
+    /// blocks to the statements right after them.
+    fn add_statements<'tree>(&mut self, statements: impl IntoIterator<Item = Node<'tree>>) {
+        let source = self.code.as_bytes();
+        let mut pending_doc: Option<DocBlock> = None;
+        for statement in statements {
+            let row = statement.start_position().row;
+            let adjacent = pending_doc
+                .as_ref()
+                .is_some_and(|doc| *doc.rows.end() + 1 == row);
+            if let Some(line) = doc_comment_line(statement, source) {
+                match &mut pending_doc {
+                    Some(doc) if adjacent => {
+                        doc.lines.push(line.to_string());
+                        doc.rows = *doc.rows.start()..=row;
+                    }
+                    _ => {
+                        self.add_standalone_doc(pending_doc.take());
+                        pending_doc = Some(DocBlock {
+                            lines: vec![line.to_string()],
+                            rows: row..=row,
+                        });
+                    }
+                }
+                continue;
+            }
+            let doc = pending_doc.take();
+            let (doc, unattached) = if adjacent { (doc, None) } else { (None, doc) };
+            self.add_standalone_doc(unattached);
+            let definitions = self.definitions(statement, doc.as_ref().and_then(DocBlock::text));
+            if definitions.is_empty() {
+                self.add_standalone_doc(doc);
+                let mut nested = vec![];
+                nested_statements(statement, &mut nested);
+                self.add_statements(nested);
+                continue;
+            }
+            self.nodes.extend(definitions);
+        }
+        self.add_standalone_doc(pending_doc);
+    }
+
+    /// A doc block not attached to anything is standalone, or the module doc if it's the first
+    /// thing in the file.
+    fn add_standalone_doc(&mut self, doc: Option<DocBlock>) {
+        let Some(block) = doc else {
+            return;
+        };
+        let Some(doc) = block.text() else {
+            return;
+        };
+        let span = Span::of_rows(self.code, block.rows);
+        if self.module_doc.is_none() && self.nodes.is_empty() {
+            self.module_doc = Some(doc);
+            self.doc_span = Some(span);
+        } else {
+            self.nodes.push(VimNode::StandaloneDocComment {
+                doc,
+                span: Some(span),
+            });
+        }
+    }
+
+    /// The nodes a statement defines, if any, with `doc` on the first one.
+    fn definitions(&self, statement: Node, mut doc: Option<String>) -> Vec<VimNode> {
+        let source = self.code.as_bytes();
+        let mut definitions = vec![];
+        for captures in matches(&self.patterns.function, statement, source) {
+            let capture = |name| {
+                captures
+                    .iter()
+                    .find(|(n, _)| *n == name)
+                    .map(|(_, node)| *node)
+            };
+            let (Some(name), Some(function)) = (capture("name"), capture("function")) else {
+                continue;
+            };
+            let Some(name) = lua_name(name, source) else {
+                continue;
+            };
+            definitions.push(VimNode::Function {
+                name: self.qualify(&name),
+                args: function_args(function, source),
+                modifiers: capture("local")
+                    .map(|_| "local".to_string())
+                    .into_iter()
+                    .collect(),
+                doc: doc.take(),
+                body: None,
+                span: Some(node_span(statement)),
+            });
+        }
+        for captures in matches(&self.patterns.field, statement, source) {
+            let capture = |name| {
+                captures
+                    .iter()
+                    .find(|(n, _)| *n == name)
+                    .map(|(_, node)| *node)
+            };
+            let (Some(name), Some(value)) = (capture("name"), capture("value")) else {
+                continue;
+            };
+            if value.kind() == "function_definition" {
+                continue;
+            }
+            let Some(name) = lua_name(name, source) else {
+                continue;
+            };
+            let name = match name.strip_prefix("vim.g.") {
+                Some(global) => format!("g:{global}"),
+                None => match self.qualify(&name) {
+                    qualified if qualified != name => qualified,
+                    _ => continue,
+                },
+            };
+            definitions.push(VimNode::Variable {
+                name,
+                init_value_token: text(value, source).to_string(),
+                doc: doc.take(),
+                span: Some(node_span(statement)),
+            });
+        }
+        for captures in matches(&self.patterns.table_function, statement, source) {
+            let capture = |name| {
+                captures
+                    .iter()
+                    .find(|(n, _)| *n == name)
+                    .map(|(_, node)| *node)
+            };
+            let (Some(table), Some(field), Some(function)) =
+                (capture("table"), capture("field"), capture("function"))
+            else {
+                continue;
+            };
+            let Some(table) = lua_name(table, source) else {
+                continue;
+            };
+            let name = format!("{table}.{}", text(field, source));
+            // Span the table field rather than the whole table.
+            let span = function
+                .parent()
+                .map_or_else(|| node_span(statement), node_span);
+            definitions.push(VimNode::Function {
+                name: self.qualify(&name),
+                args: function_args(function, source),
+                modifiers: vec![],
+                doc: doc.take(),
+                body: None,
+                span: Some(span),
+            });
+        }
+        definitions
+    }
+
+    /// Qualifies names on the returned table with the module's require name, like `foo.setup`.
+    fn qualify(&self, name: &str) -> String {
+        if let (Some(require_name), Some(table)) = (&self.require_name, &self.returned_table) {
+            for separator in ['.', ':'] {
+                if let Some(member) = name
+                    .strip_prefix(table.as_str())
+                    .and_then(|rest| rest.strip_prefix(separator))
+                {
+                    return format!("{require_name}{separator}{member}");
+                }
+            }
+        }
+        name.to_string()
+    }
+}
+
+/// The text of a `---` doc comment line, without the dashes and the space after them.
+fn doc_comment_line<'a>(statement: Node, source: &'a [u8]) -> Option<&'a str> {
+    if statement.kind() != "comment" {
+        return None;
+    }
+    let comment = text(statement, source).strip_prefix("---")?;
+    Some(comment.strip_prefix(' ').unwrap_or(comment))
+}
+
+/// Collects the statements nested in blocks of a statement, like the branches of an `if`, in
+/// source order along with their comments. Function bodies are skipped.
+///
+/// Comments before a block's first statement belong to the enclosing statement in the grammar,
+/// so they're collected from there too.
+fn nested_statements<'tree>(treenode: Node<'tree>, statements: &mut Vec<Node<'tree>>) {
+    let mut cursor = treenode.walk();
+    for child in treenode.named_children(&mut cursor) {
+        match child.kind() {
+            "block" => {
+                let mut block_cursor = child.walk();
+                statements.extend(child.named_children(&mut block_cursor));
+            }
+            "comment" => statements.push(child),
+            "function_definition" | "function_declaration" => {}
+            _ => nested_statements(child, statements),
+        }
+    }
+}
+
+/// The dotted name of a variable or table field, like `M.setup`, `M:method` or `M.x` for
+/// `M["x"]`, or None for names that aren't static.
+fn lua_name(treenode: Node, source: &[u8]) -> Option<String> {
+    let field = |name| treenode.child_by_field_name(name);
+    match treenode.kind() {
+        "identifier" => Some(text(treenode, source).to_string()),
+        "dot_index_expression" => Some(format!(
+            "{}.{}",
+            lua_name(field("table")?, source)?,
+            text(field("field")?, source)
+        )),
+        "method_index_expression" => Some(format!(
+            "{}:{}",
+            lua_name(field("table")?, source)?,
+            text(field("method")?, source)
+        )),
+        "bracket_index_expression" => {
+            let key = field("field").filter(|key| key.kind() == "string")?;
+            let content = key.child_by_field_name("content")?;
+            Some(format!(
+                "{}.{}",
+                lua_name(field("table")?, source)?,
+                text(content, source)
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn function_args(function: Node, source: &[u8]) -> Vec<String> {
+    let Some(parameters) = function.child_by_field_name("parameters") else {
+        return vec![];
+    };
+    let mut cursor = parameters.walk();
+    parameters
+        .named_children(&mut cursor)
+        .filter(|param| param.kind() != "comment")
+        .map(|param| text(param, source).to_string())
+        .collect()
+}
+
+fn node_span(treenode: Node) -> Span {
+    Span {
+        start: (
+            treenode.start_position().row,
+            treenode.start_position().column,
+        ),
+        end: (treenode.end_position().row, treenode.end_position().column),
+        byte_range: treenode.byte_range(),
+    }
+}
+
+fn text<'a>(treenode: Node, source: &'a [u8]) -> &'a str {
+    std::str::from_utf8(&source[treenode.byte_range()]).unwrap_or_default()
+}
+
+fn count_lua_lines(code: &str) -> ModuleStats {
+    let mut stats = ModuleStats::default();
+    for line in code.lines().map(str::trim) {
+        if line.is_empty() {
+            stats.blank_lines += 1;
+        } else if line.starts_with("---") {
+            stats.comment_lines += 1;
+            stats.doc_comment_lines += 1;
+        } else if line.starts_with("--") {
+            stats.comment_lines += 1;
+        } else {
+            stats.code_lines += 1;
+        }
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tree_sitter::Parser;
+
+    fn parse(code: &str, path: &str) -> VimModule {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_lua::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        parse_lua_module(&tree, code, Some(Path::new(path)))
+    }
+
+    #[test]
+    fn patterns_match_grammar() {
+        LuaPatterns::new().unwrap();
+    }
+
+    #[test]
+    fn parse_lua_module_definitions() {
+        let code = r#"--- Frobs things.
+
+local M = {}
+
+--- Default options.
+M.config = {
+  width = 80, -- {{ not a table
+  border = 'single',
+}
+
+vim.g.foo_enabled = true
+
+---@param opts table? Overrides for |M.config|.
+function M.setup(opts)
+  M.config = vim.tbl_extend('force', M.config, opts or {})
+end
+
+local function helper(...)
+end
+
+M.run = function(a, b) end
+
+return M
+"#;
+        let mut module = parse(code, "lua/foo/init.lua");
+        assert_eq!(
+            module.nodes[1].span().map(|span| (span.start, span.end)),
+            Some(((10, 0), (10, 24)))
+        );
+        module.clear_spans();
+        assert_eq!(module.doc, Some("Frobs things.".into()));
+        assert_eq!(module.language, ModuleLanguage::Lua);
+        assert_eq!(
+            module.nodes,
+            vec![
+                VimNode::Variable {
+                    name: "foo.config".into(),
+                    init_value_token: "{\n  width = 80, -- {{ not a table\n  border = 'single',\n}"
+                        .into(),
+                    doc: Some("Default options.".into()),
+                    span: None,
+                },
+                VimNode::Variable {
+                    name: "g:foo_enabled".into(),
+                    init_value_token: "true".into(),
+                    doc: None,
+                    span: None,
+                },
+                VimNode::Function {
+                    name: "foo.setup".into(),
+                    args: vec!["opts".into()],
+                    modifiers: vec![],
+                    doc: Some("@param opts table? Overrides for |M.config|.".into()),
+                    body: None,
+                    span: None,
+                },
+                VimNode::Function {
+                    name: "helper".into(),
+                    args: vec!["...".into()],
+                    modifiers: vec!["local".into()],
+                    doc: None,
+                    body: None,
+                    span: None,
+                },
+                VimNode::Function {
+                    name: "foo.run".into(),
+                    args: vec!["a".into(), "b".into()],
+                    modifiers: vec![],
+                    doc: None,
+                    body: None,
+                    span: None,
+                },
+            ]
+        );
+        assert_eq!(
+            require_name(Path::new("lua/foo/bar.lua")),
+            Some("foo.bar".into())
+        );
+    }
+
+    #[test]
+    fn parse_lua_module_nested_definitions() {
+        let code = r#"local M = {
+  --- Sets things up.
+  setup = function(opts) end,
+  width = 80,
+}
+
+if vim.fn.has("nvim-0.10") == 1 then
+  --- Only on newer versions.
+  function M.new_feature() end
+end
+
+    M["quoted"] = function() end
+
+function M:method(
+  first,
+  second
+)
+  local function inner() end
+end
+
+return M
+"#;
+        let mut module = parse(code, "lua/foo.lua");
+        assert_eq!(
+            module.nodes[2].span().map(|span| (span.start, span.end)),
+            Some(((11, 4), (11, 32)))
+        );
+        module.clear_spans();
+        assert_eq!(
+            module.nodes,
+            vec![
+                VimNode::function("foo.setup").args(["opts"]).into(),
+                VimNode::function("foo.new_feature")
+                    .doc("Only on newer versions.")
+                    .into(),
+                VimNode::function("foo.quoted").into(),
+                VimNode::function("foo:method")
+                    .args(["first", "second"])
+                    .into(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,217 @@
+//! Plugin-level metadata from addon-info.json manifests, help file headers and plugin/ file
+//! headers.
+
+use super::header::header_field_pattern;
+use super::read_source;
+use crate::data::{VimModule, VimPluginMetadata};
+use crate::SectionKind;
+use regex::Regex;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// How many lines into a help file to look for fields like `Author:`.
+const HELP_HEADER_LINES: usize = 30;
+
+/// Collects metadata for the plugin at `root`, preferring addon-info.json, then the header of
+/// its first doc/*.txt help file, then the headers of its plugin/ modules.
+///
+/// Unreadable or malformed files are skipped.
+pub(crate) fn read_plugin_metadata(root: &Path, modules: &[VimModule]) -> VimPluginMetadata {
+    let mut metadata = fs::read_to_string(root.join("addon-info.json"))
+        .map(|json| parse_addon_info(&json))
+        .unwrap_or_default();
+    if let Some(help) = first_help_file(root).and_then(|path| read_source(&path).ok()) {
+        metadata.merge(parse_help_header(&help));
+    }
+    merge_module_headers(&mut metadata, modules);
+    metadata
+}
+
+/// Fills in metadata missing from `metadata` with fields from the headers of plugin/ modules.
+pub(crate) fn merge_module_headers(metadata: &mut VimPluginMetadata, modules: &[VimModule]) {
+    let plugin_modules = modules.iter().filter(|module| {
+        module
+            .section()
+            .is_some_and(|section| section.kind == SectionKind::Plugin && !section.after)
+    });
+    for module in plugin_modules {
+        let header = &module.header;
+        metadata.merge(VimPluginMetadata {
+            author: header.author.clone().or_else(|| header.maintainer.clone()),
+            version: header.version.clone(),
+            license: header.license.clone(),
+            ..Default::default()
+        });
+    }
+}
+
+/// Parses a vim-addon-manager/maktaba style addon-info.json manifest.
+pub(crate) fn parse_addon_info(json: &str) -> VimPluginMetadata {
+    let Ok(Value::Object(info)) = serde_json::from_str(json) else {
+        return VimPluginMetadata::default();
+    };
+    let string = |value: Option<&Value>| value.and_then(Value::as_str).map(str::to_string);
+    let homepage = string(info.get("homepage")).or_else(|| match info.get("repository") {
+        Some(Value::Object(repository)) => string(repository.get("url")),
+        repository => string(repository),
+    });
+    let dependencies = match info.get("dependencies") {
+        // Maktaba maps names to version/source details.
+        Some(Value::Object(dependencies)) => dependencies.keys().cloned().collect(),
+        Some(Value::Array(dependencies)) => dependencies
+            .iter()
+            .filter_map(|d| string(Some(d)))
+            .collect(),
+        _ => vec![],
+    };
+    VimPluginMetadata {
+        name: string(info.get("name")),
+        description: string(info.get("description")),
+        version: string(info.get("version")),
+        author: string(info.get("author")),
+        license: string(info.get("license")),
+        homepage,
+        dependencies,
+    }
+}
+
+/// Parses the title line of a help file, like `*foo.txt*  Frobs things`, and fields like
+/// `Author:` in the lines after it.
+pub(crate) fn parse_help_header(help: &str) -> VimPluginMetadata {
+    static TITLE: OnceLock<Regex> = OnceLock::new();
+    let title = TITLE.get_or_init(|| Regex::new(r"^\*([^*\s]+)\.txt\*(.*)$").unwrap());
+    let mut lines = help.lines();
+    let Some(captures) = lines.next().and_then(|line| title.captures(line)) else {
+        return VimPluginMetadata::default();
+    };
+    // Title lines separate columns with tabs, like `For Vim version 8.0\tLast change: …`.
+    let description = captures[2]
+        .split('\t')
+        .map(str::trim)
+        .find(|column| !column.is_empty())
+        .filter(|column| !column.starts_with("For Vim version"))
+        .map(str::to_string);
+    let mut metadata = VimPluginMetadata {
+        name: Some(captures[1].to_string()),
+        description,
+        ..Default::default()
+    };
+    for line in lines.take(HELP_HEADER_LINES) {
+        let Some(captures) = header_field_pattern().captures(line) else {
+            continue;
+        };
+        let value = &captures[2];
+        if value.is_empty() {
+            continue;
+        }
+        let field = match captures[1].to_ascii_lowercase().as_str() {
+            "author" | "maintainer" => &mut metadata.author,
+            "license" => &mut metadata.license,
+            "version" => &mut metadata.version,
+            _ => continue,
+        };
+        field.get_or_insert_with(|| value.to_string());
+    }
+    metadata
+}
+
+fn first_help_file(root: &Path) -> Option<PathBuf> {
+    let entries = fs::read_dir(root.join("doc")).ok()?;
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VimParser;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    #[test]
+    fn parse_manifests() {
+        assert_eq!(
+            parse_addon_info(
+                r#"{
+  "name": "foo",
+  "description": "Frobs things.",
+  "version": "1.2.0",
+  "author": "Jane Doe",
+  "repository": {"type": "git", "url": "https://github.com/jane/vim-foo"},
+  "dependencies": {"maktaba": {"type": "git"}}
+}"#
+            ),
+            VimPluginMetadata {
+                name: Some("foo".into()),
+                description: Some("Frobs things.".into()),
+                version: Some("1.2.0".into()),
+                author: Some("Jane Doe".into()),
+                license: None,
+                homepage: Some("https://github.com/jane/vim-foo".into()),
+                dependencies: vec!["maktaba".into()],
+            }
+        );
+        assert_eq!(parse_addon_info("not json"), VimPluginMetadata::default());
+        assert_eq!(
+            parse_help_header(
+                "*foo.txt*\tFrobs things\tLast change: 2024 Sep 09\n\nAuthor:  Jane Doe\n\
+                 License: MIT\n"
+            ),
+            VimPluginMetadata {
+                name: Some("foo".into()),
+                description: Some("Frobs things".into()),
+                author: Some("Jane Doe".into()),
+                license: Some("MIT".into()),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            parse_help_header("*foo.txt*  For Vim version 8.0\n").description,
+            None
+        );
+    }
+
+    #[test]
+    fn read_plugin_metadata_precedence() {
+        let tmp_dir = tempdir().unwrap();
+        for (path, contents) in [
+            (
+                "addon-info.json",
+                r#"{"name": "foo", "dependencies": ["bar"]}"#,
+            ),
+            (
+                "doc/foo.txt",
+                "*foo.txt*\tFrobs things\n\nAuthor: Jane Doe\n",
+            ),
+            (
+                "plugin/foo.vim",
+                "\" Maintainer: John Roe\n\" Version: 0.1\nlet g:loaded_foo = 1\n",
+            ),
+        ] {
+            let path = tmp_dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        let plugin = VimParser::new()
+            .unwrap()
+            .parse_plugin_dir(tmp_dir.path())
+            .unwrap();
+        assert_eq!(
+            plugin.metadata,
+            VimPluginMetadata {
+                name: Some("foo".into()),
+                description: Some("Frobs things".into()),
+                version: Some("0.1".into()),
+                author: Some("Jane Doe".into()),
+                license: None,
+                homepage: None,
+                dependencies: vec!["bar".into()],
+            }
+        );
+    }
+}
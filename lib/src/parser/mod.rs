@@ -1,247 +1,1120 @@
-use crate::data::VimModule;
-use crate::{Error, VimNode, VimPlugin};
-use std::ffi::OsStr;
+use crate::data::{
+    Fingerprint, ModuleError, ModuleLanguage, VendoredPlugin, VimModule, VimPluginMetadata,
+};
+use crate::{
+    Error, ModuleProfile, ParseInfo, ParseProfile, PluginDeclaration, RemotePlugin, Span,
+    ValueRedaction, VimConfig, VimNode, VimNodeTree, VimPlugin, VimPluginSet, Warning, WarningKind,
+};
+use bodies::{attach_function_body, capture_function_bodies, function_bodies};
+use config::extract_config_statements;
+use encoding::{invalid_position, script_encoding};
+use header::{is_license_text, parse_header, strip_header_fields};
+use help::read_help_docs;
+use locations::definition_locations;
+use lua::{is_lua_path, parse_lua_module};
+use manifest::{merge_module_headers, read_plugin_metadata};
+use modeline::find_modeline;
+use queries::detect_queries;
+use references::{
+    find_autoload_references, find_function_calls, find_references, find_required_libraries,
+};
+use remote::{detect_remote_plugins, extract_registration_stubs, RegistrationStub};
+use sections::classify_path;
+use stats::count_lines;
+use std::collections::hash_map::{Entry, HashMap};
+use std::fs;
 use std::path::{Path, PathBuf};
-use std::{fs, str};
-use tree_sitter::{Parser, Point};
+use std::str;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Instant;
+use tree_sitter::{Parser, Point, Tree};
 use treenodes::TreeNodeMetadata;
+use vim9::{is_vim9script, scan_vim9};
 use walkdir::WalkDir;
 
+mod bodies;
+mod builder;
+mod config;
+mod dialect;
+mod encoding;
+mod events;
+mod header;
+mod help;
+mod incremental;
+mod lazy;
+mod locations;
+mod lua;
+mod manifest;
+mod modeline;
+mod nesting;
+mod patterns;
+mod queries;
+mod references;
+mod remote;
+mod sections;
+mod stats;
 mod treenodes;
+mod vendored;
+mod vim9;
 
-// All paths that can contain .vim files from `:help vimfiles`, plus instant/ used by some plugins.
+pub(crate) use bodies::FunctionBody;
+pub use bodies::FunctionBodyCapture;
+pub use builder::VimParserBuilder;
+pub use dialect::{detect_dialect, Dialect};
+pub use encoding::EncodingFallback;
+pub(crate) use encoding::{decode_source_with, read_source};
+pub use events::ParseEvent;
+pub use incremental::IncrementalModule;
+pub use lazy::PluginModules;
+pub(crate) use locations::{span_of, DefinitionLocation};
+pub(crate) use references::Reference;
+pub(crate) use sections::filetype_of;
+pub use sections::{
+    classify_runtime_path, classify_runtime_path_with, PluginFile, Section, SectionKind,
+};
+pub use vendored::{detect_plugin_roots, detect_vendored_plugins, VendoredPlugins};
+
+// All paths that can contain .vim files from `:help vimfiles`, plus instant/ used by some plugins
+// and lua/ for Neovim plugins.
 // Note:
 //   - we search all dir paths as DIR/ and after/DIR/
-//   - autoload can contain subdirs to arbitrary depth, but subdirs aren't checked for the others
+//   - autoload and lua can contain subdirs to arbitrary depth, ftplugin and syntax one level of
+//     filetype subdirs, but subdirs aren't checked for the others
 //   - we also check for a special menu.vim file in the root
 #[rustfmt::skip]
-const DEFAULT_SECTION_ORDER: [&str; 11] = [
-    "plugin",
-    "instant",
-    "autoload",
-    "syntax",
-    "indent",
-    "ftdetect",
-    "ftplugin",
-    "compiler",
-    "spell",
-    "lang",
-    "colors",
+const DEFAULT_SECTION_ORDER: [SectionKind; 13] = [
+    SectionKind::Plugin,
+    SectionKind::Instant,
+    SectionKind::Autoload,
+    SectionKind::Import,
+    SectionKind::Syntax,
+    SectionKind::Indent,
+    SectionKind::Ftdetect,
+    SectionKind::Ftplugin,
+    SectionKind::Compiler,
+    SectionKind::Spell,
+    SectionKind::Lang,
+    SectionKind::Colors,
+    SectionKind::Lua,
 ];
 
+/// How [VimParser::parse_plugin_dir] handles the same file showing up at several module paths,
+/// like through symlinks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicateModules {
+    /// Only parse the file at the first path it's found at.
+    #[default]
+    FirstWins,
+    /// Fail with [crate::Error::DuplicateModule].
+    Error,
+    /// Parse it again at each path, producing duplicate modules.
+    KeepBoth,
+}
+
+/// How [VimParser::parse_plugin_dir] handles modules that fail to read or parse.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Fail the whole plugin with the first module's error.
+    #[default]
+    FailFast,
+    /// Leave failed modules out, recording them in [VimPlugin::errors], and return the rest.
+    CollectErrors,
+}
+
+/// Which runtime dirs [VimParser::parse_plugin_dir] scans and how it walks them.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct WalkOptions {
+    pub sections: Vec<SectionKind>,
+    pub include_after: bool,
+    pub follow_symlinks: bool,
+    pub case_insensitive: bool,
+    pub duplicate_modules: DuplicateModules,
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        Self {
+            sections: DEFAULT_SECTION_ORDER.to_vec(),
+            include_after: true,
+            follow_symlinks: true,
+            case_insensitive: false,
+            duplicate_modules: DuplicateModules::default(),
+        }
+    }
+}
+
+/// The tree-sitter grammars modules are parsed with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Grammar {
+    Vim,
+    Lua,
+}
+
+impl Grammar {
+    const ALL: [Grammar; 2] = [Grammar::Vim, Grammar::Lua];
+
+    fn language(self) -> tree_sitter::Language {
+        match self {
+            Grammar::Vim => tree_sitter_vim::language(),
+            Grammar::Lua => tree_sitter_lua::LANGUAGE.into(),
+        }
+    }
+}
+
+impl From<ModuleLanguage> for Grammar {
+    /// The grammar for modules in a language. Vim9 modules use the vim grammar, with the Vim9
+    /// syntax it can't parse scanned separately.
+    fn from(language: ModuleLanguage) -> Self {
+        match language {
+            ModuleLanguage::Vim | ModuleLanguage::Vim9 => Grammar::Vim,
+            ModuleLanguage::Lua => Grammar::Lua,
+        }
+    }
+}
+
+/// Tree-sitter parsers reused across calls, so a [VimParser] can parse through a shared
+/// reference, including from several threads at once. Each parse takes a parser for the
+/// module's grammar from the pool, or creates one if they're all in use, and puts it back after.
+#[derive(Default)]
+struct ParserPool {
+    parsers: Mutex<HashMap<Grammar, Vec<Parser>>>,
+}
+
+impl ParserPool {
+    fn new() -> crate::Result<Self> {
+        let pool = Self::default();
+        // Create one of each up front so grammar errors surface from VimParser::new.
+        for grammar in Grammar::ALL {
+            pool.release(grammar, Self::create_parser(grammar)?);
+        }
+        Ok(pool)
+    }
+
+    fn create_parser(grammar: Grammar) -> crate::Result<Parser> {
+        let mut parser = Parser::new();
+        parser.set_language(&grammar.language())?;
+        Ok(parser)
+    }
+
+    fn parse(&self, code: &str) -> crate::Result<Tree> {
+        self.reparse(code, Grammar::Vim, None)
+    }
+
+    /// Parses code with a grammar, reusing the unchanged parts of `old_tree`, which must already
+    /// be updated with [Tree::edit] for the changes since it was parsed.
+    fn reparse(
+        &self,
+        code: &str,
+        grammar: Grammar,
+        old_tree: Option<&Tree>,
+    ) -> crate::Result<Tree> {
+        let idle = self.lock().get_mut(&grammar).and_then(Vec::pop);
+        let mut parser = match idle {
+            Some(parser) => parser,
+            None => Self::create_parser(grammar)?,
+        };
+        let tree = parser.parse(code, old_tree).ok_or(Error::ParsingFailure);
+        self.release(grammar, parser);
+        tree
+    }
+
+    fn release(&self, grammar: Grammar, parser: Parser) {
+        self.lock().entry(grammar).or_default().push(parser);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<Grammar, Vec<Parser>>> {
+        // The pool is never left inconsistent, so it's fine to keep using after a panic.
+        self.parsers.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
 /// The main entry point for parsing plugins.
+///
+/// Parsing only needs a shared reference, so a parser can be configured once and then shared
+/// (like behind an `Arc`) by callers on different threads.
 #[derive(Default)]
 pub struct VimParser {
-    parser: Parser,
+    parsers: ParserPool,
+    walk: WalkOptions,
+    vendored_plugins: VendoredPlugins,
+    record_parse_info: bool,
+    record_spans: bool,
+    value_redaction: Option<ValueRedaction>,
+    record_profile: bool,
+    function_body_capture: FunctionBodyCapture,
+    encoding_fallback: EncodingFallback,
+    error_policy: ErrorPolicy,
 }
 
 impl VimParser {
     pub fn new() -> crate::Result<Self> {
-        let mut parser = Parser::new();
-        parser.set_language(&tree_sitter_vim::language())?;
-        Ok(Self { parser })
+        Ok(Self {
+            parsers: ParserPool::new()?,
+            walk: Default::default(),
+            vendored_plugins: Default::default(),
+            record_parse_info: false,
+            record_spans: false,
+            value_redaction: None,
+            record_profile: false,
+            function_body_capture: FunctionBodyCapture::Off,
+            encoding_fallback: EncodingFallback::Lossy,
+            error_policy: ErrorPolicy::FailFast,
+        })
+    }
+
+    /// Starts building a parser with custom settings, like which runtime dirs to scan.
+    pub fn builder() -> VimParserBuilder {
+        VimParserBuilder::default()
+    }
+
+    /// Configures how plugins vendored inside other plugins (under dirs like bundle/ or
+    /// pack/*/start/) are handled by [VimParser::parse_plugin_dir].
+    pub fn set_vendored_plugins(&mut self, vendored_plugins: VendoredPlugins) -> &mut Self {
+        self.vendored_plugins = vendored_plugins;
+        self
+    }
+
+    /// Configures whether [VimParser::parse_plugin_dir] records [ParseInfo] on plugins. Off by
+    /// default, since the timestamp makes otherwise identical results differ.
+    pub fn set_record_parse_info(&mut self, record_parse_info: bool) -> &mut Self {
+        self.record_parse_info = record_parse_info;
+        self
+    }
+
+    /// Configures whether parsed modules record where each node was defined in
+    /// [VimNode::span]. Off by default, since spans make modules with the same
+    /// definitions differ when they're formatted differently.
+    pub fn set_record_spans(&mut self, record_spans: bool) -> &mut Self {
+        self.record_spans = record_spans;
+        self
+    }
+
+    /// Configures truncation or redaction of variable and flag values in parsed modules, for
+    /// parsing private configs without exposing secrets. Off by default.
+    pub fn set_value_redaction(&mut self, value_redaction: Option<ValueRedaction>) -> &mut Self {
+        self.value_redaction = value_redaction;
+        self
+    }
+
+    /// Configures whether [VimParser::parse_plugin_dir] records how long each module took to
+    /// parse in a [ParseProfile]. Off by default, since timings differ on every run.
+    pub fn set_record_profile(&mut self, record_profile: bool) -> &mut Self {
+        self.record_profile = record_profile;
+        self
+    }
+
+    /// Configures whether parsed modules capture function bodies on their [VimNode::Function]
+    /// nodes. Off by default, since bodies make parse results much larger.
+    ///
+    /// Bodies are captured as written, so [VimParser::set_value_redaction] doesn't apply to them.
+    pub fn set_function_body_capture(
+        &mut self,
+        function_body_capture: FunctionBodyCapture,
+    ) -> &mut Self {
+        self.function_body_capture = function_body_capture;
+        self
+    }
+
+    /// Configures how files that aren't valid text in their declared encoding (or UTF-8, if they
+    /// don't declare one) are read. Defaults to [EncodingFallback::Lossy], so one old file can't
+    /// fail a whole [VimParser::parse_plugin_dir].
+    pub fn set_encoding_fallback(&mut self, encoding_fallback: EncodingFallback) -> &mut Self {
+        self.encoding_fallback = encoding_fallback;
+        self
+    }
+
+    /// Configures whether [VimParser::parse_plugin_dir] fails on the first module that can't be
+    /// read or parsed, or skips it and returns the rest. Errors finding the modules in the first
+    /// place, like an unreadable plugin dir, still fail either way.
+    pub fn set_error_policy(&mut self, error_policy: ErrorPolicy) -> &mut Self {
+        self.error_policy = error_policy;
+        self
+    }
+
+    /// Classifies a path relative to a plugin root into its runtime section, matching dir names
+    /// case-insensitively only if the parser is configured to (see [classify_runtime_path]).
+    pub fn classify_runtime_path<P: AsRef<Path>>(&self, path: P) -> Option<Section> {
+        classify_runtime_path_with(path, self.walk.case_insensitive)
+    }
+
+    /// Lists the files [VimParser::parse_plugin_dir] would parse for the plugin at the given
+    /// path, in the order it would parse them, without reading any of them.
+    ///
+    /// Files of vendored plugins aren't included.
+    pub fn list_plugin_files<P: AsRef<Path>>(&self, path: P) -> crate::Result<Vec<PluginFile>> {
+        let path = path.as_ref();
+        Ok(plugin_module_paths(path, &self.walk)?
+            .into_iter()
+            .filter_map(|module_path| {
+                let relative_path = module_path.strip_prefix(path).ok()?.to_owned();
+                let section = self.classify_runtime_path(&relative_path)?;
+                Some(PluginFile {
+                    path: relative_path,
+                    section,
+                })
+            })
+            .collect())
+    }
+
+    /// Lists the modules of the plugin at the given path like [VimParser::list_plugin_files], then
+    /// parses each one only as it's iterated.
+    ///
+    /// Useful for exploring big runtime trees where only some modules are needed. Unlike
+    /// [VimParser::parse_plugin_dir], plugin-level metadata like help docs isn't collected.
+    pub fn iter_plugin_modules<P: AsRef<Path>>(self, path: P) -> crate::Result<PluginModules> {
+        let paths = plugin_module_paths(path.as_ref(), &self.walk)?;
+        Ok(PluginModules::new(self, path.as_ref().to_owned(), paths))
+    }
+
+    /// Like [VimParser::iter_plugin_modules], but borrows the parser instead of taking it, so
+    /// consumers can process and drop modules one at a time and keep using the parser after.
+    pub fn iter_plugin_dir<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> crate::Result<impl Iterator<Item = crate::Result<VimModule>> + '_> {
+        let root = path.as_ref().to_owned();
+        let paths = plugin_module_paths(&root, &self.walk)?;
+        Ok(paths.into_iter().map(move |module_path| {
+            self.parse_plugin_module(&root, &module_path)
+                .map(|(module, _, _)| module)
+        }))
+    }
+
+    /// Reads and parses one module of the plugin at `root`, returning it with its path relative
+    /// to the root, along with its source code and syntax tree.
+    fn parse_plugin_module(
+        &self,
+        root: &Path,
+        module_path: &Path,
+    ) -> crate::Result<(VimModule, String, Option<Tree>)> {
+        let mut path_warnings = vec![];
+        let relative_path = module_path.strip_prefix(root).unwrap_or_else(|_| {
+            path_warnings.push(path_warning(format!(
+                "Path isn't under the plugin root {}",
+                root.display()
+            )));
+            module_path
+        });
+        if relative_path.to_str().is_none() {
+            path_warnings.push(path_warning(format!(
+                "Path isn't valid unicode, so it may display as {}",
+                relative_path.display()
+            )));
+        }
+        let code = read_file(module_path, self.encoding_fallback)?;
+        let language = ModuleLanguage::detect(Some(relative_path), &code);
+        let (mut module, tree) = self
+            .parse_source(&code, Some(relative_path), language, None)
+            .map_err(|err| err.in_file(module_path))?;
+        module.warnings.extend(path_warnings);
+        Ok((module.with_path(relative_path), code, tree))
     }
 
     /// Parses all supported metadata from a single plugin at the given path.
-    pub fn parse_plugin_dir<P: AsRef<Path> + Copy>(&mut self, path: P) -> crate::Result<VimPlugin> {
+    pub fn parse_plugin_dir<P: AsRef<Path> + Copy>(&self, path: P) -> crate::Result<VimPlugin> {
         let mut modules: Vec<VimModule> = Vec::new();
-        let path_depth = path.as_ref().iter().count();
-        let walker = WalkDir::new(path)
-            .follow_links(true)
-            .sort_by_key(move |e| {
-                let relative_path = e.path().iter().skip(path_depth).collect::<PathBuf>();
-                let (section_index, mut depth) = match order_in_sections(relative_path.as_path()) {
-                    Some((idx, depth)) => (idx, depth),
-                    // Placeholder value for path that will be filtered.
-                    None => return (usize::MAX, usize::MAX),
-                };
-                // Add 1 to dir paths to get the depth of *files* at that path.
-                // That way foo/bar.vim comes before foo/bar/ and its contents.
-                if e.file_type().is_dir() {
-                    depth += 1;
+        let mut registration_stubs: Vec<RegistrationStub> = Vec::new();
+        let mut fingerprint = Fingerprint::new();
+        let mut profile = ParseProfile::default();
+        let mut errors = vec![];
+        for module_path in plugin_module_paths(path.as_ref(), &self.walk)? {
+            let started = Instant::now();
+            let (module, code, tree) = match self.parse_plugin_module(path.as_ref(), &module_path) {
+                Ok(parsed) => parsed,
+                Err(err) if self.error_policy == ErrorPolicy::CollectErrors => {
+                    let relative_path = module_path
+                        .strip_prefix(path.as_ref())
+                        .unwrap_or(&module_path);
+                    errors.push(ModuleError::new(relative_path.to_owned(), &err));
+                    continue;
                 }
-                (section_index, depth)
-            })
-            .into_iter();
-        for entry in walker.filter_entry(|e| {
-            // Filter to only include paths under known section dirs.
-            let relative_path = e.path().strip_prefix(path).unwrap();
-            order_in_sections(relative_path).is_some()
-        }) {
-            let entry = entry?;
-            if !(entry.file_type().is_file()
-                && entry.file_name().to_string_lossy().ends_with(".vim"))
-            {
-                continue;
-            }
-            let relative_path = entry.path().strip_prefix(path).unwrap();
-            let module = self.parse_module_file(entry.path())?;
-            // Replace absolute path with one relative to plugin root.
-            let module = VimModule {
-                path: relative_path.to_owned().into(),
-                ..module
+                Err(err) => return Err(err),
             };
+            let relative_path = module.path.as_deref().unwrap_or(&module_path);
+            fingerprint.add_module(relative_path, &code);
+            profile.modules.push(ModuleProfile {
+                path: relative_path.to_owned(),
+                duration: started.elapsed(),
+                bytes: code.len(),
+                node_count: module.nodes.len(),
+            });
+            if let Some(tree) = tree {
+                registration_stubs.extend(extract_registration_stubs(&tree, code.as_bytes()));
+            }
             modules.push(module);
         }
-        Ok(VimPlugin { content: modules })
+        let mut vendored = vec![];
+        for vendored_path in detect_vendored_plugins(path) {
+            let plugin = match self.vendored_plugins {
+                VendoredPlugins::Skip => None,
+                VendoredPlugins::ParseNested => {
+                    Some(self.parse_plugin_dir(path.as_ref().join(&vendored_path).as_path())?)
+                }
+            };
+            vendored.push(VendoredPlugin {
+                path: vendored_path,
+                plugin,
+            });
+        }
+        let mut remote_plugins = detect_remote_plugins(path.as_ref());
+        for stub in registration_stubs {
+            // Stubs usually reference the entry script by absolute path in the installed plugin.
+            let existing = remote_plugins
+                .iter_mut()
+                .find(|p| p.host == stub.host && Path::new(&stub.path).ends_with(&p.path));
+            match existing {
+                Some(remote_plugin) => remote_plugin.registrations.extend(stub.registrations),
+                None => remote_plugins.push(RemotePlugin {
+                    host: stub.host,
+                    path: stub.path.into(),
+                    registrations: stub.registrations,
+                }),
+            }
+        }
+        Ok(VimPlugin {
+            root: Some(path.as_ref().to_owned()),
+            metadata: read_plugin_metadata(path.as_ref(), &modules),
+            content: modules,
+            vendored,
+            remote_plugins,
+            queries: detect_queries(path.as_ref())?,
+            help_docs: read_help_docs(path.as_ref()),
+            parse_info: self.record_parse_info.then(|| {
+                ParseInfo::new(
+                    tree_sitter_vim::language().version(),
+                    fingerprint.finish(),
+                    self.encoding_fallback,
+                )
+            }),
+            profile: self.record_profile.then_some(profile),
+            errors,
+        })
+    }
+
+    /// Parses every plugin installed under the given paths, which can each be a plugin root, a
+    /// dir of plugins like `~/.vim/bundle`, or a package dir like `~/.vim/pack` (see
+    /// [detect_plugin_roots]).
+    ///
+    /// Plugins are named after their root dirs, in the order found.
+    pub fn parse_plugin_dirs<I, P>(&self, paths: I) -> crate::Result<VimPluginSet>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let mut plugins = VimPluginSet::new();
+        for path in paths {
+            for root in detect_plugin_roots(path) {
+                let name = root
+                    .file_name()
+                    .map_or_else(|| root.to_string_lossy(), |name| name.to_string_lossy())
+                    .into_owned();
+                plugins.add(name, self.parse_plugin_dir(root.as_path())?);
+            }
+        }
+        Ok(plugins)
     }
 
-    /// Parses and returns metadata for a single module (a.k.a. file) of vimscript code.
-    pub fn parse_module_file<P: AsRef<Path>>(&mut self, path: P) -> crate::Result<VimModule> {
-        let code = fs::read_to_string(path.as_ref())?;
-        let module = self.parse_module_str(&code)?;
-        Ok(VimModule {
-            path: Some(path.as_ref().to_owned()),
-            ..module
+    /// Parses a standalone script, like a single-file plugin distributed outside the runtime
+    /// layout, as a plugin containing just that module and named after the file.
+    ///
+    /// The module's section comes from its parent dir if that's a runtime dir, so
+    /// `~/.vim/autoload/foo.vim` is treated as autoload/foo.vim. Otherwise it's treated as
+    /// plugin/, where a standalone script would be installed. The plugin has no
+    /// [VimPlugin::root], since the module path may not exist on disk.
+    pub fn parse_plugin_file<P: AsRef<Path>>(&self, path: P) -> crate::Result<VimPlugin> {
+        let path = path.as_ref();
+        let file_name = Path::new(path.file_name().unwrap_or(path.as_os_str()));
+        let relative_path = path
+            .parent()
+            .and_then(Path::file_name)
+            .map(|dir| Path::new(dir).join(file_name))
+            .filter(|relative_path| self.classify_runtime_path(relative_path).is_some())
+            .unwrap_or_else(|| Path::new("plugin").join(file_name));
+        let code = read_file(path, self.encoding_fallback)?;
+        let language = ModuleLanguage::detect(Some(&relative_path), &code);
+        let (module, _) = self
+            .parse_source(&code, Some(&relative_path), language, None)
+            .map_err(|err| err.in_file(path))?;
+        let content = vec![module.with_path(relative_path)];
+        let mut metadata = VimPluginMetadata {
+            name: path
+                .file_stem()
+                .map(|name| name.to_string_lossy().into_owned()),
+            ..Default::default()
+        };
+        merge_module_headers(&mut metadata, &content);
+        Ok(VimPlugin {
+            metadata,
+            content,
+            ..Default::default()
         })
     }
 
-    /// Parses and returns metadata for a single module (a.k.a. file) of vimscript code.
-    pub fn parse_module_str(&mut self, code: &str) -> crate::Result<VimModule> {
-        let tree = self.parser.parse(code, None).ok_or(Error::ParsingFailure)?;
-        let mut tree_cursor = tree.walk();
-        let mut module_nodes: Vec<VimNode> = Vec::new();
-        let mut module_doc = None;
-        let mut last_block_comment: Option<TreeNodeMetadata> = None;
-        let mut reached_end = !tree_cursor.goto_first_child();
-        while !reached_end {
-            let mut node_metadata: TreeNodeMetadata = (tree_cursor.node(), code.as_bytes()).into();
-            let cur_pos = tree_cursor.node().start_position();
-            let mut next_pos = Point {
-                row: cur_pos.row + 1,
-                ..cur_pos
-            };
-            if node_metadata.kind() == "comment" {
-                // Consume more lines of comment.
-                loop {
-                    match tree_cursor.node().next_sibling() {
-                        Some(s) if s.kind() == "comment" && s.start_position() == next_pos => {
-                            // Another comment at same indentation on the following line.
-                            // Consume and absorb into node_metadata.
-                            next_pos = Point {
-                                row: next_pos.row + 1,
-                                ..next_pos
-                            };
-                            tree_cursor.goto_next_sibling();
-                            node_metadata.treenodes.push(tree_cursor.node());
-                        }
-                        _ => {
-                            break;
-                        }
+    /// Parses and returns metadata for a single module (a.k.a. file) of code, in the language
+    /// detected from its path and contents (see [ModuleLanguage::detect]).
+    pub fn parse_module_file<P: AsRef<Path>>(&self, path: P) -> crate::Result<VimModule> {
+        let path = path.as_ref();
+        let code = read_file(path, self.encoding_fallback)?;
+        let language = ModuleLanguage::detect(Some(path), &code);
+        let (module, _) = self
+            .parse_source(&code, Some(path), language, None)
+            .map_err(|err| err.in_file(path))?;
+        Ok(module.with_path(path))
+    }
+
+    /// Parses and returns metadata for a single module (a.k.a. file) of vimscript code, either
+    /// legacy or Vim9 script.
+    pub fn parse_module_str(&self, code: &str) -> crate::Result<VimModule> {
+        self.parse_module_str_as(code, ModuleLanguage::detect(None, code))
+    }
+
+    /// Parses and returns metadata for a single module of code in the given language.
+    pub fn parse_module_str_as(
+        &self,
+        code: &str,
+        language: ModuleLanguage,
+    ) -> crate::Result<VimModule> {
+        Ok(self.parse_source(code, None, language, None)?.0)
+    }
+
+    /// Parses vimscript code like [VimParser::parse_module_str], also returning the tree-sitter
+    /// syntax tree the module was extracted from.
+    ///
+    /// An escape hatch for tools that need more than [VimModule] captures, like running their own
+    /// queries against the tree's language (`tree.language()`) without parsing the code again.
+    pub fn parse_module_with_tree(&self, code: &str) -> crate::Result<(VimModule, Tree)> {
+        let language = ModuleLanguage::detect(None, code);
+        let (module, tree) = self.parse_source(code, None, language, None)?;
+        Ok((module, tree.ok_or(Error::ParsingFailure)?))
+    }
+
+    /// Parses vimscript code into a module that can be edited and reparsed incrementally, for
+    /// editor integrations keeping metadata up to date as the user types.
+    pub fn parse_incremental<S: Into<String>>(&self, code: S) -> crate::Result<IncrementalModule> {
+        IncrementalModule::new(self, code.into())
+    }
+
+    /// Parses vimscript code into its nodes arranged by how they're nested in the code, like
+    /// autocmds under the augroup block they're in or dict functions defined inside a function.
+    ///
+    /// The top level holds the same nodes as [VimParser::parse_module_str], plus any definitions
+    /// from inside `if` blocks, loops and the like, which aren't nodes themselves.
+    pub fn parse_module_tree(&self, code: &str) -> crate::Result<Vec<VimNodeTree>> {
+        let tree = self.parsers.parse(code)?;
+        let module = module_from_tree(&tree, code, ModuleLanguage::detect(None, code));
+        let mut trees = nesting::node_trees(&tree, code, module.nodes);
+        let bodies = capture_function_bodies(&tree, code.as_bytes(), self.function_body_capture);
+        VimNodeTree::for_each_node_mut(&mut trees, &mut |node| {
+            attach_function_body(node, &bodies);
+            if !self.record_spans {
+                *node.span_mut() = None;
+            }
+            if let Some(redaction) = &self.value_redaction {
+                redaction.apply_to_node(node);
+            }
+        });
+        Ok(trees)
+    }
+
+    /// Parses vimscript code, passing each doc comment and definition to `on_event` as it's found
+    /// instead of building a [VimModule].
+    ///
+    /// Useful for scanning lots of code when only a few kinds of definitions matter. Only
+    /// functions, commands, variables, flags and doc comments are reported, and settings like
+    /// [VimParser::set_value_redaction] don't apply.
+    pub fn parse_module_events<'a, F: FnMut(ParseEvent<'a>)>(
+        &self,
+        code: &'a str,
+        mut on_event: F,
+    ) -> crate::Result<()> {
+        let tree = self.parsers.parse(code)?;
+        events::emit_events(tree.root_node(), code, &mut on_event);
+        Ok(())
+    }
+
+    /// Parses a module with the grammar for its language, along with the syntax tree for
+    /// languages the vim grammar handles. An edited `old_tree` from an earlier parse of the same
+    /// module is reused for unchanged parts.
+    fn parse_source(
+        &self,
+        code: &str,
+        path: Option<&Path>,
+        language: ModuleLanguage,
+        old_tree: Option<&Tree>,
+    ) -> crate::Result<(VimModule, Option<Tree>)> {
+        let (mut module, tree) = match language {
+            ModuleLanguage::Lua => {
+                let tree = self.parsers.reparse(code, Grammar::Lua, None)?;
+                (parse_lua_module(&tree, code, path), None)
+            }
+            ModuleLanguage::Vim | ModuleLanguage::Vim9 => {
+                let tree = self.parsers.reparse(code, language.into(), old_tree)?;
+                let mut module = module_from_tree(&tree, code, language);
+                self.attach_function_bodies(&mut module.nodes, &tree, code);
+                (module, Some(tree))
+            }
+        };
+        self.finish_module(&mut module);
+        Ok((module, tree))
+    }
+
+    /// Stores function bodies on the nodes that define them, if configured to. Must run before
+    /// spans are cleared.
+    fn attach_function_bodies(&self, nodes: &mut [VimNode], tree: &Tree, code: &str) {
+        let bodies = capture_function_bodies(tree, code.as_bytes(), self.function_body_capture);
+        if !bodies.is_empty() {
+            for node in nodes {
+                attach_function_body(node, &bodies);
+            }
+        }
+    }
+
+    /// Applies settings that post-process parsed modules.
+    fn finish_module(&self, module: &mut VimModule) {
+        if !self.record_spans {
+            module.clear_spans();
+        }
+        if let Some(redaction) = &self.value_redaction {
+            module.redact_values(redaction);
+        }
+    }
+
+    /// Parses a user config file like a vimrc or init.vim, extracting settings, mappings,
+    /// autocmds and plugin manager declarations along with normal module metadata.
+    pub fn parse_config_file<P: AsRef<Path>>(&self, path: P) -> crate::Result<VimConfig> {
+        let code = read_file(path.as_ref(), self.encoding_fallback)?;
+        let config = self
+            .parse_config_str(&code)
+            .map_err(|err| err.in_file(path.as_ref()))?;
+        Ok(VimConfig {
+            module: VimModule {
+                path: Some(path.as_ref().to_owned()),
+                ..config.module
+            },
+            ..config
+        })
+    }
+
+    /// Parses a module along with the source locations of its function and command definitions.
+    ///
+    /// Node spans are always recorded, regardless of [VimParser::set_record_spans].
+    pub(crate) fn parse_module_str_with_locations(
+        &self,
+        code: &str,
+    ) -> crate::Result<(VimModule, Vec<DefinitionLocation>)> {
+        let tree = self.parsers.parse(code)?;
+        Ok((
+            module_from_tree(&tree, code, ModuleLanguage::detect(None, code)),
+            definition_locations(&tree),
+        ))
+    }
+
+    /// Parses code into a raw syntax tree, for analyses that need more than [VimModule] captures.
+    pub(crate) fn parse_tree(&self, code: &str) -> crate::Result<Tree> {
+        self.parsers.parse(code)
+    }
+
+    /// Fingerprints the bodies of all functions defined in the given code.
+    pub(crate) fn function_bodies_str(&self, code: &str) -> crate::Result<Vec<FunctionBody>> {
+        let tree = self.parsers.parse(code)?;
+        Ok(function_bodies(&tree, code.as_bytes()))
+    }
+
+    /// Finds every occurrence of a function, command or variable name in the given code.
+    pub(crate) fn find_references_str(
+        &self,
+        code: &str,
+        name: &str,
+    ) -> crate::Result<Vec<Reference>> {
+        let tree = self.parsers.parse(code)?;
+        Ok(find_references(&tree, code.as_bytes(), name))
+    }
+
+    /// Finds plugin-manager declarations like `Plug 'foo/bar'`, `call dein#add('foo/bar')` or
+    /// packer.nvim `use` calls in a lua heredoc, anywhere in the given vimrc or plugin code.
+    pub fn parse_plugin_declarations_str(
+        &self,
+        code: &str,
+    ) -> crate::Result<Vec<PluginDeclaration>> {
+        let tree = self.parsers.parse(code)?;
+        Ok(extract_config_statements(&tree, code.as_bytes()).plugin_declarations)
+    }
+
+    /// Parses user config code like the contents of a vimrc (see [VimParser::parse_config_file]).
+    pub fn parse_config_str(&self, code: &str) -> crate::Result<VimConfig> {
+        let tree = self.parsers.parse(code)?;
+        let statements = extract_config_statements(&tree, code.as_bytes());
+        let mut module = module_from_tree(&tree, code, ModuleLanguage::detect(None, code));
+        self.attach_function_bodies(&mut module.nodes, &tree, code);
+        self.finish_module(&mut module);
+        Ok(VimConfig {
+            module,
+            settings: statements.settings,
+            mappings: statements.mappings,
+            autocmds: statements.autocmds,
+            plugin_declarations: statements.plugin_declarations,
+        })
+    }
+}
+
+impl ModuleLanguage {
+    /// Detects a module's language from its file extension, or for vimscript, whether it starts
+    /// with `vim9script`.
+    pub fn detect(path: Option<&Path>, code: &str) -> Self {
+        if path.is_some_and(is_lua_path) {
+            ModuleLanguage::Lua
+        } else if is_vim9script(code) {
+            ModuleLanguage::Vim9
+        } else {
+            ModuleLanguage::Vim
+        }
+    }
+}
+
+fn module_from_tree(tree: &Tree, code: &str, language: ModuleLanguage) -> VimModule {
+    let mut tree_cursor = tree.walk();
+    let mut module_nodes: Vec<VimNode> = Vec::new();
+    let mut module_doc = None;
+    let mut license_doc = None;
+    let mut doc_span = None;
+    let mut warnings = vec![];
+    let mut last_block_comment: Option<TreeNodeMetadata> = None;
+    let mut current_augroup: Option<String> = None;
+    let mut vim9 = (language == ModuleLanguage::Vim9).then(|| scan_vim9(code));
+    // Vim9 definitions are merged in by row with the nodes the grammar could parse.
+    let mut vim9_nodes = vim9
+        .as_mut()
+        .map(|vim9| std::mem::take(&mut vim9.nodes))
+        .unwrap_or_default()
+        .into_iter()
+        .peekable();
+    let mut reached_end = !tree_cursor.goto_first_child();
+    while !reached_end {
+        if let Some(vim9) = &vim9 {
+            // The grammar can't parse Vim9 syntax, so skip its errors and anything inside
+            // def bodies.
+            let node = tree_cursor.node();
+            if node.kind() == "ERROR" || vim9.contains_row(node.start_position().row) {
+                reached_end = !tree_cursor.goto_next_sibling();
+                continue;
+            }
+        }
+        let mut node_metadata: TreeNodeMetadata = (tree_cursor.node(), code.as_bytes()).into();
+        let cur_pos = tree_cursor.node().start_position();
+        let mut next_pos = Point {
+            row: cur_pos.row + 1,
+            ..cur_pos
+        };
+        if node_metadata.kind() == "comment" {
+            // Consume more lines of comment.
+            loop {
+                match tree_cursor.node().next_sibling() {
+                    Some(s) if s.kind() == "comment" && s.start_position() == next_pos => {
+                        // Another comment at same indentation on the following line.
+                        // Consume and absorb into node_metadata.
+                        next_pos = Point {
+                            row: next_pos.row + 1,
+                            ..next_pos
+                        };
+                        tree_cursor.goto_next_sibling();
+                        node_metadata.treenodes.push(tree_cursor.node());
+                    }
+                    _ => {
+                        break;
                     }
                 }
             }
-            node_metadata.maybe_consume_doc(&mut last_block_comment);
-            reached_end = !tree_cursor.goto_next_sibling();
+        }
+        node_metadata.maybe_consume_doc(&mut last_block_comment);
+        if node_metadata.kind() == "augroup_statement" {
+            current_augroup = tree_cursor
+                .node()
+                .named_child(0)
+                .map(|name| treenodes::get_treenode_text(&name, code.as_bytes()))
+                .filter(|name| !name.eq_ignore_ascii_case("END"))
+                .map(str::to_string);
+        }
+        reached_end = !tree_cursor.goto_next_sibling();
 
-            // Consume any dangling comments that can no longer attach to any node after.
-            let mut nodes_to_consume = vec![];
-            if let Some(last) = last_block_comment.take() {
-                nodes_to_consume.push(last);
-            }
-            if node_metadata.kind() != "comment"
-                || tree_cursor.node().start_position() != next_pos
-                || reached_end
+        // Consume any dangling comments that can no longer attach to any node after.
+        let mut nodes_to_consume = vec![];
+        if let Some(last) = last_block_comment.take() {
+            nodes_to_consume.push(last);
+        }
+        if node_metadata.kind() != "comment"
+            || tree_cursor.node().start_position() != next_pos
+            || reached_end
+        {
+            nodes_to_consume.push(node_metadata);
+        } else {
+            last_block_comment = Some(node_metadata);
+        }
+        let mut comment_can_be_module_doc = module_doc.is_none() && module_nodes.is_empty();
+        for node_metadata in nodes_to_consume {
+            let row = node_metadata.treenodes[0].start_position().row;
+            while let Some(node) =
+                vim9_nodes.next_if(|node| node.span().is_some_and(|span| span.start.0 < row))
             {
-                nodes_to_consume.push(node_metadata);
-            } else {
-                last_block_comment = Some(node_metadata);
-            }
-            let mut comment_can_be_module_doc = module_doc.is_none() && module_nodes.is_empty();
-            for node_metadata in nodes_to_consume {
-                for node in <TreeNodeMetadata<'_> as Into<Vec<_>>>::into(node_metadata) {
-                    match node {
-                        VimNode::StandaloneDocComment { doc: doc_content }
-                            if comment_can_be_module_doc =>
-                        {
-                            // This standalone doc comment is the first one in the module.
-                            // Treat it as overall module doc.
-                            module_doc = Some(doc_content);
-                            comment_can_be_module_doc = false;
-                        }
-                        node => {
-                            module_nodes.push(node);
-                        }
+                module_nodes.push(node);
+            }
+            let span = node_metadata.span();
+            for node in node_metadata.into_nodes(&mut warnings) {
+                match node {
+                    VimNode::StandaloneDocComment {
+                        doc: doc_content, ..
+                    } if comment_can_be_module_doc && is_license_text(&doc_content) => {
+                        // Boilerplate license header, which goes in the module header instead
+                        // so the next doc comment can still be the module doc.
+                        license_doc.get_or_insert(doc_content);
+                        continue;
+                    }
+                    VimNode::StandaloneDocComment {
+                        doc: doc_content, ..
+                    } if comment_can_be_module_doc => {
+                        // This standalone doc comment is the first one in the module.
+                        // Treat it as overall module doc.
+                        module_doc = Some(doc_content);
+                        doc_span = Some(span.clone());
+                        comment_can_be_module_doc = false;
+                        continue;
+                    }
+                    VimNode::Autocmd {
+                        group: None,
+                        events,
+                        patterns,
+                        command,
+                        doc,
+                        ..
+                    } => {
+                        module_nodes.push(VimNode::Autocmd {
+                            group: current_augroup.clone(),
+                            events,
+                            patterns,
+                            command,
+                            doc,
+                            span: Some(span.clone()),
+                        });
+                    }
+                    node => {
+                        module_nodes.push(node.with_span(span.clone()));
                     }
                 }
             }
         }
-        Ok(VimModule {
-            path: None,
-            doc: module_doc,
-            nodes: module_nodes,
+    }
+    module_nodes.extend(vim9_nodes);
+    let (vim9_imports, vim9_exports) = vim9
+        .map(|vim9| (vim9.imports, vim9.exports))
+        .unwrap_or_default();
+    let doc = module_doc.as_deref().and_then(strip_header_fields);
+    let autoload_references = find_autoload_references(tree, code.as_bytes());
+    let mut header = parse_header(code);
+    if header.license_text.is_none() {
+        header.license_text = license_doc;
+    }
+    VimModule {
+        path: None,
+        language,
+        doc_span: doc_span.filter(|_| doc.is_some()),
+        doc,
+        nodes: module_nodes,
+        header,
+        modeline: find_modeline(code),
+        encoding: script_encoding(code.as_bytes()),
+        stats: count_lines(code),
+        autoload_references: autoload_references.iter().map(|r| r.name.clone()).collect(),
+        optional_autoload_references: autoload_references
+            .into_iter()
+            .filter(|r| r.optional)
+            .map(|r| r.name)
+            .collect(),
+        required_libraries: find_required_libraries(tree, code.as_bytes()),
+        function_calls: find_function_calls(tree, code.as_bytes()),
+        vim9_imports,
+        vim9_exports,
+        warnings,
+    }
+}
+
+/// Reads a module's source like [read_source], reporting which file failed on errors and where
+/// text that doesn't decode starts.
+fn read_file(path: &Path, fallback: EncodingFallback) -> crate::Result<String> {
+    let bytes = fs::read(path).map_err(|err| Error::from(err).in_file(path))?;
+    decode_source_with(&bytes, fallback).map_err(|err| Error::InFile {
+        path: path.into(),
+        position: invalid_position(&bytes),
+        source: Box::new(err.into()),
+    })
+}
+
+/// Lists the .vim files under the known runtime dirs of a plugin, in the order they're parsed.
+pub(crate) fn plugin_module_paths(path: &Path, walk: &WalkOptions) -> crate::Result<Vec<PathBuf>> {
+    let mut paths = vec![];
+    // Canonical paths of the modules found so far, mapped to the path each was first found at.
+    let mut found_files: HashMap<PathBuf, PathBuf> = HashMap::new();
+    let path_depth = path.iter().count();
+    // The sort key closure must be 'static, so it gets its own copy.
+    let sort_walk = walk.clone();
+    let walker = WalkDir::new(path)
+        .follow_links(walk.follow_symlinks)
+        .sort_by_key(move |e| {
+            let relative_path = e.path().iter().skip(path_depth).collect::<PathBuf>();
+            let (section_index, mut depth) =
+                match order_in_sections(relative_path.as_path(), &sort_walk) {
+                    Some((idx, depth)) => (idx, depth),
+                    // Placeholder value for path that will be filtered.
+                    None => return (usize::MAX, usize::MAX),
+                };
+            // Add 1 to dir paths to get the depth of *files* at that path.
+            // That way foo/bar.vim comes before foo/bar/ and its contents.
+            if e.file_type().is_dir() {
+                depth += 1;
+            }
+            (section_index, depth)
         })
+        .into_iter();
+    for entry in walker.filter_entry(|e| {
+        // Filter to only include paths under known section dirs.
+        e.path()
+            .strip_prefix(path)
+            .is_ok_and(|relative_path| order_in_sections(relative_path, walk).is_some())
+    }) {
+        let entry = entry?;
+        let is_module = entry.path().extension().is_some_and(|ext| {
+            ["vim", "lua"].into_iter().any(|expected| {
+                if walk.case_insensitive {
+                    ext.eq_ignore_ascii_case(expected)
+                } else {
+                    ext == expected
+                }
+            })
+        });
+        if !(entry.file_type().is_file() && is_module) {
+            continue;
+        }
+        if walk.duplicate_modules != DuplicateModules::KeepBoth {
+            let mut file = fs::canonicalize(entry.path()).unwrap_or_else(|_| entry.path().into());
+            if walk.case_insensitive {
+                file = file.to_string_lossy().to_lowercase().into();
+            }
+            match found_files.entry(file) {
+                Entry::Occupied(first) => {
+                    if walk.duplicate_modules == DuplicateModules::FirstWins {
+                        continue;
+                    }
+                    return Err(Error::DuplicateModule {
+                        path: entry.into_path(),
+                        duplicate_of: first.get().clone(),
+                    });
+                }
+                Entry::Vacant(vacant) => {
+                    vacant.insert(entry.path().to_owned());
+                }
+            }
+        }
+        paths.push(entry.into_path());
+    }
+    Ok(paths)
+}
+
+/// A warning about a module's path rather than its contents.
+fn path_warning(message: String) -> Warning {
+    Warning {
+        kind: WarningKind::Path,
+        message,
+        span: Span::default(),
     }
 }
 
 /// Get sort key for relative path sorting by:
-///   1. the subdir's order in DEFAULT_SECTION_ORDER, and
+///   1. the subdir's order in the configured sections, and
 ///   2. the path's depth
 ///
 /// or None if the path shouldn't be included at all.
-fn order_in_sections(path: &Path) -> Option<(usize, usize)> {
+fn order_in_sections(path: &Path, walk: &WalkOptions) -> Option<(usize, usize)> {
     let depth = path.iter().count();
-    let mut paths = vec![(path, 0)];
-    if let Ok(path) = path.strip_prefix("after") {
-        // Offset to ensure all after/ paths come after normal paths.
-        paths.push((path, DEFAULT_SECTION_ORDER.len()));
-    }
-    for (rel_path, offset) in paths {
-        let Some(path_parts) = rel_path
-            .iter()
-            .map(OsStr::to_str)
-            .collect::<Option<Vec<_>>>()
-        else {
-            continue;
-        };
-        let key = match path_parts[..] {
-            // Root dir or after/.
-            [] => Some((offset, depth)),
-            // Special case: standalone file in root dir.
-            ["menu.vim"] => Some((offset, depth)),
-            [section @ "autoload", ..] | [section] | [section, _] => DEFAULT_SECTION_ORDER
-                .iter()
-                .position(|s| *s == section)
-                .map(|idx| (offset + idx, depth)),
-            _ => None,
-        };
-        if key.is_some() {
-            return key;
-        }
+    let (kind, after) = classify_path(path, walk.case_insensitive)?;
+    if after && !walk.include_after {
+        return None;
     }
-
-    None
+    // Root dir, after/ and menu.vim sort with the first section.
+    let section_index = match kind {
+        Some(SectionKind::Menu) | None => 0,
+        Some(kind) => walk.sections.iter().position(|k| *k == kind)?,
+    };
+    // Offset to ensure all after/ paths come after normal paths.
+    let offset = if after { walk.sections.len() } else { 0 };
+    Some((offset + section_index, depth))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        ConfigAutocmd, ConfigMapping, ConfigSetting, FunctionCall, ModuleStats, PluginDeclaration,
+        PluginManager, RemoteRegistration, TreeSitterQuery, WarningKind,
+    };
     use pretty_assertions::assert_eq;
+    use std::fs;
     use std::path::PathBuf;
     use tempfile::tempdir;
 
+    #[test]
+    fn parse_from_shared_parser() {
+        let parser = std::sync::Arc::new(VimParser::new().unwrap());
+        let threads: Vec<_> = (0..4)
+            .map(|i| {
+                let parser = parser.clone();
+                std::thread::spawn(move || {
+                    parser
+                        .parse_module_str(&format!("command Foo{i} echo {i}"))
+                        .unwrap()
+                        .nodes
+                })
+            })
+            .collect();
+        for (i, thread) in threads.into_iter().enumerate() {
+            assert_eq!(
+                thread.join().unwrap(),
+                vec![VimNode::command(format!("Foo{i}"))
+                    .repl(format!("echo {i}"))
+                    .build()]
+            );
+        }
+        // Parsers that weren't built with a language also get a working pool.
+        assert!(VimParser::default().parse_module_str("let x = 1").is_ok());
+    }
+
     #[test]
     fn parse_module_empty() {
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str("").unwrap(),
             VimModule {
                 path: None,
                 doc: None,
-                nodes: vec![]
+                nodes: vec![],
+                ..Default::default()
             }
         );
     }
 
     #[test]
     fn parse_module_one_nondoc_comment() {
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str("\" A comment").unwrap(),
             VimModule {
                 path: None,
                 doc: None,
-                nodes: vec![]
+                nodes: vec![],
+                stats: ModuleStats {
+                    blank_lines: 0,
+                    comment_lines: 1,
+                    doc_comment_lines: 0,
+                    code_lines: 0,
+                },
+                ..Default::default()
             }
         );
     }
@@ -252,13 +1125,20 @@ mod tests {
 ""
 " Foo
 "#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
                 path: None,
                 doc: "Foo".to_string().into(),
-                nodes: vec![]
+                nodes: vec![],
+                stats: ModuleStats {
+                    blank_lines: 1,
+                    comment_lines: 2,
+                    doc_comment_lines: 2,
+                    code_lines: 0,
+                },
+                ..Default::default()
             }
         );
     }
@@ -269,13 +1149,20 @@ mod tests {
 "" Foo
 "bar
 "#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
                 path: None,
                 doc: "Foo\nbar".to_string().into(),
-                nodes: vec![]
+                nodes: vec![],
+                stats: ModuleStats {
+                    blank_lines: 1,
+                    comment_lines: 2,
+                    doc_comment_lines: 2,
+                    code_lines: 0,
+                },
+                ..Default::default()
             }
         );
     }
@@ -288,17 +1175,42 @@ mod tests {
 ""
 " More doc comment.
 "#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
                 path: None,
                 doc: Some("Doc comment.\n\"\nMore doc comment.".into()),
                 nodes: vec![],
+                stats: ModuleStats {
+                    blank_lines: 1,
+                    comment_lines: 4,
+                    doc_comment_lines: 4,
+                    code_lines: 0,
+                },
+                ..Default::default()
             },
         );
     }
 
+    #[test]
+    fn parse_module_license_header_not_doc() {
+        let code = r#"
+""
+" Copyright 2024 Jane Doe. Licensed under the MIT license.
+
+""
+" Frobs things.
+"#;
+        let module = VimParser::new().unwrap().parse_module_str(code).unwrap();
+        assert_eq!(module.doc.as_deref(), Some("Frobs things."));
+        assert_eq!(module.nodes, vec![]);
+        assert_eq!(
+            module.header.license_text.as_deref(),
+            Some("Copyright 2024 Jane Doe. Licensed under the MIT license.")
+        );
+    }
+
     #[test]
     fn parse_module_doc_before_statement() {
         let code = r#"
@@ -307,7 +1219,7 @@ mod tests {
 echo 'Hi'
 func MyFunc() | endfunc
 "#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
@@ -320,8 +1232,17 @@ func MyFunc() | endfunc
                         args: vec![],
                         modifiers: vec![],
                         doc: None,
+                        body: None,
+                        span: None,
                     }
                 ],
+                stats: ModuleStats {
+                    blank_lines: 1,
+                    comment_lines: 2,
+                    doc_comment_lines: 2,
+                    code_lines: 2,
+                },
+                ..Default::default()
             }
         );
     }
@@ -333,7 +1254,7 @@ func MyFunc()
   return 1
 endfunc
 "#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
@@ -343,8 +1264,17 @@ endfunc
                     name: "MyFunc".into(),
                     args: vec![],
                     modifiers: vec![],
-                    doc: None
-                }]
+                    doc: None,
+                    body: None,
+                    span: None,
+                }],
+                stats: ModuleStats {
+                    blank_lines: 1,
+                    comment_lines: 0,
+                    doc_comment_lines: 0,
+                    code_lines: 3,
+                },
+                ..Default::default()
             }
         );
     }
@@ -360,7 +1290,7 @@ func MyFunc()
   return 1
 endfunc
 "#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
@@ -371,7 +1301,16 @@ endfunc
                     args: vec![],
                     modifiers: vec![],
                     doc: Some("Does a thing.\n\nCall and enjoy.".into()),
-                }]
+                    body: None,
+                    span: None,
+                }],
+                stats: ModuleStats {
+                    blank_lines: 1,
+                    comment_lines: 4,
+                    doc_comment_lines: 4,
+                    code_lines: 3,
+                },
+                ..Default::default()
             }
         );
     }
@@ -383,7 +1322,7 @@ func MyFunc(arg1, arg2)
   return 1
 endfunc
 "#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
@@ -393,8 +1332,17 @@ endfunc
                     name: "MyFunc".into(),
                     args: vec!["arg1".into(), "arg2".into()],
                     modifiers: vec![],
-                    doc: None
-                }]
+                    doc: None,
+                    body: None,
+                    span: None,
+                }],
+                stats: ModuleStats {
+                    blank_lines: 1,
+                    comment_lines: 0,
+                    doc_comment_lines: 0,
+                    code_lines: 3,
+                },
+                ..Default::default()
             }
         );
     }
@@ -406,7 +1354,7 @@ func! MyFunc(arg1, ...) range dict abort
   return 1
 endfunc
 "#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
@@ -416,12 +1364,41 @@ endfunc
                     name: "MyFunc".into(),
                     args: vec!["arg1".into(), "...".into()],
                     modifiers: vec!["!".into(), "range".into(), "dict".into(), "abort".into()],
-                    doc: None
-                }]
+                    doc: None,
+                    body: None,
+                    span: None,
+                }],
+                stats: ModuleStats {
+                    blank_lines: 1,
+                    comment_lines: 0,
+                    doc_comment_lines: 0,
+                    code_lines: 3,
+                },
+                ..Default::default()
             }
         );
     }
 
+    #[test]
+    fn parse_module_dict_func_and_chained_flag() {
+        let code = r#"
+function! s:plugin.Frob(...) abort
+endfunction
+call maktaba#plugin#Get('foo').Flag('verbose', 0)
+"#;
+        let parser = VimParser::new().unwrap();
+        assert_eq!(
+            parser.parse_module_str(code).unwrap().nodes,
+            vec![
+                VimNode::function("s:plugin.Frob")
+                    .args(["..."])
+                    .modifiers(["!", "abort"])
+                    .into(),
+                VimNode::flag("verbose").default_value_token("0").into(),
+            ]
+        );
+    }
+
     #[test]
     fn parse_module_two_docs() {
         let code = r#"
@@ -429,15 +1406,23 @@ endfunc
 
 "" Another doc
 "#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
                 path: None,
                 doc: Some("One doc".into()),
                 nodes: vec![VimNode::StandaloneDocComment {
-                    doc: "Another doc".into()
-                },]
+                    doc: "Another doc".into(),
+                    span: None,
+                }],
+                stats: ModuleStats {
+                    blank_lines: 2,
+                    comment_lines: 2,
+                    doc_comment_lines: 2,
+                    code_lines: 0,
+                },
+                ..Default::default()
             }
         );
     }
@@ -450,13 +1435,20 @@ endfunc
 ""
 " Module doc
 "#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
                 path: None,
                 doc: Some("Module doc".into()),
-                nodes: vec![]
+                nodes: vec![],
+                stats: ModuleStats {
+                    blank_lines: 2,
+                    comment_lines: 3,
+                    doc_comment_lines: 2,
+                    code_lines: 0,
+                },
+                ..Default::default()
             }
         );
     }
@@ -467,7 +1459,7 @@ endfunc
 "" One doc
  " Ignored comment
 "#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
@@ -477,6 +1469,13 @@ endfunc
                     // Comment at different indentation is treated as a normal
                     // non-doc comment and ignored.
                 ],
+                stats: ModuleStats {
+                    blank_lines: 1,
+                    comment_lines: 2,
+                    doc_comment_lines: 2,
+                    code_lines: 0,
+                },
+                ..Default::default()
             }
         );
     }
@@ -485,7 +1484,7 @@ endfunc
     fn parse_module_two_funcs() {
         let code = r#"func FuncOne() | endfunc
 func FuncTwo() | endfunc"#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
@@ -496,15 +1495,26 @@ func FuncTwo() | endfunc"#;
                         name: "FuncOne".into(),
                         args: vec![],
                         modifiers: vec![],
-                        doc: None
+                        doc: None,
+                        body: None,
+                        span: None,
                     },
                     VimNode::Function {
                         name: "FuncTwo".into(),
                         args: vec![],
                         modifiers: vec![],
-                        doc: None
+                        doc: None,
+                        body: None,
+                        span: None,
                     },
-                ]
+                ],
+                stats: ModuleStats {
+                    blank_lines: 0,
+                    comment_lines: 0,
+                    doc_comment_lines: 0,
+                    code_lines: 2,
+                },
+                ..Default::default()
             }
         );
     }
@@ -512,7 +1522,7 @@ func FuncTwo() | endfunc"#;
     #[test]
     fn parse_module_autoload_funcname() {
         let code = "func foo#bar#Baz() | endfunc";
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
@@ -522,8 +1532,17 @@ func FuncTwo() | endfunc"#;
                     name: "foo#bar#Baz".into(),
                     args: vec![],
                     modifiers: vec![],
-                    doc: None
-                }]
+                    doc: None,
+                    body: None,
+                    span: None,
+                }],
+                stats: ModuleStats {
+                    blank_lines: 0,
+                    comment_lines: 0,
+                    doc_comment_lines: 0,
+                    code_lines: 1,
+                },
+                ..Default::default()
             }
         );
     }
@@ -531,7 +1550,7 @@ func FuncTwo() | endfunc"#;
     #[test]
     fn parse_module_scriptlocal_funcname() {
         let code = "func s:SomeFunc() | endfunc";
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
@@ -541,8 +1560,17 @@ func FuncTwo() | endfunc"#;
                     name: "s:SomeFunc".into(),
                     args: vec![],
                     modifiers: vec![],
-                    doc: None
-                }]
+                    doc: None,
+                    body: None,
+                    span: None,
+                }],
+                stats: ModuleStats {
+                    blank_lines: 0,
+                    comment_lines: 0,
+                    doc_comment_lines: 0,
+                    code_lines: 1,
+                },
+                ..Default::default()
             }
         );
     }
@@ -558,7 +1586,7 @@ function Outer()
   return l:thing
 endfunction
 "#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
@@ -569,10 +1597,19 @@ endfunction
                         name: "Outer".into(),
                         args: vec![],
                         modifiers: vec![],
-                        doc: None
+                        doc: None,
+                        body: None,
+                        span: None,
                     },
-                    // TODO: Should have more nodes for inner function.
-                ]
+                    // The inner function is only in VimParser::parse_module_tree.
+                ],
+                stats: ModuleStats {
+                    blank_lines: 1,
+                    comment_lines: 0,
+                    doc_comment_lines: 0,
+                    code_lines: 7,
+                },
+                ..Default::default()
             }
         );
     }
@@ -580,7 +1617,7 @@ endfunction
     #[test]
     fn parse_module_one_command() {
         let code = r#"command SomeCommand echo "Hi""#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
@@ -589,8 +1626,17 @@ endfunction
                 nodes: vec![VimNode::Command {
                     name: "SomeCommand".into(),
                     modifiers: vec![],
-                    doc: None
+                    repl: Some(r#"echo "Hi""#.into()),
+                    doc: None,
+                    span: None,
                 }],
+                stats: ModuleStats {
+                    blank_lines: 0,
+                    comment_lines: 0,
+                    doc_comment_lines: 0,
+                    code_lines: 1,
+                },
+                ..Default::default()
             }
         );
     }
@@ -602,7 +1648,7 @@ endfunction
 " Do a complex thing.
 command -range -bang -nargs=+ -bar SomeComplexCommand call SomeHelper() | echo 'Hi'
 "#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
@@ -616,8 +1662,17 @@ command -range -bang -nargs=+ -bar SomeComplexCommand call SomeHelper() | echo '
                         "-nargs=+".into(),
                         "-bar".into()
                     ],
+                    repl: Some("call SomeHelper() | echo 'Hi'".into()),
                     doc: Some("Do a complex thing.".into()),
+                    span: None,
                 }],
+                stats: ModuleStats {
+                    blank_lines: 1,
+                    comment_lines: 2,
+                    doc_comment_lines: 2,
+                    code_lines: 1,
+                },
+                ..Default::default()
             }
         );
     }
@@ -625,7 +1680,7 @@ command -range -bang -nargs=+ -bar SomeComplexCommand call SomeHelper() | echo '
     #[test]
     fn parse_module_one_variable() {
         let code = "let somevar = 1";
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
@@ -635,7 +1690,15 @@ command -range -bang -nargs=+ -bar SomeComplexCommand call SomeHelper() | echo '
                     name: "somevar".into(),
                     init_value_token: "1".into(),
                     doc: None,
+                    span: None,
                 }],
+                stats: ModuleStats {
+                    blank_lines: 0,
+                    comment_lines: 0,
+                    doc_comment_lines: 0,
+                    code_lines: 1,
+                },
+                ..Default::default()
             },
         );
     }
@@ -647,7 +1710,7 @@ command -range -bang -nargs=+ -bar SomeComplexCommand call SomeHelper() | echo '
 " Doc for first variable.
 let g:somevar = 'xyz' | let s:othervar = system("ls")
 "#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
@@ -658,13 +1721,22 @@ let g:somevar = 'xyz' | let s:othervar = system("ls")
                         name: "g:somevar".into(),
                         init_value_token: "'xyz'".into(),
                         doc: Some("Doc for first variable.".into()),
+                        span: None,
                     },
                     VimNode::Variable {
                         name: "s:othervar".into(),
                         init_value_token: "system(\"ls\")".into(),
                         doc: None,
+                        span: None,
                     },
                 ],
+                stats: ModuleStats {
+                    blank_lines: 1,
+                    comment_lines: 2,
+                    doc_comment_lines: 2,
+                    code_lines: 1,
+                },
+                ..Default::default()
             },
         );
     }
@@ -672,7 +1744,7 @@ let g:somevar = 'xyz' | let s:othervar = system("ls")
     #[test]
     fn parse_module_one_flag() {
         let code = "call Flag('someflag', 'somedefault')";
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
@@ -681,8 +1753,20 @@ let g:somevar = 'xyz' | let s:othervar = system("ls")
                 nodes: vec![VimNode::Flag {
                     name: "someflag".into(),
                     default_value_token: Some("'somedefault'".into()),
-                    doc: None
+                    doc: None,
+                    span: None,
                 }],
+                function_calls: vec![FunctionCall {
+                    caller: None,
+                    callee: "Flag".into(),
+                }],
+                stats: ModuleStats {
+                    blank_lines: 0,
+                    comment_lines: 0,
+                    doc_comment_lines: 0,
+                    code_lines: 1,
+                },
+                ..Default::default()
             }
         );
     }
@@ -690,7 +1774,7 @@ let g:somevar = 'xyz' | let s:othervar = system("ls")
     #[test]
     fn parse_module_flag_without_default() {
         let code = "call Flag('someflag')";
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
@@ -699,8 +1783,20 @@ let g:somevar = 'xyz' | let s:othervar = system("ls")
                 nodes: vec![VimNode::Flag {
                     name: "someflag".into(),
                     default_value_token: None,
-                    doc: None
+                    doc: None,
+                    span: None,
+                }],
+                function_calls: vec![FunctionCall {
+                    caller: None,
+                    callee: "Flag".into(),
                 }],
+                stats: ModuleStats {
+                    blank_lines: 0,
+                    comment_lines: 0,
+                    doc_comment_lines: 0,
+                    code_lines: 1,
+                },
+                ..Default::default()
             }
         );
     }
@@ -712,7 +1808,7 @@ let g:somevar = 'xyz' | let s:othervar = system("ls")
 " A flag for the value of a thing.
 call Flag('someflag', 'somedefault')
 "#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
@@ -722,7 +1818,19 @@ call Flag('someflag', 'somedefault')
                     name: "someflag".into(),
                     default_value_token: Some("'somedefault'".into()),
                     doc: Some("A flag for the value of a thing.".into()),
+                    span: None,
                 }],
+                function_calls: vec![FunctionCall {
+                    caller: None,
+                    callee: "Flag".into(),
+                }],
+                stats: ModuleStats {
+                    blank_lines: 1,
+                    comment_lines: 2,
+                    doc_comment_lines: 2,
+                    code_lines: 1,
+                },
+                ..Default::default()
             }
         );
     }
@@ -736,7 +1844,7 @@ if !s:enter
 endif
 call s:plugin.Flag('someflag', 'somedefault')
 "#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
@@ -747,18 +1855,33 @@ call s:plugin.Flag('someflag', 'somedefault')
                         name: "s:plugin".into(),
                         init_value_token: "plugin#Enter(expand('<sfile>:p'))[0]".into(),
                         doc: None,
+                        span: None,
                     },
                     VimNode::Variable {
                         name: "s:enter".into(),
                         init_value_token: "plugin#Enter(expand('<sfile>:p'))[1]".into(),
                         doc: None,
+                        span: None,
                     },
                     VimNode::Flag {
                         name: "someflag".into(),
                         default_value_token: Some("'somedefault'".into()),
-                        doc: None
+                        doc: None,
+                        span: None,
                     },
                 ],
+                autoload_references: vec!["plugin#Enter".into()],
+                function_calls: vec![FunctionCall {
+                    caller: None,
+                    callee: "plugin#Enter".into(),
+                }],
+                stats: ModuleStats {
+                    blank_lines: 1,
+                    comment_lines: 0,
+                    doc_comment_lines: 0,
+                    code_lines: 5,
+                },
+                ..Default::default()
             }
         );
     }
@@ -766,7 +1889,7 @@ call s:plugin.Flag('someflag', 'somedefault')
     #[test]
     fn parse_module_flag_name_special_chars() {
         let code = r#"call Flag("some\"'flag֎")"#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
@@ -775,8 +1898,20 @@ call s:plugin.Flag('someflag', 'somedefault')
                 nodes: vec![VimNode::Flag {
                     name: r#"some"'flag֎"#.into(),
                     default_value_token: None,
-                    doc: None
+                    doc: None,
+                    span: None,
+                }],
+                function_calls: vec![FunctionCall {
+                    caller: None,
+                    callee: "Flag".into(),
                 }],
+                stats: ModuleStats {
+                    blank_lines: 0,
+                    comment_lines: 0,
+                    doc_comment_lines: 0,
+                    code_lines: 1,
+                },
+                ..Default::default()
             }
         );
     }
@@ -787,45 +1922,489 @@ call s:plugin.Flag('someflag', 'somedefault')
 " Some normal comment.
 call SomeFunc()
 "#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
                 path: None,
                 doc: None,
                 nodes: vec![],
+                function_calls: vec![FunctionCall {
+                    caller: None,
+                    callee: "SomeFunc".into(),
+                }],
+                stats: ModuleStats {
+                    blank_lines: 1,
+                    comment_lines: 1,
+                    doc_comment_lines: 0,
+                    code_lines: 1,
+                },
+                ..Default::default()
             }
         );
     }
 
+    #[test]
+    fn parse_module_autoload_references() {
+        let code = r#"
+func! foo#Run() abort
+  let l:Trim = function('foo#util#Trim')
+  call foo#util#Split(g:foo#sep, maktaba#ensure#IsString(s:x))
+  execute 'call bar#Init()'
+endfunc
+command! -complete=customlist,foo#complete#Names Foo call foo#Run()
+nnoremap <Plug>(foo) :call foo#Run()<CR>
+echo 'See issue#12 or foo#Run'
+"#;
+        let parser = VimParser::new().unwrap();
+        assert_eq!(
+            parser.parse_module_str(code).unwrap().autoload_references,
+            vec![
+                "foo#util#Trim",
+                "foo#util#Split",
+                "maktaba#ensure#IsString",
+                "bar#Init",
+                "foo#complete#Names",
+                "foo#Run",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_module_optional_autoload_references() {
+        let code = r#"
+silent! call other#Setup()
+try
+  call x#Init()
+catch /E117/
+  call y#Fallback()
+endtry
+silent call z#Run()
+silent! call y#Fallback()
+try
+  func s:Later()
+    call w#Hook()
+  endfunc
+endtry
+"#;
+        let parser = VimParser::new().unwrap();
+        assert_eq!(
+            parser
+                .parse_module_str(code)
+                .unwrap()
+                .optional_autoload_references,
+            vec!["other#Setup", "x#Init"]
+        );
+    }
+
+    #[test]
+    fn parse_module_autocmds() {
+        let code = r#"
+call foo#Init()
+
+""
+" Filetype detection.
+augroup foo
+  autocmd!
+  ""
+  " Detects foo files.
+  autocmd BufRead,BufNewFile *.foo,*.bar setfiletype foo
+augroup END
+
+autocmd bar FileType vim echo 1
+autocmd VimEnter * call foo#Start()
+"#;
+        let parser = VimParser::new().unwrap();
+        assert_eq!(
+            parser.parse_module_str(code).unwrap().nodes,
+            vec![
+                VimNode::Augroup {
+                    name: "foo".into(),
+                    doc: Some("Filetype detection.".into()),
+                    span: None,
+                },
+                VimNode::Autocmd {
+                    group: Some("foo".into()),
+                    events: vec!["BufRead".into(), "BufNewFile".into()],
+                    patterns: vec!["*.foo".into(), "*.bar".into()],
+                    command: "setfiletype foo".into(),
+                    doc: Some("Detects foo files.".into()),
+                    span: None,
+                },
+                VimNode::Autocmd {
+                    group: Some("bar".into()),
+                    events: vec!["FileType".into()],
+                    patterns: vec!["vim".into()],
+                    command: "echo 1".into(),
+                    doc: None,
+                    span: None,
+                },
+                VimNode::Autocmd {
+                    group: None,
+                    events: vec!["VimEnter".into()],
+                    patterns: vec!["*".into()],
+                    command: "call foo#Start()".into(),
+                    doc: None,
+                    span: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_module_mappings() {
+        let code = r#"
+""
+" Runs foo.
+nnoremap <silent> <buffer> <Leader>f :call foo#Run()<CR>
+xmap <Leader>f <Plug>(foo)
+map! <C-f> foo
+"#;
+        let parser = VimParser::new().unwrap();
+        let nodes = parser.parse_module_str(code).unwrap().nodes;
+        assert_eq!(
+            nodes,
+            vec![
+                VimNode::Mapping {
+                    mode: "n".into(),
+                    noremap: true,
+                    lhs: "<Leader>f".into(),
+                    rhs: ":call foo#Run()<CR>".into(),
+                    arguments: vec!["<silent>".into(), "<buffer>".into()],
+                    doc: Some("Runs foo.".into()),
+                    span: None,
+                },
+                VimNode::Mapping {
+                    mode: "x".into(),
+                    noremap: false,
+                    lhs: "<Leader>f".into(),
+                    rhs: "<Plug>(foo)".into(),
+                    arguments: vec![],
+                    doc: None,
+                    span: None,
+                },
+                VimNode::Mapping {
+                    mode: "!".into(),
+                    noremap: false,
+                    lhs: "<C-f>".into(),
+                    rhs: "foo".into(),
+                    arguments: vec![],
+                    doc: None,
+                    span: None,
+                },
+            ]
+        );
+        assert!(nodes[0]
+            .map_arguments()
+            .is_some_and(|args| args.silent && args.buffer));
+    }
+
+    #[test]
+    fn parse_module_highlights_and_signs() {
+        let code = r#"
+"" Highlights foo errors.
+highlight default link FooError Error
+hi FooTodo guifg=#ff0000 gui=bold
+hi clear FooTodo
+sign define FooSign text=>> texthl=FooError
+sign place 1 line=3 name=FooSign
+"#;
+        let parser = VimParser::new().unwrap();
+        assert_eq!(
+            parser.parse_module_str(code).unwrap().nodes,
+            vec![
+                VimNode::Highlight {
+                    name: "FooError".into(),
+                    default: true,
+                    link: Some("Error".into()),
+                    attributes: vec![],
+                    doc: Some("Highlights foo errors.".into()),
+                    span: None,
+                },
+                VimNode::Highlight {
+                    name: "FooTodo".into(),
+                    default: false,
+                    link: None,
+                    attributes: vec!["guifg=#ff0000".into(), "gui=bold".into()],
+                    doc: None,
+                    span: None,
+                },
+                VimNode::Sign {
+                    name: "FooSign".into(),
+                    attributes: vec!["text=>>".into(), "texthl=FooError".into()],
+                    doc: None,
+                    span: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_module_set_options() {
+        let code = r#"
+"" Two-space indents.
+setlocal sw=2 nowrap
+set cpo+=x
+"#;
+        let parser = VimParser::new().unwrap();
+        let set_option =
+            |name: &str, item: &str, value: Option<&str>, scope: &str| VimNode::SetOption {
+                name: name.into(),
+                item: item.into(),
+                value: value.map(str::to_string),
+                scope: scope.into(),
+                doc: (scope == "local").then(|| "Two-space indents.".into()),
+                span: None,
+            };
+        assert_eq!(
+            parser.parse_module_str(code).unwrap().nodes,
+            vec![
+                set_option("sw", "sw=2", Some("2"), "local"),
+                set_option("wrap", "nowrap", None, "local"),
+                set_option("cpo", "cpo+=x", Some("x"), "both"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_module_vim9script() {
+        let code = r#"vim9script
+
+## Frobs {x}.
+export def Foo(x: number): string
+  command Inner echo 1
+  return "a"
+enddef
+
+command Foo echo Foo(1)
+export var count = 0
+"#;
+        let parser = VimParser::new().unwrap();
+        assert_eq!(
+            parser.parse_module_str(code).unwrap().nodes,
+            vec![
+                VimNode::Function {
+                    name: "Foo".into(),
+                    args: vec!["x".into()],
+                    modifiers: vec!["export".into()],
+                    doc: Some("Frobs {x}.".into()),
+                    body: None,
+                    span: None,
+                },
+                VimNode::Command {
+                    name: "Foo".into(),
+                    modifiers: vec![],
+                    repl: Some("echo Foo(1)".into()),
+                    doc: None,
+                    span: None,
+                },
+                VimNode::Variable {
+                    name: "count".into(),
+                    init_value_token: "0".into(),
+                    doc: None,
+                    span: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_module_record_spans() {
+        let code = r#"""
+" Module doc.
+
+""
+" Frobs.
+function! Foo() abort
+endfunction
+let [g:a, g:b] = [1, 2]
+"#;
+        let parser = VimParser::builder().record_spans(true).build().unwrap();
+        let module = parser.parse_module_str(code).unwrap();
+        assert_eq!(
+            module.doc_span,
+            Some(Span {
+                start: (0, 0),
+                end: (1, 13),
+                byte_range: 0..16,
+            })
+        );
+        assert_eq!(
+            module
+                .nodes
+                .iter()
+                .map(|node| (node.name(), node.span().map(|s| (s.start, s.end))))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some("Foo"), Some(((5, 0), (6, 11)))),
+                (Some("g:a"), Some(((7, 0), (7, 23)))),
+                (Some("g:b"), Some(((7, 0), (7, 23)))),
+            ]
+        );
+        assert_eq!(
+            &code[module.nodes[0].span().unwrap().byte_range.clone()],
+            "function! Foo() abort\nendfunction"
+        );
+
+        let parser = VimParser::new().unwrap();
+        let module = parser.parse_module_str(code).unwrap();
+        assert_eq!(
+            (module.nodes.iter().find_map(VimNode::span), module.doc_span),
+            (None, None)
+        );
+    }
+
+    #[test]
+    fn parse_module_collects_warnings() {
+        let code = "let g:x = 1\nif (\nlet g:y = 2\n";
+        let parser = VimParser::new().unwrap();
+        let module = parser.parse_module_str(code).unwrap();
+        assert_eq!(
+            module.nodes.iter().map(|n| n.name()).collect::<Vec<_>>(),
+            vec![Some("g:x"), Some("g:y")]
+        );
+        assert_eq!(
+            module
+                .warnings
+                .iter()
+                .map(|w| (w.kind, w.to_string()))
+                .collect::<Vec<_>>(),
+            vec![(
+                WarningKind::SyntaxError,
+                "2:1: Syntax error near \"if (\"".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn parse_module_language_detection() {
+        assert_eq!(
+            ModuleLanguage::detect(Some(Path::new("lua/foo.lua")), "vim9script"),
+            ModuleLanguage::Lua
+        );
+        assert_eq!(
+            ModuleLanguage::detect(None, "\" Comment.\nvim9script\n"),
+            ModuleLanguage::Vim9
+        );
+        assert_eq!(
+            ModuleLanguage::detect(None, "let x = 1"),
+            ModuleLanguage::Vim
+        );
+        let parser = VimParser::new().unwrap();
+        let module = parser
+            .parse_module_str_as("function M.setup() end", ModuleLanguage::Lua)
+            .unwrap();
+        assert_eq!(module.language, ModuleLanguage::Lua);
+        assert_eq!(module.nodes, vec![VimNode::function("M.setup").build()]);
+    }
+
+    #[test]
+    fn parser_pool_parses_with_each_grammar() {
+        let pool = ParserPool::new().unwrap();
+        for _ in 0..2 {
+            let lua = pool.reparse("local x = 1", Grammar::Lua, None).unwrap();
+            assert_eq!(lua.root_node().kind(), "chunk");
+            let vim = pool.reparse("let x = 1", Grammar::Vim, None).unwrap();
+            assert_eq!(vim.root_node().kind(), "script_file");
+        }
+        // Parsers are returned to the pool for their own grammar.
+        let idle = pool.lock();
+        assert_eq!(
+            (idle[&Grammar::Vim].len(), idle[&Grammar::Lua].len()),
+            (1, 1)
+        );
+    }
+
     #[test]
     fn parse_module_unicode() {
         let code = r#"
 ""
 " Fun stuff 🎈 ( ͡° ͜ʖ ͡°)
 "#;
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         assert_eq!(
             parser.parse_module_str(code).unwrap(),
             VimModule {
                 path: None,
                 doc: Some("Fun stuff 🎈 ( ͡° ͜ʖ ͡°)".into()),
                 nodes: vec![],
+                stats: ModuleStats {
+                    blank_lines: 1,
+                    comment_lines: 2,
+                    doc_comment_lines: 2,
+                    code_lines: 0,
+                },
+                ..Default::default()
             }
         );
     }
 
+    #[test]
+    fn parse_module_file_error_has_path() {
+        let tmp_dir = tempdir().unwrap();
+        let path = tmp_dir.path().join("plugin/missing.vim");
+        let err = VimParser::new()
+            .unwrap()
+            .parse_module_file(&path)
+            .unwrap_err();
+        assert_eq!(err.path(), Some(path.as_path()));
+        assert_eq!(err.position(), None);
+        assert!(matches!(err.inner(), Error::IOError(_)));
+        assert!(err
+            .to_string()
+            .starts_with(&format!("{}: I/O error: ", path.display())));
+    }
+
+    #[test]
+    fn parse_plugin_file_infers_section() {
+        let tmp_dir = tempdir().unwrap();
+        create_plugin_file(
+            tmp_dir.path(),
+            "scripts/frob.vim",
+            "\" Version: 1.2\ncommand Frob echo 1",
+        );
+        create_plugin_file(
+            tmp_dir.path(),
+            "autoload/frob.vim",
+            "func frob#X()\nendfunc",
+        );
+        let parser = VimParser::new().unwrap();
+        let plugin = parser
+            .parse_plugin_file(tmp_dir.path().join("scripts/frob.vim"))
+            .unwrap();
+        assert_eq!(plugin.metadata.name.as_deref(), Some("frob"));
+        assert_eq!(plugin.metadata.version.as_deref(), Some("1.2"));
+        assert_eq!(plugin.root, None);
+        assert_eq!(
+            plugin.content[0].path.as_deref(),
+            Some(Path::new("plugin/frob.vim"))
+        );
+        let plugin = parser
+            .parse_plugin_file(tmp_dir.path().join("autoload/frob.vim"))
+            .unwrap();
+        assert_eq!(
+            plugin.content[0].path.as_deref(),
+            Some(Path::new("autoload/frob.vim"))
+        );
+    }
+
     #[test]
     fn parse_plugin_dir_empty() {
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         let tmp_dir = tempdir().unwrap();
         let plugin = parser.parse_plugin_dir(tmp_dir.path()).unwrap();
-        assert_eq!(plugin, VimPlugin { content: vec![] });
+        assert_eq!(
+            plugin,
+            VimPlugin {
+                root: Some(tmp_dir.path().to_owned()),
+                ..Default::default()
+            }
+        );
     }
 
     #[test]
     fn parse_plugin_dir_one_autoload_func() {
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         let tmp_dir = tempdir().unwrap();
         create_plugin_file(
             tmp_dir.path(),
@@ -840,6 +2419,7 @@ endfunc
         assert_eq!(
             plugin,
             VimPlugin {
+                root: Some(tmp_dir.path().to_owned()),
                 content: vec![VimModule {
                     path: PathBuf::from("autoload/foo.vim").into(),
                     doc: None,
@@ -847,16 +2427,236 @@ endfunc
                         name: "foo#Bar".into(),
                         args: vec![],
                         modifiers: vec![],
-                        doc: None
-                    }]
+                        doc: None,
+                        body: None,
+                        span: None,
+                    }],
+                    stats: ModuleStats {
+                        blank_lines: 1,
+                        comment_lines: 0,
+                        doc_comment_lines: 0,
+                        code_lines: 3,
+                    },
+                    ..Default::default()
                 }],
+                ..Default::default()
             }
         );
     }
 
+    #[test]
+    fn parse_plugin_dir_custom_sections() {
+        let parser = VimParser::builder()
+            .sections([SectionKind::Autoload, SectionKind::Plugin])
+            .include_after(false)
+            .build()
+            .unwrap();
+        let tmp_dir = tempdir().unwrap();
+        for subpath in [
+            "plugin/foo.vim",
+            "autoload/foo.vim",
+            "ftplugin/foo.vim",
+            "after/plugin/foo.vim",
+        ] {
+            create_plugin_file(tmp_dir.path(), subpath, "");
+        }
+        let plugin = parser.parse_plugin_dir(tmp_dir.path()).unwrap();
+        assert_eq!(
+            plugin
+                .content
+                .iter()
+                .map(|m| m.path.as_ref().unwrap().to_str().unwrap())
+                .collect::<Vec<_>>(),
+            vec!["autoload/foo.vim", "plugin/foo.vim"]
+        );
+    }
+
+    #[test]
+    fn list_plugin_files_without_parsing() {
+        let parser = VimParser::new().unwrap();
+        let tmp_dir = tempdir().unwrap();
+        for subpath in [
+            "after/plugin/foo.vim",
+            "plugin/foo.vim",
+            "menu.vim",
+            "lua/foo/init.lua",
+            "doc/foo.txt",
+        ] {
+            // Invalid syntax, which is fine since nothing is parsed.
+            create_plugin_file(tmp_dir.path(), subpath, "function! (");
+        }
+        assert_eq!(
+            parser
+                .list_plugin_files(tmp_dir.path())
+                .unwrap()
+                .iter()
+                .map(|f| (f.path.to_str().unwrap(), f.section.to_string()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("menu.vim", "menu".to_string()),
+                ("plugin/foo.vim", "plugin".to_string()),
+                ("lua/foo/init.lua", "lua".to_string()),
+                ("after/plugin/foo.vim", "after/plugin".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_plugin_dir_case_insensitive_paths() {
+        let tmp_dir = tempdir().unwrap();
+        create_plugin_file(tmp_dir.path(), "Plugin/Foo.VIM", "let g:foo = 1");
+        create_plugin_file(tmp_dir.path(), "plugin/bar.vim", "let g:bar = 1");
+        let module_paths = |parser: &VimParser| {
+            parser
+                .parse_plugin_dir(tmp_dir.path())
+                .unwrap()
+                .content
+                .into_iter()
+                .map(|module| module.path.unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            module_paths(&VimParser::new().unwrap()),
+            vec![PathBuf::from("plugin/bar.vim")]
+        );
+        let parser = VimParser::builder()
+            .case_insensitive_paths(true)
+            .build()
+            .unwrap();
+        let mut paths = module_paths(&parser);
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![PathBuf::from("Plugin/Foo.VIM"), "plugin/bar.vim".into()]
+        );
+        assert_eq!(
+            parser.classify_runtime_path("Plugin/Foo.VIM"),
+            Some(Section {
+                kind: SectionKind::Plugin,
+                after: false
+            })
+        );
+        assert_eq!(
+            VimParser::new()
+                .unwrap()
+                .classify_runtime_path("Plugin/Foo.VIM"),
+            None
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn parse_plugin_dir_duplicate_modules() {
+        let tmp_dir = tempdir().unwrap();
+        create_plugin_file(tmp_dir.path(), "plugin/foo.vim", "let g:foo = 1");
+        std::os::unix::fs::symlink(
+            tmp_dir.path().join("plugin/foo.vim"),
+            tmp_dir.path().join("plugin/link.vim"),
+        )
+        .unwrap();
+        let parse = |duplicate_modules| {
+            VimParser::builder()
+                .duplicate_modules(duplicate_modules)
+                .build()
+                .unwrap()
+                .parse_plugin_dir(tmp_dir.path())
+        };
+        assert_eq!(parse(DuplicateModules::FirstWins).unwrap().content.len(), 1);
+        assert_eq!(parse(DuplicateModules::KeepBoth).unwrap().content.len(), 2);
+        assert!(matches!(
+            parse(DuplicateModules::Error),
+            Err(Error::DuplicateModule { .. })
+        ));
+    }
+
+    #[test]
+    fn parse_plugin_dirs_bundle_and_pack() {
+        let tmp_dir = tempdir().unwrap();
+        create_plugin_file(tmp_dir.path(), "bundle/foo/plugin/foo.vim", "let g:foo = 1");
+        create_plugin_file(
+            tmp_dir.path(),
+            "pack/a/start/bar/autoload/bar.vim",
+            "func bar#Baz()\nendfunc",
+        );
+        let plugins = VimParser::new()
+            .unwrap()
+            .parse_plugin_dirs([tmp_dir.path().join("bundle"), tmp_dir.path().join("pack")])
+            .unwrap();
+        assert_eq!(
+            plugins
+                .iter()
+                .map(|(name, plugin)| (name, plugin.content.len()))
+                .collect::<Vec<_>>(),
+            vec![("foo", 1), ("bar", 1)]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn parse_plugin_dir_non_unicode_path() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let tmp_dir = tempdir().unwrap();
+        let file_name = OsStr::from_bytes(b"foo\xff.vim");
+        let Ok(()) = fs::create_dir_all(tmp_dir.path().join("plugin"))
+            .and_then(|()| fs::write(tmp_dir.path().join("plugin").join(file_name), "let g:x = 1"))
+        else {
+            // Some filesystems reject non-unicode names.
+            return;
+        };
+        let plugin = VimParser::new()
+            .unwrap()
+            .parse_plugin_dir(tmp_dir.path())
+            .unwrap();
+        assert_eq!(
+            plugin.content[0].nodes,
+            vec![VimNode::variable("g:x", "1").into()]
+        );
+        assert_eq!(
+            plugin
+                .warnings()
+                .map(|(_, warning)| warning.kind)
+                .collect::<Vec<_>>(),
+            vec![WarningKind::Path]
+        );
+    }
+
+    #[test]
+    fn parse_plugin_dir_record_profile() {
+        let tmp_dir = tempdir().unwrap();
+        create_plugin_file(
+            tmp_dir.path(),
+            "plugin/foo.vim",
+            "let g:a = 1\nlet g:b = 2\n",
+        );
+        create_plugin_file(tmp_dir.path(), "autoload/foo.vim", "");
+        let plugin = VimParser::builder()
+            .record_profile(true)
+            .build()
+            .unwrap()
+            .parse_plugin_dir(tmp_dir.path())
+            .unwrap();
+        assert_eq!(
+            plugin
+                .profile
+                .unwrap()
+                .modules
+                .iter()
+                .map(|m| (m.path.to_str().unwrap(), m.bytes, m.node_count))
+                .collect::<Vec<_>>(),
+            vec![("plugin/foo.vim", 24, 2), ("autoload/foo.vim", 0, 0)]
+        );
+        let plugin = VimParser::new()
+            .unwrap()
+            .parse_plugin_dir(tmp_dir.path())
+            .unwrap();
+        assert_eq!(plugin.profile, None);
+    }
+
     #[test]
     fn parse_plugin_dir_various_subdirs() {
-        let mut parser = VimParser::new().unwrap();
+        let parser = VimParser::new().unwrap();
         let tmp_dir = tempdir().unwrap();
         for path in [
             // Ignored paths.
@@ -880,6 +2680,7 @@ endfunc
         assert_eq!(
             parser.parse_plugin_dir(tmp_dir.path()).unwrap(),
             VimPlugin {
+                root: Some(tmp_dir.path().to_owned()),
                 content: [
                     "menu.vim",
                     "plugin/x.vim",
@@ -897,12 +2698,385 @@ endfunc
                     path: PathBuf::from(path).into(),
                     doc: None,
                     nodes: vec![],
+                    ..Default::default()
                 })
-                .collect()
+                .collect(),
+                ..Default::default()
             }
         );
     }
 
+    #[test]
+    fn parse_plugin_dir_vendored() {
+        let mut parser = VimParser::new().unwrap();
+        let tmp_dir = tempdir().unwrap();
+        create_plugin_file(tmp_dir.path(), "plugin/foo.vim", "");
+        create_plugin_file(tmp_dir.path(), "bundle/bar/plugin/bar.vim", "");
+        assert_eq!(
+            parser.parse_plugin_dir(tmp_dir.path()).unwrap().vendored,
+            vec![VendoredPlugin {
+                path: PathBuf::from("bundle/bar"),
+                plugin: None,
+            }]
+        );
+        parser.set_vendored_plugins(VendoredPlugins::ParseNested);
+        assert_eq!(
+            parser.parse_plugin_dir(tmp_dir.path()).unwrap().vendored,
+            vec![VendoredPlugin {
+                path: PathBuf::from("bundle/bar"),
+                plugin: Some(VimPlugin {
+                    root: Some(tmp_dir.path().join("bundle/bar")),
+                    ..VimPlugin::new(vec![VimModule::default().with_path("plugin/bar.vim")])
+                }),
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_plugin_dir_remote_plugins() {
+        let parser = VimParser::new().unwrap();
+        let tmp_dir = tempdir().unwrap();
+        create_plugin_file(tmp_dir.path(), "rplugin/python3/foo.py", "");
+        create_plugin_file(tmp_dir.path(), "rplugin/node/bar/index.js", "");
+        create_plugin_file(
+            tmp_dir.path(),
+            "plugin/foo.vim",
+            r#"
+call remote#host#RegisterPlugin('python3', '/x/foo/rplugin/python3/foo.py', [
+      \ {'sync': v:true, 'name': 'Foo', 'type': 'command', 'opts': {'nargs': '*'}},
+      \ {'sync': v:false, 'name': 'BufEnter', 'type': 'autocmd', 'opts': {'pattern': '*'}},
+     \ ])
+"#,
+        );
+        assert_eq!(
+            parser
+                .parse_plugin_dir(tmp_dir.path())
+                .unwrap()
+                .remote_plugins,
+            vec![
+                RemotePlugin {
+                    host: "node".into(),
+                    path: PathBuf::from("rplugin/node/bar"),
+                    registrations: vec![],
+                },
+                RemotePlugin {
+                    host: "python3".into(),
+                    path: PathBuf::from("rplugin/python3/foo.py"),
+                    registrations: vec![
+                        RemoteRegistration {
+                            kind: "command".into(),
+                            name: "Foo".into(),
+                            sync: true,
+                        },
+                        RemoteRegistration {
+                            kind: "autocmd".into(),
+                            name: "BufEnter".into(),
+                            sync: false,
+                        },
+                    ],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_plugin_dir_queries() {
+        let parser = VimParser::new().unwrap();
+        let tmp_dir = tempdir().unwrap();
+        create_plugin_file(
+            tmp_dir.path(),
+            "queries/python/highlights.scm",
+            "(decorator) @attribute\n(identifier) @variable",
+        );
+        create_plugin_file(tmp_dir.path(), "queries/python/README.md", "");
+        create_plugin_file(
+            tmp_dir.path(),
+            "after/queries/lua/textobjects.scm",
+            "(function_definition) @function.outer",
+        );
+        let plugin = parser.parse_plugin_dir(tmp_dir.path()).unwrap();
+        assert_eq!(
+            plugin.queries,
+            vec![
+                TreeSitterQuery {
+                    language: "python".into(),
+                    query_type: "highlights".into(),
+                    path: PathBuf::from("queries/python/highlights.scm"),
+                    captures: vec!["attribute".into(), "variable".into()],
+                },
+                TreeSitterQuery {
+                    language: "lua".into(),
+                    query_type: "textobjects".into(),
+                    path: PathBuf::from("after/queries/lua/textobjects.scm"),
+                    captures: vec!["function.outer".into()],
+                },
+            ]
+        );
+        assert_eq!(plugin.query_languages("highlights"), vec!["python"]);
+    }
+
+    #[test]
+    fn parse_config_str_vimrc() {
+        let code = r#"
+set nocompatible sw=2
+call plug#begin()
+Plug 'tpope/vim-fugitive'
+call plug#end()
+packadd! matchit
+if has('nvim')
+  nnoremap <silent> <Leader>f :Files<CR>
+endif
+let &tabstop = 4
+augroup vimrc
+  autocmd!
+  autocmd BufRead,BufNewFile *.py setlocal et
+augroup END
+"#;
+        let parser = VimParser::new().unwrap();
+        let config = parser.parse_config_str(code).unwrap();
+        assert_eq!(
+            config.settings,
+            vec![
+                ConfigSetting {
+                    command: "set".into(),
+                    name: "compatible".into(),
+                    item: "nocompatible".into(),
+                    value: None,
+                },
+                ConfigSetting {
+                    command: "set".into(),
+                    name: "sw".into(),
+                    item: "sw=2".into(),
+                    value: Some("2".into()),
+                },
+                ConfigSetting {
+                    command: "let".into(),
+                    name: "tabstop".into(),
+                    item: "&tabstop".into(),
+                    value: Some("4".into()),
+                },
+                ConfigSetting {
+                    command: "setlocal".into(),
+                    name: "et".into(),
+                    item: "et".into(),
+                    value: None,
+                },
+            ]
+        );
+        assert_eq!(
+            config.mappings,
+            vec![ConfigMapping {
+                command: "nnoremap".into(),
+                arguments: vec!["<silent>".into()],
+                lhs: "<Leader>f".into(),
+                rhs: ":Files<CR>".into(),
+            }]
+        );
+        assert_eq!(
+            config.autocmds,
+            vec![ConfigAutocmd {
+                group: Some("vimrc".into()),
+                events: vec!["BufRead".into(), "BufNewFile".into()],
+                patterns: vec!["*.py".into()],
+                command: "setlocal et".into(),
+            }]
+        );
+        assert_eq!(
+            config.plugin_declarations,
+            vec![
+                PluginDeclaration {
+                    manager: PluginManager::VimPlug,
+                    name: "tpope/vim-fugitive".into(),
+                    options: vec![],
+                },
+                PluginDeclaration {
+                    manager: PluginManager::Packadd,
+                    name: "matchit".into(),
+                    options: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_plugin_declarations_str_options() {
+        let code = r#"
+Plug 'junegunn/fzf', { 'branch': 'devel', 'on': ['FZF', 'Files'], 'for': 'go' }
+call dein#add('Shougo/ddc.vim', {'rev': 'main', 'on_ft': ['python', 'vim']})
+lua << EOF
+use 'wbthomason/packer.nvim'
+use {'nvim-treesitter/nvim-treesitter', branch = 'v0.9', ft = {'lua', 'c'}}
+EOF
+"#;
+        let parser = VimParser::new().unwrap();
+        let declarations = parser.parse_plugin_declarations_str(code).unwrap();
+        assert_eq!(
+            declarations
+                .iter()
+                .map(|d| (
+                    d.manager,
+                    d.name.as_str(),
+                    d.branch(),
+                    d.on(),
+                    d.for_filetypes()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    PluginManager::VimPlug,
+                    "junegunn/fzf",
+                    Some("devel".to_string()),
+                    vec!["FZF".to_string(), "Files".to_string()],
+                    vec!["go".to_string()],
+                ),
+                (
+                    PluginManager::Dein,
+                    "Shougo/ddc.vim",
+                    Some("main".to_string()),
+                    vec![],
+                    vec!["python".to_string(), "vim".to_string()],
+                ),
+                (
+                    PluginManager::Packer,
+                    "wbthomason/packer.nvim",
+                    None,
+                    vec![],
+                    vec![],
+                ),
+                (
+                    PluginManager::Packer,
+                    "nvim-treesitter/nvim-treesitter",
+                    Some("v0.9".to_string()),
+                    vec![],
+                    vec!["lua".to_string(), "c".to_string()],
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_plugin_dir_error_policy() {
+        let tmp_dir = tempdir().unwrap();
+        create_plugin_file(tmp_dir.path(), "plugin/foo.vim", "command Foo echo\n");
+        fs::create_dir_all(tmp_dir.path().join("autoload")).unwrap();
+        fs::write(tmp_dir.path().join("autoload/old.vim"), b"\" Caf\xe9\n").unwrap();
+        let builder = || VimParser::builder().encoding_fallback(EncodingFallback::Strict);
+        let err = builder()
+            .build()
+            .unwrap()
+            .parse_plugin_dir(tmp_dir.path())
+            .unwrap_err();
+        assert_eq!(
+            err.path(),
+            Some(tmp_dir.path().join("autoload/old.vim").as_path())
+        );
+        assert_eq!(err.position(), Some((0, 5)));
+        let plugin = builder()
+            .error_policy(ErrorPolicy::CollectErrors)
+            .build()
+            .unwrap()
+            .parse_plugin_dir(tmp_dir.path())
+            .unwrap();
+        assert_eq!(
+            plugin
+                .content
+                .iter()
+                .map(|module| module.path.as_deref().unwrap())
+                .collect::<Vec<_>>(),
+            vec![Path::new("plugin/foo.vim")]
+        );
+        assert_eq!(
+            plugin.errors,
+            vec![ModuleError {
+                path: PathBuf::from("autoload/old.vim"),
+                position: Some((0, 5)),
+                message: "I/O error: File isn't valid UTF-8 text".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_module_with_tree_custom_query() {
+        use tree_sitter::{Query, QueryCursor};
+
+        let code = "call foo#Init()\nfunc! s:Helper() abort\n  call foo#Run()\nendfunc\n";
+        let (module, tree) = VimParser::new()
+            .unwrap()
+            .parse_module_with_tree(code)
+            .unwrap();
+        assert_eq!(
+            module,
+            VimParser::new().unwrap().parse_module_str(code).unwrap()
+        );
+        let query = Query::new(
+            &tree.language(),
+            "(call_statement (call_expression function: (identifier) @name))",
+        )
+        .unwrap();
+        let names: Vec<&str> = QueryCursor::new()
+            .matches(&query, tree.root_node(), code.as_bytes())
+            .map(|m| m.captures[0].node.utf8_text(code.as_bytes()).unwrap())
+            .collect();
+        assert_eq!(names, vec!["foo#Init", "foo#Run"]);
+    }
+
+    #[test]
+    fn parse_module_str_function_bodies() {
+        let code = r#"
+func! s:Add(a, b) abort
+  " Sum.
+  return a:a + a:b
+endfunc
+func! s:Empty()
+endfunc
+"#;
+        let bodies = |nodes: Vec<&VimNode>| -> Vec<Option<String>> {
+            nodes
+                .into_iter()
+                .filter_map(|node| match node {
+                    VimNode::Function { body, .. } => Some(body.clone()),
+                    _ => None,
+                })
+                .collect()
+        };
+        let module = VimParser::new().unwrap().parse_module_str(code).unwrap();
+        assert_eq!(bodies(module.nodes.iter().collect()), vec![None, None]);
+        let parser = VimParser::builder()
+            .function_body_capture(FunctionBodyCapture::Source)
+            .build()
+            .unwrap();
+        let module = parser.parse_module_str(code).unwrap();
+        assert_eq!(
+            bodies(module.nodes.iter().collect()),
+            vec![
+                Some("  \" Sum.\n  return a:a + a:b\n".into()),
+                Some("".into())
+            ]
+        );
+        // Bodies are captured without spans, and for nested functions too.
+        assert_eq!(module.nodes[0].span(), None);
+        let trees = parser
+            .parse_module_tree("function! Outer()\n  function! Inner()\n    return 1\n  endfunction\nendfunction\n")
+            .unwrap();
+        assert_eq!(
+            bodies(VimNodeTree::flatten(&trees).collect()),
+            vec![
+                Some("  function! Inner()\n    return 1\n  endfunction\n".into()),
+                Some("    return 1\n".into())
+            ]
+        );
+        let module = VimParser::builder()
+            .function_body_capture(FunctionBodyCapture::Tokens)
+            .build()
+            .unwrap()
+            .parse_module_str(code)
+            .unwrap();
+        assert_eq!(
+            bodies(module.nodes.iter().collect()),
+            vec![Some("return a: $0 + a: $1".into()), Some("".into())]
+        );
+    }
+
     fn create_plugin_file<P: AsRef<Path>>(root: &Path, subpath: P, contents: &str) {
         let filepath = root.join(subpath);
         fs::create_dir_all(filepath.parent().unwrap()).unwrap();
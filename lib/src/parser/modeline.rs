@@ -0,0 +1,97 @@
+use crate::data::Modeline;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// How many lines at the start and end of a file vim checks for modelines by default.
+const MODELINES: usize = 5;
+
+/// Finds modelines in the first or last few lines of a module, like vim does when 'modeline' is
+/// set. Options from several modelines are combined in the order vim applies them.
+pub fn find_modeline(code: &str) -> Option<Modeline> {
+    let lines: Vec<&str> = code.lines().collect();
+    let head = lines.len().min(MODELINES);
+    let tail_start = lines.len().saturating_sub(MODELINES).max(head);
+    let options: Vec<_> = lines[..head]
+        .iter()
+        .chain(&lines[tail_start..])
+        .flat_map(|line| parse_modeline(line))
+        .collect();
+    (!options.is_empty()).then_some(Modeline { options })
+}
+
+fn parse_modeline(line: &str) -> Vec<(String, Option<String>)> {
+    static MARKER: OnceLock<Regex> = OnceLock::new();
+    static SET_FORM: OnceLock<Regex> = OnceLock::new();
+    let marker = MARKER.get_or_init(|| Regex::new(r"(?:^|\s)(?:vi|[vV]im[<=>]?\d*|ex):").unwrap());
+    let Some(m) = marker.find(line) else {
+        return vec![];
+    };
+    let rest = &line[m.end()..];
+    let set_form = SET_FORM.get_or_init(|| Regex::new(r"^\s*set? ").unwrap());
+    match set_form.find(rest) {
+        // "se[t] {options}:" ends at the first unescaped colon, and anything after is ignored.
+        Some(m) => split_options(&rest[m.end()..], true),
+        None => split_options(rest, false),
+    }
+}
+
+fn split_options(text: &str, set_form: bool) -> Vec<(String, Option<String>)> {
+    let mut options = vec![];
+    let mut current = String::new();
+    let mut chars = text.chars();
+    let mut flush = |current: &mut String| {
+        if !current.is_empty() {
+            let option = match current.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (current.clone(), None),
+            };
+            options.push(option);
+            current.clear();
+        }
+    };
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.clone().next() == Some(':') => {
+                current.push(':');
+                chars.next();
+            }
+            ':' if set_form => break,
+            ':' => flush(&mut current),
+            c if c.is_whitespace() => flush(&mut current),
+            c => current.push(c),
+        }
+    }
+    flush(&mut current);
+    options
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn find_modeline_forms() {
+        assert_eq!(
+            find_modeline("\" vim: set sw=2 et fdm=marker fmr=\\:{,\\:}: trailing text\n"),
+            Some(Modeline {
+                options: vec![
+                    ("sw".into(), Some("2".into())),
+                    ("et".into(), None),
+                    ("fdm".into(), Some("marker".into())),
+                    ("fmr".into(), Some(":{,:}".into())),
+                ]
+            })
+        );
+        let modeline = find_modeline("\" vim600:ts=8:noet\n\" ex: ts=2\n").unwrap();
+        assert_eq!(modeline.option("ts"), Some(Some("2")));
+        assert_eq!(modeline.option("noet"), Some(None));
+        // Only the first and last few lines count, and "vim:" needs whitespace before it.
+        let code = format!(
+            "\" xvim: et\n{}\" vi: ts=4\n{}",
+            "\n".repeat(6),
+            "\n".repeat(6)
+        );
+        assert_eq!(find_modeline(&code), None);
+    }
+}
@@ -0,0 +1,156 @@
+use super::treenodes::TreeNodeMetadata;
+use crate::{VimNode, VimNodeTree};
+use tree_sitter::{Node, Tree};
+
+/// Arranges a module's top-level nodes by how they're nested in its syntax tree, adding the
+/// definitions nested inside blocks like functions, `if` statements and loops.
+///
+/// `module_nodes` must have their spans recorded, to match them up with the statements that
+/// defined them.
+pub(super) fn node_trees(tree: &Tree, code: &str, module_nodes: Vec<VimNode>) -> Vec<VimNodeTree> {
+    let root = tree.root_node();
+    let mut nodes = module_nodes.into_iter().peekable();
+    let mut level = Level::default();
+    let mut cursor = root.walk();
+    for statement in root.named_children(&mut cursor) {
+        let (first_row, last_row) = (statement.start_position().row, statement.end_position().row);
+        // Nodes the grammar couldn't parse as statements, like Vim9 definitions, come first.
+        while let Some(node) = nodes.next_if(|node| start_row(node) < first_row) {
+            level.push(VimNodeTree::leaf(node));
+        }
+        let mut defined = vec![];
+        while let Some(node) = nodes.next_if(|node| start_row(node) <= last_row) {
+            defined.push(node);
+        }
+        level.push_statement(statement, defined, code);
+    }
+    for node in nodes {
+        level.push(VimNodeTree::leaf(node));
+    }
+    level.trees
+}
+
+fn start_row(node: &VimNode) -> usize {
+    node.span().map_or(0, |span| span.start.0)
+}
+
+/// The trees for the statements of one block, like a function body or the top level of a module.
+#[derive(Default)]
+struct Level {
+    trees: Vec<VimNodeTree>,
+    /// Index in `trees` of the augroup whose block is open, if any.
+    augroup: Option<usize>,
+}
+
+impl Level {
+    /// Adds a tree at this level, or under the open augroup.
+    fn push(&mut self, mut tree: VimNodeTree) {
+        let Some(augroup) = self.augroup else {
+            self.trees.push(tree);
+            return;
+        };
+        let parent = &mut self.trees[augroup];
+        if let (
+            VimNode::Autocmd {
+                group: group @ None,
+                ..
+            },
+            VimNode::Augroup { name, .. },
+        ) = (&mut tree.node, &parent.node)
+        {
+            *group = Some(name.clone());
+        }
+        parent.children.push(tree);
+    }
+
+    /// Adds the nodes a statement defined, with any definitions nested in its blocks.
+    fn push_statement(&mut self, statement: Node, mut defined: Vec<VimNode>, code: &str) {
+        let nested = nested_trees(statement, code);
+        if statement.kind() == "augroup_statement" {
+            // `augroup END` defines no node and closes the block.
+            self.augroup = None;
+            if let Some(augroup) = defined.pop() {
+                self.trees.push(VimNodeTree::leaf(augroup));
+                self.augroup = Some(self.trees.len() - 1);
+            }
+            return;
+        }
+        let function = (statement.kind() == "function_definition")
+            .then(|| defined.pop())
+            .flatten();
+        for node in defined {
+            self.push(VimNodeTree::leaf(node));
+        }
+        match function {
+            Some(node) => self.push(VimNodeTree {
+                node,
+                children: nested,
+            }),
+            // Blocks like `if` and `for` aren't nodes themselves, so their contents stay here.
+            None => {
+                for tree in nested {
+                    self.push(tree);
+                }
+            }
+        }
+    }
+}
+
+/// Trees for the statements in the blocks of `statement`, like its function body or the
+/// branches of an `if`.
+fn nested_trees(statement: Node, code: &str) -> Vec<VimNodeTree> {
+    let mut trees = vec![];
+    let mut cursor = statement.walk();
+    for child in statement.named_children(&mut cursor) {
+        if child.kind() == "body" {
+            trees.extend(block_trees(child, code));
+        } else {
+            // Branches like `elseif` and `catch` hold their own bodies.
+            trees.extend(nested_trees(child, code));
+        }
+    }
+    trees
+}
+
+/// Trees for the statements of a block, attaching doc comments like the module's top level does.
+fn block_trees(block: Node, code: &str) -> Vec<VimNodeTree> {
+    let source = code.as_bytes();
+    let mut level = Level::default();
+    let mut last_comment: Option<TreeNodeMetadata> = None;
+    let mut cursor = block.walk();
+    let mut statements = block.named_children(&mut cursor).peekable();
+    while let Some(statement) = statements.next() {
+        let mut metadata = TreeNodeMetadata::from((statement, source));
+        if statement.kind() == "comment" {
+            metadata.absorb_comment_lines(&mut statements);
+        }
+        metadata.maybe_consume_doc(&mut last_comment);
+        if let Some(comment) = last_comment.take() {
+            level.push_statement(comment.treenodes[0], spanned_nodes(comment), code);
+        }
+        let last_row = metadata.span().end.0;
+        if statement.kind() == "comment"
+            && statements
+                .peek()
+                .is_some_and(|next| next.start_position().row == last_row + 1)
+        {
+            last_comment = Some(metadata);
+            continue;
+        }
+        level.push_statement(statement, spanned_nodes(metadata), code);
+    }
+    if let Some(comment) = last_comment {
+        level.push_statement(comment.treenodes[0], spanned_nodes(comment), code);
+    }
+    level.trees
+}
+
+fn spanned_nodes(metadata: TreeNodeMetadata) -> Vec<VimNode> {
+    let span = metadata.span();
+    // Module warnings only cover top-level statements, so nested ones don't add any.
+    metadata
+        .into_nodes(&mut vec![])
+        .into_iter()
+        .map(|node| node.with_span(span.clone()))
+        .collect()
+}
@@ -0,0 +1,152 @@
+//! Declarative tree-sitter query patterns for the statements that define [crate::VimNode]s.
+
+use std::sync::OnceLock;
+use tree_sitter::{Node, Query, QueryCursor};
+
+const FUNCTION_PATTERNS: &str = r#"
+(function_definition (function_declaration name: (_) @name))
+(function_definition
+  (function_declaration parameters: (parameters [(identifier) (spread)] @param)))
+(function_definition [(bang) "dict" "range" "abort" "closure"] @modifier)
+"#;
+
+const COMMAND_PATTERNS: &str = r#"
+(command_statement name: (command_name) @name)
+(command_statement (command_attribute) @modifier)
+(command_statement repl: (command) @repl)
+"#;
+
+// Only plain `let` assignments define variables. Others like `let +=` modify existing ones.
+const LET_PATTERNS: &str = r#"
+(let_statement "let" . (_) @lhs . "=" . (_) @rhs)
+"#;
+
+// Matches `Flag('name', default)` calls, including method calls like `s:plugin.Flag(…)` or
+// `maktaba#plugin#Get('foo').Flag(…)`.
+const FLAG_PATTERNS: &str = r#"
+(call_statement
+  (call_expression
+    function: [
+      (identifier) @function
+      (scoped_identifier (identifier) @function)
+      (field_expression field: (identifier) @function)
+    ]
+    .
+    (string_literal) @name
+    .
+    (_)? @default)
+  (#eq? @function "Flag"))
+"#;
+
+const HIGHLIGHT_PATTERNS: &str = r#"
+(highlight_statement "default" @default)
+(highlight_statement from: (hl_group) @name to: (hl_group) @link)
+(highlight_statement (hl_group) @name . (hl_attribute))
+(highlight_statement (hl_attribute) @attribute)
+"#;
+
+const SIGN_PATTERNS: &str = r#"
+(sign_statement sub: "define" name: (_) @name)
+(sign_statement sub: "define" (sign_argument) @attribute)
+"#;
+
+/// Compiled query patterns for each kind of definition statement.
+pub(super) struct NodePatterns {
+    pub function: Query,
+    pub command: Query,
+    pub let_statement: Query,
+    pub flag: Query,
+    pub highlight: Query,
+    pub sign: Query,
+}
+
+impl NodePatterns {
+    fn new() -> Result<Self, tree_sitter::QueryError> {
+        let language = tree_sitter_vim::language();
+        Ok(Self {
+            function: Query::new(&language, FUNCTION_PATTERNS)?,
+            command: Query::new(&language, COMMAND_PATTERNS)?,
+            let_statement: Query::new(&language, LET_PATTERNS)?,
+            flag: Query::new(&language, FLAG_PATTERNS)?,
+            highlight: Query::new(&language, HIGHLIGHT_PATTERNS)?,
+            sign: Query::new(&language, SIGN_PATTERNS)?,
+        })
+    }
+
+    pub fn get() -> &'static Self {
+        static PATTERNS: OnceLock<NodePatterns> = OnceLock::new();
+        // The patterns are checked against the bundled grammar in tests.
+        PATTERNS.get_or_init(|| Self::new().expect("invalid node patterns"))
+    }
+}
+
+/// Runs `query` against `treenode` itself (not its descendants), returning the captured nodes
+/// by capture name in source order.
+pub(super) fn captures<'q, 'tree>(
+    query: &'q Query,
+    treenode: Node<'tree>,
+    source: &[u8],
+) -> Vec<(&'q str, Node<'tree>)> {
+    let mut cursor = QueryCursor::new();
+    cursor.set_max_start_depth(Some(0));
+    let names = query.capture_names();
+    cursor
+        .captures(query, treenode, source)
+        .map(|(query_match, index)| {
+            let capture = query_match.captures[index];
+            (names[capture.index as usize], capture.node)
+        })
+        .collect()
+}
+
+/// Like [captures], but grouped by match, for queries that can match the same statement more
+/// than once (like each function in a table constructor).
+pub(super) fn matches<'q, 'tree>(
+    query: &'q Query,
+    treenode: Node<'tree>,
+    source: &[u8],
+) -> Vec<Vec<(&'q str, Node<'tree>)>> {
+    let mut cursor = QueryCursor::new();
+    cursor.set_max_start_depth(Some(0));
+    let names = query.capture_names();
+    cursor
+        .matches(query, treenode, source)
+        .map(|query_match| {
+            query_match
+                .captures
+                .iter()
+                .map(|capture| (names[capture.index as usize], capture.node))
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tree_sitter::Parser;
+
+    #[test]
+    fn patterns_match_grammar() {
+        let patterns = NodePatterns::new().unwrap();
+        let code = "let [a, b] = [1, 2]\nlet g:y += 1\n";
+        let mut parser = Parser::new();
+        parser.set_language(&tree_sitter_vim::language()).unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        let root = tree.root_node();
+        let captured: Vec<Vec<_>> = [root.child(0).unwrap(), root.child(1).unwrap()]
+            .into_iter()
+            .map(|treenode| {
+                captures(&patterns.let_statement, treenode, code.as_bytes())
+                    .into_iter()
+                    .map(|(name, node)| (name, node.kind()))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            captured,
+            vec![vec![("lhs", "list_assignment"), ("rhs", "list")], vec![]]
+        );
+    }
+}
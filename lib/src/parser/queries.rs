@@ -0,0 +1,81 @@
+use crate::TreeSitterQuery;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Finds tree-sitter query files under queries/{lang}/ and after/queries/{lang}/, returned with
+/// paths relative to `root`.
+pub fn detect_queries(root: &Path) -> crate::Result<Vec<TreeSitterQuery>> {
+    let mut queries = vec![];
+    for queries_dir in ["queries", "after/queries"] {
+        for language_dir in sorted_subpaths(&root.join(queries_dir)) {
+            if !language_dir.is_dir() {
+                continue;
+            }
+            let Some(language) = language_dir.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            for query_path in sorted_subpaths(&language_dir) {
+                if query_path.extension().and_then(|e| e.to_str()) != Some("scm") {
+                    continue;
+                }
+                let Some(query_type) = query_path.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                let code = fs::read_to_string(&query_path)
+                    .map_err(|err| crate::Error::from(err).in_file(&query_path))?;
+                queries.push(TreeSitterQuery {
+                    language: language.to_string(),
+                    query_type: query_type.to_string(),
+                    path: query_path.strip_prefix(root).unwrap().to_path_buf(),
+                    captures: capture_names(&code),
+                });
+            }
+        }
+    }
+    Ok(queries)
+}
+
+fn sorted_subpaths(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut paths: Vec<_> = entries.filter_map(Result::ok).map(|e| e.path()).collect();
+    paths.sort();
+    paths
+}
+
+/// Extracts distinct `@capture` names from query source, skipping strings and `;` comments.
+fn capture_names(code: &str) -> Vec<String> {
+    static TOKEN: OnceLock<Regex> = OnceLock::new();
+    let token =
+        TOKEN.get_or_init(|| Regex::new(r#"(?m)"(?:[^"\\]|\\.)*"|;.*$|@([\w.-]+)"#).unwrap());
+    let mut captures: Vec<String> = vec![];
+    for name in token.captures_iter(code).filter_map(|c| c.get(1)) {
+        if !captures.iter().any(|c| c == name.as_str()) {
+            captures.push(name.as_str().to_string());
+        }
+    }
+    captures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn capture_names_skips_strings_and_comments() {
+        let code = r#"
+; Comment mentioning @not.a.capture
+((identifier) @variable.builtin
+  (#eq? @variable.builtin "@self"))
+(call function: (identifier) @function.call)
+"#;
+        assert_eq!(
+            capture_names(code),
+            vec!["variable.builtin".to_string(), "function.call".to_string()]
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use super::locations::span_of;
+use super::treenodes::{get_treenode_text, unquote_string_literal};
+use crate::{FunctionCall, Span};
+use regex::Regex;
+use std::sync::OnceLock;
+use tree_sitter::{Node, Tree};
+use tree_sitter_traversal::{traverse, Order};
+
+/// A place where a symbol name appears in source, including its definition.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reference {
+    pub span: Span,
+    /// The name as written, which can differ from the searched name for script-local functions
+    /// called through `<SID>`.
+    pub text: String,
+}
+
+/// An autoload function or variable referenced in a module (see [find_autoload_references]).
+#[derive(Clone, Debug, PartialEq)]
+pub struct AutoloadReference {
+    pub name: String,
+    /// Whether every use is guarded by `silent!` or a `try` block, as for optional integrations
+    /// with other plugins that might not be installed.
+    pub optional: bool,
+}
+
+/// Finds every occurrence of a function, command or variable name in the tree.
+///
+/// Besides identifiers, this scans text vim doesn't parse until runtime, like command
+/// replacement text, mapping right-hand sides and strings (for `function('Name')` or
+/// `execute`), matching whole names only.
+pub fn find_references(tree: &Tree, source: &[u8], name: &str) -> Vec<Reference> {
+    let mut aliases = vec![name.to_string()];
+    if let Some(script_local) = name.strip_prefix("s:") {
+        aliases.push(format!("<SID>{script_local}"));
+    }
+    let mut references = vec![];
+    for node in traverse(tree.walk(), Order::Pre) {
+        match node.kind() {
+            "identifier" | "scoped_identifier" | "command_name" => {
+                // Names inside scoped identifiers are only part of a name, like Foo in s:Foo.
+                if node.kind() == "identifier"
+                    && node
+                        .parent()
+                        .is_some_and(|p| p.kind() == "scoped_identifier")
+                {
+                    continue;
+                }
+                let text = get_treenode_text(&node, source);
+                if aliases.iter().any(|a| a == text) {
+                    references.push(Reference {
+                        span: span_of(&node),
+                        text: text.to_string(),
+                    });
+                }
+            }
+            "string_literal" | "map_side" | "command" => {
+                references.extend(find_in_raw_text(&node, source, &aliases));
+            }
+            _ => {}
+        }
+    }
+    references
+}
+
+/// Finds autoload functions and variables referenced anywhere in the tree, deduplicated in order
+/// of first use. Definitions like `func foo#Bar()` don't count.
+///
+/// Like [find_references], this also scans strings, command replacement text and mapping
+/// right-hand sides, where it picks up calls like `foo#Bar(` and whole strings naming a function
+/// like `function('foo#Bar')`.
+pub fn find_autoload_references(tree: &Tree, source: &[u8]) -> Vec<AutoloadReference> {
+    static AUTOLOAD_NAME: OnceLock<Regex> = OnceLock::new();
+    let autoload_name = AUTOLOAD_NAME.get_or_init(|| Regex::new(r"[A-Za-z_]\w*(?:#\w+)+").unwrap());
+    let mut references: Vec<AutoloadReference> = vec![];
+    for node in traverse(tree.walk(), Order::Pre) {
+        let mut add = |name: &str| {
+            let guarded = is_error_guarded(node);
+            match references.iter_mut().find(|r| r.name == name) {
+                Some(existing) => existing.optional &= guarded,
+                None => references.push(AutoloadReference {
+                    name: name.to_string(),
+                    optional: guarded,
+                }),
+            }
+        };
+        match node.kind() {
+            "identifier" => {
+                let name = get_treenode_text(&node, source);
+                let parent_kind = node.parent().map(|p| p.kind());
+                if name.contains('#')
+                    && !matches!(
+                        parent_kind,
+                        Some("scoped_identifier" | "function_declaration")
+                    )
+                {
+                    add(name);
+                }
+            }
+            "string_literal" | "map_side" | "command" => {
+                let text = get_treenode_text(&node, source);
+                if node.kind() == "string_literal" {
+                    let content = text.get(1..text.len().saturating_sub(1)).unwrap_or("");
+                    if autoload_name
+                        .find(content)
+                        .is_some_and(|m| m.len() == content.len())
+                    {
+                        add(content);
+                        continue;
+                    }
+                }
+                for m in autoload_name.find_iter(text) {
+                    let before = text[..m.start()].chars().next_back();
+                    if text[m.end()..].starts_with('(')
+                        && !before.is_some_and(|c| c.is_alphanumeric() || matches!(c, '_' | ':'))
+                    {
+                        add(m.as_str());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    references
+}
+
+/// Finds libraries loaded with `maktaba#library#Require('name')`, deduplicated in order of first
+/// use. Calls with names that aren't string literals are skipped.
+pub fn find_required_libraries(tree: &Tree, source: &[u8]) -> Vec<String> {
+    let mut libraries: Vec<String> = vec![];
+    for node in traverse(tree.walk(), Order::Pre) {
+        if node.kind() != "call_expression" {
+            continue;
+        }
+        let is_require = node
+            .child_by_field_name("function")
+            .is_some_and(|f| get_treenode_text(&f, source) == "maktaba#library#Require");
+        let name = node
+            .named_child(1)
+            .filter(|arg| is_require && arg.kind() == "string_literal")
+            .map(|arg| unquote_string_literal(get_treenode_text(&arg, source)));
+        if let Some(name) = name.filter(|name| !libraries.contains(name)) {
+            libraries.push(name);
+        }
+    }
+    libraries
+}
+
+/// Finds calls to user-defined functions, deduplicated in order of first use, along with the
+/// function each is made from.
+///
+/// Besides call expressions, this counts function references passed to `function()` and
+/// `funcref()` as string literals, since those are usually called back later.
+pub fn find_function_calls(tree: &Tree, source: &[u8]) -> Vec<FunctionCall> {
+    let mut calls: Vec<FunctionCall> = vec![];
+    for node in traverse(tree.walk(), Order::Pre) {
+        if node.kind() != "call_expression" {
+            continue;
+        }
+        let Some(function) = node.child_by_field_name("function") else {
+            continue;
+        };
+        let function_name = get_treenode_text(&function, source);
+        let callee = match function.kind() {
+            "identifier" if matches!(function_name, "function" | "funcref") => node
+                .named_child(1)
+                .filter(|arg| arg.kind() == "string_literal")
+                .map(|arg| unquote_string_literal(get_treenode_text(&arg, source))),
+            "identifier" | "scoped_identifier" => Some(function_name.to_string()),
+            _ => None,
+        };
+        // Builtin function names start with a lowercase letter, unlike user-defined ones.
+        let Some(callee) = callee
+            .filter(|name| name.contains(['#', ':', '>']) || name.starts_with(char::is_uppercase))
+        else {
+            continue;
+        };
+        let call = FunctionCall {
+            caller: enclosing_function_name(node, source),
+            callee: normalize_sid(&callee),
+        };
+        if !calls.contains(&call) {
+            calls.push(call);
+        }
+    }
+    calls
+}
+
+/// The name of the function whose body contains `node`, if any.
+fn enclosing_function_name(node: Node, source: &[u8]) -> Option<String> {
+    let mut ancestor = node.parent();
+    while let Some(current) = ancestor {
+        if current.kind() == "function_definition" {
+            let name = current
+                .named_children(&mut current.walk())
+                .find(|child| child.kind() == "function_declaration")?
+                .child_by_field_name("name")?;
+            return Some(normalize_sid(get_treenode_text(&name, source)));
+        }
+        ancestor = current.parent();
+    }
+    None
+}
+
+/// Rewrites a `<SID>Foo` function name to the equivalent `s:Foo`.
+fn normalize_sid(name: &str) -> String {
+    match name.get(..5) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("<SID>") => format!("s:{}", &name[5..]),
+        _ => name.to_string(),
+    }
+}
+
+/// Whether errors from the statement containing `node` are suppressed, because it's under a
+/// `silent!` or in the body of a `try` block. Stops at function definitions, whose bodies run
+/// later outside any guard around the definition.
+fn is_error_guarded(node: Node) -> bool {
+    let mut child = node;
+    while let Some(parent) = child.parent() {
+        match parent.kind() {
+            "function_definition" => return false,
+            "silent_statement" => {
+                let mut cursor = parent.walk();
+                if parent
+                    .children(&mut cursor)
+                    .any(|sibling| sibling.kind() == "bang")
+                {
+                    return true;
+                }
+            }
+            "try_statement" if child.kind() == "body" => return true,
+            _ => {}
+        }
+        child = parent;
+    }
+    false
+}
+
+fn find_in_raw_text(node: &Node, source: &[u8], aliases: &[String]) -> Vec<Reference> {
+    let text = get_treenode_text(node, source);
+    let is_name_char = |c: char| c.is_alphanumeric() || matches!(c, '_' | '#' | ':');
+    let mut references = vec![];
+    for alias in aliases {
+        for (offset, _) in text.match_indices(alias.as_str()) {
+            let before = text[..offset].chars().next_back();
+            let after = text[offset + alias.len()..].chars().next();
+            if before.is_some_and(is_name_char) || after.is_some_and(is_name_char) {
+                continue;
+            }
+            // Raw text can span lines (like line continuations), so count rows and columns.
+            let preceding = &text[..offset];
+            let rows = preceding.matches('\n').count();
+            let column = match preceding.rfind('\n') {
+                Some(newline) => offset - newline - 1,
+                None => node.start_position().column + offset,
+            };
+            let start = (node.start_position().row + rows, column);
+            let byte_start = node.start_byte() + offset;
+            references.push(Reference {
+                span: Span {
+                    start,
+                    end: (start.0, start.1 + alias.len()),
+                    byte_range: byte_start..byte_start + alias.len(),
+                },
+                text: alias.clone(),
+            });
+        }
+    }
+    references.sort_by_key(|r| r.span.byte_range.start);
+    references
+}
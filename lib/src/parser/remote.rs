@@ -0,0 +1,125 @@
+use super::treenodes::{get_treenode_text, unquote_string_literal};
+use crate::{RemotePlugin, RemoteRegistration};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tree_sitter::{Node, Tree};
+use tree_sitter_traversal::{traverse, Order};
+
+/// Finds remote plugin entry points under rplugin/{host}/, like rplugin/python3/foo.py or a
+/// rplugin/python3/foo/ package, returned with paths relative to `root`.
+pub fn detect_remote_plugins(root: &Path) -> Vec<RemotePlugin> {
+    let mut found = vec![];
+    for host_dir in sorted_entries(&root.join("rplugin")) {
+        if !host_dir.is_dir() {
+            continue;
+        }
+        let Some(host) = host_dir.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        for entry in sorted_entries(&host_dir) {
+            let Ok(path) = entry.strip_prefix(root) else {
+                continue;
+            };
+            found.push(RemotePlugin {
+                host: host.to_string(),
+                path: path.to_path_buf(),
+                registrations: vec![],
+            });
+        }
+    }
+    found
+}
+
+fn sorted_entries(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| {
+            !p.file_name()
+                .is_some_and(|n| n.to_string_lossy().starts_with('.'))
+        })
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// A `remote#host#RegisterPlugin(host, path, specs)` call found in vim code.
+pub struct RegistrationStub {
+    pub host: String,
+    pub path: String,
+    pub registrations: Vec<RemoteRegistration>,
+}
+
+pub fn extract_registration_stubs(tree: &Tree, source: &[u8]) -> Vec<RegistrationStub> {
+    traverse(tree.walk(), Order::Pre)
+        .filter(|n| n.kind() == "call_expression")
+        .filter_map(|n| registration_stub_from_call(&n, source))
+        .collect()
+}
+
+fn registration_stub_from_call(node: &Node, source: &[u8]) -> Option<RegistrationStub> {
+    let function = node.child_by_field_name("function")?;
+    if get_treenode_text(&function, source) != "remote#host#RegisterPlugin" {
+        return None;
+    }
+    let mut cursor = node.walk();
+    let args: Vec<_> = node
+        .named_children(&mut cursor)
+        .filter(|c| c.id() != function.id())
+        .collect();
+    let [host, path, specs, ..] = args[..] else {
+        return None;
+    };
+    let mut specs_cursor = specs.walk();
+    let registrations = specs
+        .named_children(&mut specs_cursor)
+        .filter(|spec| spec.kind() == "dictionnary")
+        .filter_map(|spec| registration_from_spec(&spec, source))
+        .collect();
+    Some(RegistrationStub {
+        host: string_value(&host, source),
+        path: string_value(&path, source),
+        registrations,
+    })
+}
+
+fn registration_from_spec(spec: &Node, source: &[u8]) -> Option<RemoteRegistration> {
+    let mut kind = None;
+    let mut name = None;
+    let mut sync = false;
+    let mut cursor = spec.walk();
+    for entry in spec.named_children(&mut cursor) {
+        let (Some(key), Some(value)) = (
+            entry.child_by_field_name("key"),
+            entry.child_by_field_name("value"),
+        ) else {
+            continue;
+        };
+        match string_value(&key, source).as_str() {
+            "type" => kind = Some(string_value(&value, source)),
+            "name" => name = Some(string_value(&value, source)),
+            "sync" => {
+                sync = matches!(get_treenode_text(&value, source), "v:true" | "1" | "'1'");
+            }
+            _ => {}
+        }
+    }
+    Some(RemoteRegistration {
+        kind: kind?,
+        name: name?,
+        sync,
+    })
+}
+
+/// Gets the contents of a string literal node, or its raw text for other expressions.
+fn string_value(node: &Node, source: &[u8]) -> String {
+    let text = get_treenode_text(node, source);
+    if node.kind() == "string_literal" {
+        unquote_string_literal(text)
+    } else {
+        text.to_string()
+    }
+}
@@ -0,0 +1,300 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+
+/// A known kind of vim runtime subdirectory (see `:help vimfiles`), or the special root menu.vim
+/// file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum SectionKind {
+    Plugin,
+    Instant,
+    Autoload,
+    /// Vim9 script modules loaded with `:import`.
+    Import,
+    Syntax,
+    Indent,
+    Ftdetect,
+    Ftplugin,
+    Compiler,
+    Spell,
+    Lang,
+    Colors,
+    /// Lua modules for Neovim plugins, loaded with `require()`.
+    Lua,
+    /// The standalone menu.vim file in the root dir.
+    Menu,
+}
+
+impl SectionKind {
+    /// The name of the section's subdirectory (or "menu" for [SectionKind::Menu]).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SectionKind::Plugin => "plugin",
+            SectionKind::Instant => "instant",
+            SectionKind::Autoload => "autoload",
+            SectionKind::Import => "import",
+            SectionKind::Syntax => "syntax",
+            SectionKind::Indent => "indent",
+            SectionKind::Ftdetect => "ftdetect",
+            SectionKind::Ftplugin => "ftplugin",
+            SectionKind::Compiler => "compiler",
+            SectionKind::Spell => "spell",
+            SectionKind::Lang => "lang",
+            SectionKind::Colors => "colors",
+            SectionKind::Lua => "lua",
+            SectionKind::Menu => "menu",
+        }
+    }
+
+    fn from_dir_name(dir_name: &str, case_insensitive: bool) -> Option<Self> {
+        super::DEFAULT_SECTION_ORDER
+            .into_iter()
+            .find(|kind| names_match(kind.as_str(), dir_name, case_insensitive))
+    }
+}
+
+/// The section a runtime file belongs to, like `plugin` or `after/ftplugin`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Section {
+    pub kind: SectionKind,
+    /// Whether the path is under the after/ dir.
+    pub after: bool,
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.after {
+            write!(f, "after/")?;
+        }
+        write!(f, "{}", self.kind.as_str())
+    }
+}
+
+/// A file [crate::VimParser::parse_plugin_dir] would parse, as listed by
+/// [crate::VimParser::list_plugin_files].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PluginFile {
+    /// Path of the file relative to the plugin root.
+    pub path: PathBuf,
+    pub section: Section,
+}
+
+/// Classifies a path relative to a plugin root into the runtime section it belongs to, the same
+/// way the parser decides which files to include.
+///
+/// Returns None for paths the parser wouldn't scan, like root files besides menu.vim or nested
+/// subdirs outside autoload/, lua/ and filetype subdirs of ftplugin/ and syntax/, and for
+/// absolute paths.
+///
+/// Windows-style `\` separators are accepted on every platform. Dir names match
+/// case-sensitively, like the parser's default. Use [classify_runtime_path_with] or
+/// [crate::VimParser::classify_runtime_path] to match dir names like `Plugin` as a parser
+/// configured with [crate::VimParserBuilder::case_insensitive_paths] would.
+///
+/// ```
+/// use vim_plugin_metadata::{classify_runtime_path, Section, SectionKind};
+///
+/// assert_eq!(
+///     classify_runtime_path("after/ftplugin/python.vim"),
+///     Some(Section { kind: SectionKind::Ftplugin, after: true }),
+/// );
+/// assert_eq!(classify_runtime_path("plugin/nested/x.vim"), None);
+/// ```
+pub fn classify_runtime_path<P: AsRef<Path>>(path: P) -> Option<Section> {
+    classify_runtime_path_with(path, false)
+}
+
+/// Classifies a relative path into its section, like [classify_runtime_path] but matching dir
+/// names case-insensitively if `case_insensitive` is set.
+pub fn classify_runtime_path_with<P: AsRef<Path>>(
+    path: P,
+    case_insensitive: bool,
+) -> Option<Section> {
+    let (kind, after) = classify_path(path.as_ref(), case_insensitive)?;
+    kind.map(|kind| Section { kind, after })
+}
+
+/// Classifies a relative path into its section kind and whether it's under after/.
+///
+/// The kind is None for the root dir and after/ dir themselves, which contain sections but
+/// aren't in one.
+pub(super) fn classify_path(
+    path: &Path,
+    case_insensitive: bool,
+) -> Option<(Option<SectionKind>, bool)> {
+    let parts = path_parts(path)?;
+    let parts: Vec<&str> = parts.iter().map(String::as_str).collect();
+    let mut paths = vec![(&parts[..], false)];
+    if let Some((first, rest)) = parts.split_first() {
+        if names_match("after", first, case_insensitive) {
+            paths.push((rest, true));
+        }
+    }
+    for (path_parts, after) in paths {
+        let classified = match path_parts {
+            // Root dir or after/.
+            [] => Some(None),
+            // Special case: standalone file in root dir.
+            [name] if names_match("menu.vim", name, case_insensitive) => {
+                Some(Some(SectionKind::Menu))
+            }
+            [section, rest @ ..] => SectionKind::from_dir_name(section, case_insensitive)
+                .filter(|kind| match kind {
+                    SectionKind::Autoload | SectionKind::Import | SectionKind::Lua => true,
+                    // Filetype subdirs, like ftplugin/python/*.vim.
+                    SectionKind::Ftplugin | SectionKind::Syntax => rest.len() <= 2,
+                    _ => rest.len() <= 1,
+                })
+                .map(Some),
+        };
+        if let Some(kind) = classified {
+            return Some((kind, after));
+        }
+    }
+
+    None
+}
+
+/// The filetype a module in ftplugin/, indent/ or syntax/ applies to, following vim's naming
+/// conventions (see `:help ftplugin-name`): `<ft>.vim`, `<ft>_*.vim` (ftplugin/ only) and
+/// `<ft>/*.vim` (ftplugin/ and syntax/).
+pub(crate) fn filetype_of(path: &Path) -> Option<String> {
+    let section = classify_runtime_path_with(path, true)?;
+    let parts = path_parts(path)?;
+    let parts = if section.after {
+        &parts[1..]
+    } else {
+        &parts[..]
+    };
+    match (section.kind, parts) {
+        (SectionKind::Ftplugin | SectionKind::Syntax, [_, filetype, _]) => Some(filetype.clone()),
+        (SectionKind::Ftplugin | SectionKind::Indent | SectionKind::Syntax, [_, file_name]) => {
+            let stem = file_name
+                .rsplit_once('.')
+                .map_or(&file_name[..], |(stem, _)| stem);
+            let filetype = match section.kind {
+                SectionKind::Ftplugin => stem.split_once('_').map_or(stem, |(ft, _)| ft),
+                _ => stem,
+            };
+            Some(filetype.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Splits a relative path into its parts, also splitting on `\` so Windows-style paths classify
+/// the same on every platform. Parts that aren't valid unicode are converted lossily, since only
+/// the dir names need to match.
+///
+/// Returns None for absolute paths, including ones with Windows drive or UNC prefixes.
+fn path_parts(path: &Path) -> Option<Vec<String>> {
+    let mut parts = vec![];
+    for component in path.components() {
+        let part = match component {
+            Component::Normal(part) => part.to_string_lossy(),
+            Component::CurDir => continue,
+            Component::Prefix(_) | Component::RootDir | Component::ParentDir => return None,
+        };
+        if parts.is_empty() && (part.starts_with('\\') || is_drive_prefix(&part)) {
+            return None;
+        }
+        for part in part.split('\\') {
+            match part {
+                "" | "." => {}
+                ".." => return None,
+                part => parts.push(part.to_string()),
+            }
+        }
+    }
+    Some(parts)
+}
+
+/// Whether a path part starts with a Windows drive like `C:`.
+fn is_drive_prefix(part: &str) -> bool {
+    let bytes = part.as_bytes();
+    bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+fn names_match(expected: &str, name: &str, case_insensitive: bool) -> bool {
+    if case_insensitive {
+        expected.eq_ignore_ascii_case(name)
+    } else {
+        expected == name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn classify_runtime_path_sections() {
+        assert_eq!(
+            [
+                "plugin/x.vim",
+                "autoload/a/b/c.vim",
+                "lua/foo/bar/init.lua",
+                "after/menu.vim",
+                "after/after/x.vim",
+                "x.vim",
+                "after",
+            ]
+            .map(|p| classify_runtime_path(p).map(|s| s.to_string())),
+            [
+                Some("plugin".to_string()),
+                Some("autoload".to_string()),
+                Some("lua".to_string()),
+                Some("after/menu".to_string()),
+                None,
+                None,
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn classify_windows_style_paths() {
+        assert_eq!(
+            [
+                "after\\ftplugin\\python.vim",
+                ".\\Autoload\\foo\\bar.vim",
+                "\\\\server\\share\\plugin\\x.vim",
+                "C:\\plugin\\x.vim",
+                "/plugin/x.vim",
+            ]
+            .map(|p| classify_runtime_path(p).map(|s| s.to_string())),
+            [Some("after/ftplugin".to_string()), None, None, None, None,]
+        );
+        assert_eq!(
+            classify_runtime_path_with(".\\Autoload\\foo\\bar.vim", true).map(|s| s.kind),
+            Some(SectionKind::Autoload)
+        );
+        assert_eq!(classify_runtime_path("Plugin/x.vim"), None);
+    }
+
+    #[test]
+    fn filetype_subdir_conventions() {
+        assert_eq!(
+            [
+                "ftplugin/python.vim",
+                "ftplugin/python_fold.vim",
+                "after/ftplugin/python/fold.vim",
+                "syntax/python/extra.vim",
+                "indent/python.lua",
+                "indent/python/x.vim",
+                "plugin/python.vim",
+            ]
+            .map(|p| filetype_of(Path::new(p))),
+            [
+                Some("python".to_string()),
+                Some("python".to_string()),
+                Some("python".to_string()),
+                Some("python".to_string()),
+                Some("python".to_string()),
+                None,
+                None,
+            ]
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use crate::data::ModuleStats;
+
+/// Classifies each line of a module as blank, comment, doc comment or code.
+///
+/// A doc comment block starts with a `""` line and continues through the `"` lines right
+/// after it.
+pub fn count_lines(code: &str) -> ModuleStats {
+    let mut stats = ModuleStats::default();
+    let mut in_doc_block = false;
+    for line in code.lines() {
+        let line = line.trim_start();
+        if line.is_empty() {
+            stats.blank_lines += 1;
+            in_doc_block = false;
+        } else if line.starts_with('"') {
+            stats.comment_lines += 1;
+            in_doc_block |= line.starts_with("\"\"");
+            if in_doc_block {
+                stats.doc_comment_lines += 1;
+            }
+        } else {
+            stats.code_lines += 1;
+            in_doc_block = false;
+        }
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn count_lines_kinds() {
+        let code = r#"
+" vim: set sw=2:
+
+""
+" Does a thing.
+func! foo#Bar() abort " Trailing comment
+  " Not doc.
+  return 1
+endfunc
+"#;
+        assert_eq!(
+            count_lines(code),
+            ModuleStats {
+                blank_lines: 2,
+                comment_lines: 4,
+                doc_comment_lines: 2,
+                code_lines: 3,
+            }
+        );
+    }
+}
@@ -1,5 +1,10 @@
-use crate::VimNode;
+use super::config::{autocmd_from_node, map_mode, mapping_from_node, setting_from_item};
+use super::patterns::{captures, NodePatterns};
+use super::span_of;
+use crate::{Span, VimNode, Warning, WarningKind};
+use std::borrow::Cow;
 use std::fmt::Formatter;
+use std::iter::Peekable;
 use std::{fmt, str};
 use tree_sitter::Node;
 use unicode_ellipsis::truncate_str;
@@ -32,20 +37,80 @@ impl fmt::Debug for TreeNodeMetadata<'_> {
 }
 
 pub fn get_treenode_text<'a>(node: &Node, source: &'a [u8]) -> &'a str {
-    str::from_utf8(&source[node.byte_range()]).unwrap()
+    // Sources are decoded to valid UTF-8 up front, so this can only fail on a grammar bug.
+    str::from_utf8(&source[node.byte_range()]).unwrap_or_default()
+}
+
+/// Gets the contents of a vimscript 'single' or "double" quoted string literal.
+pub fn unquote_string_literal(literal: &str) -> String {
+    if let Some(contents) = literal.strip_prefix("'").and_then(|l| l.strip_suffix("'")) {
+        contents.replace("''", "'")
+    } else {
+        quoted_string::unquote_unchecked(literal).into()
+    }
+}
+
+/// The variables a `let_statement` defines, as (name, value) pairs borrowed from the source where
+/// possible.
+///
+/// Destructuring like `let [a, b] = [1, 2]` gives a pair per target, with values from the items
+/// of a literal list or indexed like `rhs[0]`. Other kinds of `let`, like `let +=`, don't
+/// define a new variable and give none.
+pub fn let_assignments<'a>(treenode: Node, source: &'a [u8]) -> Vec<(&'a str, Cow<'a, str>)> {
+    let captured = captures(&NodePatterns::get().let_statement, treenode, source);
+    let [("lhs", lhs), ("rhs", rhs), ..] = captured[..] else {
+        return vec![];
+    };
+    if lhs.kind() != "list_assignment" {
+        return vec![(
+            get_treenode_text(&lhs, source),
+            get_treenode_text(&rhs, source).into(),
+        )];
+    }
+    let rhs_is_literal = rhs.kind() == "list" && lhs.named_child_count() == rhs.named_child_count();
+    let mut cursor = lhs.walk();
+    let assignments = lhs
+        .named_children(&mut cursor)
+        .enumerate()
+        .map(|(i, target)| {
+            let value = match rhs.named_child(i).filter(|_| rhs_is_literal) {
+                Some(item) => get_treenode_text(&item, source).into(),
+                None => format!("{}[{i}]", get_treenode_text(&rhs, source)).into(),
+            };
+            (get_treenode_text(&target, source), value)
+        })
+        .collect();
+    assignments
 }
 
 impl<'a> TreeNodeMetadata<'a> {
-    fn try_get_treenode(&self) -> Result<Node<'a>, String> {
+    fn try_get_treenode(&self) -> Result<Node<'a>, Warning> {
         if self.treenodes.len() != 1 {
-            Err(format!(
-                "Attempted to process single tree node but found multiple: {self:?}"
-            ))
+            Err(Warning {
+                kind: WarningKind::Internal,
+                message: format!(
+                    "Expected a single {} node but found {}",
+                    self.kind(),
+                    self.treenodes.len()
+                ),
+                span: self.span(),
+            })
         } else {
             Ok(self.treenodes[0])
         }
     }
 
+    /// The span from the start of the first tree node to the end of the last.
+    pub(crate) fn span(&self) -> Span {
+        let first = self.treenodes[0];
+        let last = self.treenodes[self.treenodes.len() - 1];
+        Span {
+            start: (first.start_position().row, first.start_position().column),
+            end: (last.end_position().row, last.end_position().column),
+            byte_range: first.start_byte()..last.end_byte(),
+        }
+    }
+
     pub(crate) fn kind(&self) -> &'a str {
         let kind = self.treenodes[0].kind();
         for treenode in &self.treenodes {
@@ -56,142 +121,168 @@ impl<'a> TreeNodeMetadata<'a> {
         kind
     }
 
-    fn get_func_node(&self) -> Result<VimNode, String> {
+    fn get_func_node(&mut self) -> Result<VimNode, Warning> {
         let treenode = self.try_get_treenode()?;
-        let mut cursor = treenode.walk();
-        let mut decl = None;
+        let mut name = None;
+        let mut args = vec![];
         let mut modifiers = vec![];
-        for child in treenode.children(&mut cursor) {
-            match child.kind() {
-                "function" | "endfunction" => {}
-                "function_declaration" => {
-                    decl = Some(child);
-                }
-                "body" => {
-                    break;
-                }
-                // Everything else between function_declaration and body is a modifier.
-                _ => {
-                    modifiers.push(get_treenode_text(&child, self.source).to_string());
-                }
+        for (capture, node) in captures(&NodePatterns::get().function, treenode, self.source) {
+            let text = get_treenode_text(&node, self.source);
+            match capture {
+                "name" => name = Some(text),
+                "param" => args.push(text.to_string()),
+                _ => modifiers.push(text.to_string()),
             }
         }
-        let name = decl
-            .and_then(|decl| decl.child_by_field_name("name"))
-            .map(|ident| get_treenode_text(&ident, self.source))
-            .ok_or_else(|| {
-                format!(
-                    "Failed to find function name for {} at {:?}",
-                    treenode.kind(),
-                    treenode.start_position(),
-                )
-            })?;
-        let params = decl.and_then(|decl| {
-            decl.children(&mut cursor)
-                .find(|c| c.kind() == "parameters")
-        });
-        let args: Vec<_> = params
-            .map(|params| {
-                params
-                    .children(&mut cursor)
-                    .filter(|c| c.kind() == "identifier" || c.kind() == "spread")
-                    .map(|c| get_treenode_text(&c, self.source).to_string())
-                    .collect()
-            })
-            .unwrap_or_default();
+        let name = name.ok_or_else(|| Warning {
+            kind: WarningKind::MissingName,
+            message: format!("Failed to find function name for {}", treenode.kind()),
+            span: span_of(&treenode),
+        })?;
         Ok(VimNode::Function {
             name: name.to_string(),
             args,
             modifiers,
-            doc: self.doc.clone(),
+            doc: self.doc.take(),
+            body: None,
+            span: None,
         })
     }
 
-    fn get_command_node(&self) -> Result<VimNode, String> {
+    fn get_command_node(&mut self) -> Result<VimNode, Warning> {
         let treenode = self.try_get_treenode()?;
-        let name = treenode
-            .child_by_field_name("name")
-            .map(|n| get_treenode_text(&n, self.source))
-            .ok_or_else(|| {
-                format!(
-                    "Failed to find command name for {} at {:?}",
-                    treenode.kind(),
-                    treenode.start_position(),
-                )
-            })?;
-        let mut cursor = treenode.walk();
-        let modifiers: Vec<_> = treenode
-            .children(&mut cursor)
-            .filter(|c| c.kind() == "command_attribute")
-            .map(|c| get_treenode_text(&c, self.source).to_string())
-            .collect();
+        let mut name = None;
+        let mut modifiers = vec![];
+        let mut repl = None;
+        for (capture, node) in captures(&NodePatterns::get().command, treenode, self.source) {
+            let text = get_treenode_text(&node, self.source);
+            match capture {
+                "name" => name = Some(text),
+                "modifier" => modifiers.push(text.to_string()),
+                _ => repl = Some(text.trim_start().to_string()),
+            }
+        }
+        let name = name.ok_or_else(|| Warning {
+            kind: WarningKind::MissingName,
+            message: format!("Failed to find command name for {}", treenode.kind()),
+            span: span_of(&treenode),
+        })?;
         Ok(VimNode::Command {
             name: name.to_string(),
             modifiers,
-            doc: self.doc.clone(),
+            repl,
+            doc: self.doc.take(),
+            span: None,
         })
     }
 
-    fn get_flag_node(&self) -> Result<Option<VimNode>, String> {
+    fn get_flag_node(&mut self) -> Result<Option<VimNode>, Warning> {
         let treenode = self.try_get_treenode()?;
-        let mut cursor = treenode.walk();
-        let call_exp = treenode
-            .children(&mut cursor)
-            .find(|c| c.kind() == "call_expression");
-        if let Some(func_expr) = call_exp.and_then(|call| call.child_by_field_name("function")) {
-            let last_func_id = tree_sitter_traversal::traverse(
-                func_expr.walk(),
-                tree_sitter_traversal::Order::Pre,
-            )
-            .filter(|n| n.kind() == "identifier")
-            .last();
-            if last_func_id
-                .is_some_and(|func_id| get_treenode_text(&func_id, self.source) == "Flag")
-            {
-                let arg1 = func_expr.next_named_sibling();
-                let arg2 = arg1.and_then(|a1| a1.next_named_sibling());
-                match arg1 {
-                    Some(arg1) if arg1.kind() == "string_literal" => {
-                        // Matched call Flag(arg1, arg2, ...).
-                        let flag_name_literal = get_treenode_text(&arg1, self.source);
-                        let flag_name = if let Some(flag_name) = flag_name_literal
-                            .strip_prefix("'")
-                            .and_then(|l| l.strip_suffix("'"))
-                        {
-                            flag_name.to_string()
-                        } else {
-                            quoted_string::unquote_unchecked(flag_name_literal).into()
-                        };
-                        let default_value =
-                            arg2.map(|a2| get_treenode_text(&a2, self.source).to_string());
-                        return Ok(Some(VimNode::Flag {
-                            name: flag_name,
-                            default_value_token: default_value,
-                            doc: self.doc.clone(),
-                        }));
-                    }
-                    _ => {}
-                }
+        let mut flag_name = None;
+        let mut default_value = None;
+        for (capture, node) in captures(&NodePatterns::get().flag, treenode, self.source) {
+            let text = get_treenode_text(&node, self.source);
+            match capture {
+                "name" => flag_name = Some(unquote_string_literal(text)),
+                "default" => default_value = Some(text.to_string()),
+                _ => {}
+            }
+        }
+        Ok(flag_name.map(|name| VimNode::Flag {
+            name,
+            default_value_token: default_value,
+            doc: self.doc.take(),
+            span: None,
+        }))
+    }
+
+    /// Gets the group defined by a `:highlight` statement, if it defines one. Statements like
+    /// `:hi clear Foo` or a bare `:hi Foo` don't.
+    fn get_highlight_node(&mut self) -> Result<Option<VimNode>, Warning> {
+        let treenode = self.try_get_treenode()?;
+        let mut name = None;
+        let mut default = false;
+        let mut link = None;
+        let mut attributes = vec![];
+        for (capture, node) in captures(&NodePatterns::get().highlight, treenode, self.source) {
+            let text = get_treenode_text(&node, self.source);
+            match capture {
+                "default" => default = true,
+                "name" => name = Some(text.to_string()),
+                "link" => link = Some(text.to_string()),
+                _ => attributes.push(text.to_string()),
+            }
+        }
+        Ok(name.map(|name| VimNode::Highlight {
+            name,
+            default,
+            link,
+            attributes,
+            doc: self.doc.take(),
+            span: None,
+        }))
+    }
+
+    /// Gets the sign defined by a `:sign define` statement. Other `:sign` subcommands don't
+    /// define anything.
+    fn get_sign_node(&mut self) -> Result<Option<VimNode>, Warning> {
+        let treenode = self.try_get_treenode()?;
+        let mut name = None;
+        let mut attributes = vec![];
+        for (capture, node) in captures(&NodePatterns::get().sign, treenode, self.source) {
+            let text = get_treenode_text(&node, self.source).to_string();
+            match capture {
+                "name" => name = Some(text),
+                _ => attributes.push(text),
             }
         }
+        Ok(name.map(|name| VimNode::Sign {
+            name,
+            attributes,
+            doc: self.doc.take(),
+            span: None,
+        }))
+    }
 
-        Ok(None)
+    /// Absorbs comments at the same column on the lines right after this comment block.
+    pub(crate) fn absorb_comment_lines<I: Iterator<Item = Node<'a>>>(
+        &mut self,
+        statements: &mut Peekable<I>,
+    ) {
+        while let Some(next) = statements.next_if(|next| {
+            let last = self.treenodes[self.treenodes.len() - 1].start_position();
+            next.kind() == "comment"
+                && next.start_position().row == last.row + 1
+                && next.start_position().column == last.column
+        }) {
+            self.treenodes.push(next);
+        }
     }
 
     pub(crate) fn maybe_consume_doc(&mut self, doc: &mut Option<TreeNodeMetadata>) {
         if !matches!(
             self.kind(),
-            "function_definition" | "command_statement" | "call_statement" | "let_statement"
+            "function_definition"
+                | "command_statement"
+                | "call_statement"
+                | "let_statement"
+                | "augroup_statement"
+                | "autocmd_statement"
+                | "map_statement"
+                | "highlight_statement"
+                | "sign_statement"
+                | "set_statement"
+                | "setlocal_statement"
         ) {
             return;
         }
-        if let Some(VimNode::StandaloneDocComment { doc: consumed_doc }) =
-            doc.take().and_then(|doc| {
-                let mut doc_nodes: Vec<VimNode> = doc.into();
-                // TODO: Use all nodes or error if multiple.
-                doc_nodes.pop()
-            })
-        {
+        if let Some(VimNode::StandaloneDocComment {
+            doc: consumed_doc, ..
+        }) = doc.take().and_then(|doc| {
+            let mut doc_nodes: Vec<VimNode> = doc.into();
+            // TODO: Use all nodes or error if multiple.
+            doc_nodes.pop()
+        }) {
             self.doc = Some(consumed_doc);
         }
     }
@@ -209,7 +300,16 @@ impl<'a> From<(Node<'a>, &'a [u8])> for TreeNodeMetadata<'a> {
 }
 
 impl<'a> From<TreeNodeMetadata<'a>> for Vec<VimNode> {
+    /// Converts into nodes, discarding any warnings (see [TreeNodeMetadata::into_nodes]).
     fn from(metadata: TreeNodeMetadata) -> Self {
+        metadata.into_nodes(&mut vec![])
+    }
+}
+
+impl TreeNodeMetadata<'_> {
+    /// Converts into the nodes it defines, adding any problems found to `warnings`.
+    pub(crate) fn into_nodes(self, warnings: &mut Vec<Warning>) -> Vec<VimNode> {
+        let mut metadata = self;
         match metadata.kind() {
             "comment" => {
                 let mut doc_lines = vec![];
@@ -234,9 +334,9 @@ impl<'a> From<TreeNodeMetadata<'a>> for Vec<VimNode> {
                     let comment_content = comment_text.strip_prefix("\"").unwrap();
                     doc_lines.push(comment_content.strip_prefix(" ").unwrap_or(comment_content));
                 }
-                vec![VimNode::StandaloneDocComment {
-                    doc: doc_lines.join("\n").trim_end().to_string(),
-                }]
+                let mut doc = doc_lines.join("\n");
+                doc.truncate(doc.trim_end().len());
+                vec![VimNode::StandaloneDocComment { doc, span: None }]
             }
             "function_definition" => {
                 let mut nodes = vec![];
@@ -244,8 +344,8 @@ impl<'a> From<TreeNodeMetadata<'a>> for Vec<VimNode> {
                     Ok(node) => {
                         nodes.push(node);
                     }
-                    Err(err) => {
-                        eprintln!("{err}");
+                    Err(warning) => {
+                        warnings.push(warning);
                     }
                 }
                 nodes
@@ -256,76 +356,133 @@ impl<'a> From<TreeNodeMetadata<'a>> for Vec<VimNode> {
                     Ok(node) => {
                         nodes.push(node);
                     }
-                    Err(err) => {
-                        eprintln!("{err}");
+                    Err(warning) => {
+                        warnings.push(warning);
                     }
                 }
                 nodes
             }
             "let_statement" => metadata.try_get_treenode().map_or_else(
-                |err| {
-                    eprintln!("{err}");
+                |warning| {
+                    warnings.push(warning);
                     vec![]
                 },
                 |treenode| {
-                    let mut nodes = vec![];
-                    // Extract identifier and its next named sibling from node like:
-                    // (let_statement (identifier) SOME_RHS)
-                    let mut cursor = treenode.walk();
-                    match treenode.children(&mut cursor).collect::<Vec<_>>()[..] {
-                        [cmd, _, op, _, ..] if cmd.kind() != "let" || op.kind() != "=" => {
-                            // Ignore types of let_statement besides standard assignment.
-                            // For example, let+= isn't defining a new variable.
-                        }
-                        [_, lhs, _, rhs, ..] if lhs.kind() == "list_assignment" => {
-                            // Destructuring assignment.
-                            let rhs_is_literal = rhs.kind() == "list"
-                                && lhs.named_child_count() == rhs.named_child_count();
-                            for (i, lhs) in lhs.named_children(&mut cursor).enumerate() {
-                                let rhs_str = if rhs_is_literal {
-                                    get_treenode_text(&rhs.named_child(i).unwrap(), metadata.source)
-                                        .to_string()
-                                } else {
-                                    format!("{}[{}]", get_treenode_text(&rhs, metadata.source), i)
-                                };
-                                nodes.push(VimNode::Variable {
-                                    name: get_treenode_text(&lhs, metadata.source).to_string(),
-                                    init_value_token: rhs_str,
-                                    doc: metadata.doc.clone(),
-                                });
-                            }
-                        }
-                        [_, lhs, _, rhs, ..] => {
-                            // Standard assignment.
-                            nodes.push(VimNode::Variable {
-                                name: get_treenode_text(&lhs, metadata.source).to_string(),
-                                init_value_token: get_treenode_text(&rhs, metadata.source)
-                                    .to_string(),
-                                doc: metadata.doc.clone(),
-                            });
-                        }
-                        _ => {}
-                    }
-
-                    nodes
+                    let doc = metadata.doc.take();
+                    let_assignments(treenode, metadata.source)
+                        .into_iter()
+                        .map(|(name, value)| VimNode::Variable {
+                            name: name.to_string(),
+                            init_value_token: value.into_owned(),
+                            doc: doc.clone(),
+                            span: None,
+                        })
+                        .collect()
                 },
             ),
+            "augroup_statement" => metadata
+                .try_get_treenode()
+                .ok()
+                .and_then(|treenode| treenode.named_child(0))
+                .map(|name| get_treenode_text(&name, metadata.source))
+                // `augroup END` closes the current group instead of defining one.
+                .filter(|name| !name.eq_ignore_ascii_case("END"))
+                .map(|name| VimNode::Augroup {
+                    name: name.to_string(),
+                    doc: metadata.doc.take(),
+                    span: None,
+                })
+                .into_iter()
+                .collect(),
+            "autocmd_statement" => metadata
+                .try_get_treenode()
+                .ok()
+                // Groups from an enclosing `:augroup` are filled in by the caller.
+                .and_then(|treenode| autocmd_from_node(&treenode, metadata.source, &None))
+                .map(|autocmd| VimNode::Autocmd {
+                    group: autocmd.group,
+                    events: autocmd.events,
+                    patterns: autocmd.patterns,
+                    command: autocmd.command,
+                    doc: metadata.doc.take(),
+                    span: None,
+                })
+                .into_iter()
+                .collect(),
+            "map_statement" => metadata
+                .try_get_treenode()
+                .ok()
+                .and_then(|treenode| {
+                    let mapping = mapping_from_node(&treenode, metadata.source)?;
+                    let (mode, noremap) = map_mode(&treenode, &mapping)?;
+                    Some(VimNode::Mapping {
+                        mode,
+                        noremap,
+                        lhs: mapping.lhs,
+                        rhs: mapping.rhs,
+                        arguments: mapping.arguments,
+                        doc: metadata.doc.take(),
+                        span: None,
+                    })
+                })
+                .into_iter()
+                .collect(),
             "call_statement" => match metadata.get_flag_node() {
                 Ok(Some(flag_node)) => vec![flag_node],
                 Ok(None) => vec![],
-                Err(err) => {
-                    eprintln!("{err}");
+                Err(warning) => {
+                    warnings.push(warning);
+                    vec![]
+                }
+            },
+            "set_statement" | "setlocal_statement" => metadata.try_get_treenode().map_or_else(
+                |warning| {
+                    warnings.push(warning);
+                    vec![]
+                },
+                |treenode| {
+                    let command = treenode.kind().trim_end_matches("_statement");
+                    let scope = if command == "setlocal" {
+                        "local"
+                    } else {
+                        "both"
+                    };
+                    let mut cursor = treenode.walk();
+                    treenode
+                        .named_children(&mut cursor)
+                        .filter_map(|item| setting_from_item(command, &item, metadata.source))
+                        .map(|setting| VimNode::SetOption {
+                            name: setting.name,
+                            item: setting.item,
+                            value: setting.value,
+                            scope: scope.to_string(),
+                            doc: metadata.doc.clone(),
+                            span: None,
+                        })
+                        .collect()
+                },
+            ),
+            "highlight_statement" => match metadata.get_highlight_node() {
+                Ok(node) => node.into_iter().collect(),
+                Err(warning) => {
+                    warnings.push(warning);
+                    vec![]
+                }
+            },
+            "sign_statement" => match metadata.get_sign_node() {
+                Ok(node) => node.into_iter().collect(),
+                Err(warning) => {
+                    warnings.push(warning);
                     vec![]
                 }
             },
             "ERROR" => {
-                let start_pos = metadata.treenodes[0].start_position();
-                eprintln!(
-                    "Syntax error at ({}, {}) near {:?}",
-                    start_pos.row,
-                    start_pos.column,
-                    get_treenode_text(&metadata.treenodes[0], metadata.source)
-                );
+                let text = get_treenode_text(&metadata.treenodes[0], metadata.source);
+                warnings.push(Warning {
+                    kind: WarningKind::SyntaxError,
+                    message: format!("Syntax error near {:?}", truncate_str(text, 40)),
+                    span: metadata.span(),
+                });
                 vec![]
             }
             _ => vec![],
@@ -358,6 +515,8 @@ mod tests {
                 args: vec![],
                 modifiers: vec![],
                 doc: None,
+                body: None,
+                span: None,
             }]
         );
     }
@@ -366,12 +525,18 @@ mod tests {
     fn metadata_into_nodes_func_missing_name() {
         let code = "func () | endfunc";
         let tree = tree_from_code(code);
-        let nodes: Vec<_> = node_metadata_from_code(&tree, code).into();
+        let mut warnings = vec![];
+        let nodes = node_metadata_from_code(&tree, code).into_nodes(&mut warnings);
+        assert_eq!(nodes, vec![]);
         assert_eq!(
-            nodes,
-            vec![
-                // Function skipped (printed to stderr instead).
-            ]
+            warnings
+                .iter()
+                .map(|w| (w.kind, w.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(
+                WarningKind::SyntaxError,
+                "Syntax error near \"func () | endfunc\""
+            )]
         );
     }
 
@@ -379,12 +544,18 @@ mod tests {
     fn metadata_into_nodes_command_missing_name() {
         let code = r"command -bang";
         let tree = tree_from_code(code);
-        let nodes: Vec<_> = node_metadata_from_code(&tree, code).into();
+        let mut warnings = vec![];
+        let nodes = node_metadata_from_code(&tree, code).into_nodes(&mut warnings);
+        assert_eq!(nodes, vec![]);
         assert_eq!(
-            nodes,
-            vec![
-                // Command skipped (printed to stderr instead).
-            ]
+            warnings
+                .iter()
+                .map(|w| (w.kind, w.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(
+                WarningKind::SyntaxError,
+                "Syntax error near \"command -bang\""
+            )]
         );
     }
 
@@ -434,12 +605,14 @@ mod tests {
                     name: "var1".to_string(),
                     init_value_token: "1".to_string(),
                     doc: Some("Some doc".into()),
+                    span: None,
                 },
                 VimNode::Variable {
                     name: "var2".to_string(),
                     init_value_token: "2".to_string(),
                     // Note: same doc attaches to all items.
                     doc: Some("Some doc".into()),
+                    span: None,
                 },
             ]
         );
@@ -457,11 +630,13 @@ mod tests {
                     name: "var1".to_string(),
                     init_value_token: "SomeFunc()[0]".to_string(),
                     doc: None,
+                    span: None,
                 },
                 VimNode::Variable {
                     name: "var2".to_string(),
                     init_value_token: "SomeFunc()[1]".to_string(),
                     doc: None,
+                    span: None,
                 },
             ]
         );
@@ -0,0 +1,172 @@
+use super::sections::classify_path;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Dirs that conventionally hold copies of other plugins inside a plugin repo.
+const VENDORED_DIRS: [&str; 6] = ["pack", "bundle", "deps", "vendor", "third_party", "plugged"];
+
+/// How [crate::VimParser::parse_plugin_dir] handles other plugins vendored inside a plugin.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VendoredPlugins {
+    /// Record vendored plugin paths in [crate::VimPlugin::vendored] without parsing them.
+    #[default]
+    Skip,
+    /// Parse vendored plugins as separate nested [crate::VimPlugin]s.
+    ParseNested,
+}
+
+/// Finds plugin roots vendored under known dirs like bundle/ or pack/*/start/ inside the plugin
+/// at `root`, returned as paths relative to `root`.
+pub fn detect_vendored_plugins<P: AsRef<Path>>(root: P) -> Vec<PathBuf> {
+    let root = root.as_ref();
+    let mut found = vec![];
+    for vendored_dir in VENDORED_DIRS {
+        let candidates = if vendored_dir == "pack" {
+            // Packages nest plugins as pack/{name}/{start,opt}/{plugin}.
+            subdirs(&root.join(vendored_dir))
+                .into_iter()
+                .flat_map(|package| {
+                    ["start", "opt"]
+                        .into_iter()
+                        .flat_map(move |kind| subdirs(&package.join(kind)))
+                })
+                .collect()
+        } else {
+            subdirs(&root.join(vendored_dir))
+        };
+        found.extend(
+            candidates
+                .into_iter()
+                .filter(|dir| looks_like_plugin_root(dir))
+                .filter_map(|dir| dir.strip_prefix(root).ok().map(Path::to_path_buf)),
+        );
+    }
+    found
+}
+
+/// Finds the plugin roots to scan for a runtimepath entry or plugin manager dir, like `~/.vim`,
+/// `~/.vim/bundle`, `~/.vim/pack` or `~/.vim/pack/foo/start`.
+///
+/// A path that's a plugin root itself is included along with any plugins vendored inside it (see
+/// [detect_vendored_plugins]). Otherwise its subdirs are searched for plugin roots and for
+/// package layouts with start/ and opt/ dirs.
+pub fn detect_plugin_roots<P: AsRef<Path>>(path: P) -> Vec<PathBuf> {
+    let path = path.as_ref();
+    if looks_like_plugin_root(path) {
+        let vendored = detect_vendored_plugins(path);
+        return std::iter::once(path.to_owned())
+            .chain(vendored.into_iter().map(|vendored| path.join(vendored)))
+            .collect();
+    }
+    let mut roots = vec![];
+    for dir in package_plugin_dirs(path) {
+        if looks_like_plugin_root(&dir) {
+            roots.push(dir);
+            continue;
+        }
+        // A package like pack/foo/ in a pack/ dir.
+        roots.extend(
+            package_plugin_dirs(&dir)
+                .into_iter()
+                .filter(|dir| looks_like_plugin_root(dir)),
+        );
+    }
+    roots
+}
+
+/// The subdirs of `dir`, or of its start/ and opt/ dirs if it's a package.
+fn package_plugin_dirs(dir: &Path) -> Vec<PathBuf> {
+    let package_dirs: Vec<_> = ["start", "opt"]
+        .into_iter()
+        .map(|kind| dir.join(kind))
+        .filter(|dir| dir.is_dir())
+        .collect();
+    if package_dirs.is_empty() {
+        subdirs(dir)
+    } else {
+        package_dirs.iter().flat_map(|dir| subdirs(dir)).collect()
+    }
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut dirs: Vec<_> = entries
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| e.path())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn looks_like_plugin_root(dir: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return false;
+    };
+    entries.filter_map(Result::ok).any(|e| {
+        let name = PathBuf::from(e.file_name());
+        name == Path::new("after")
+            || classify_path(&name, false).is_some_and(|(kind, _)| kind.is_some())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    #[test]
+    fn detect_vendored_plugins_known_dirs() {
+        let tmp_dir = tempdir().unwrap();
+        for path in [
+            "bundle/foo/plugin/foo.vim",
+            "bundle/not_a_plugin/README.md",
+            "pack/vendor/start/bar/autoload/bar.vim",
+            "pack/vendor/opt/baz/after/plugin/baz.vim",
+            "unknown/qux/plugin/qux.vim",
+        ] {
+            let path = tmp_dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        assert_eq!(
+            detect_vendored_plugins(tmp_dir.path()),
+            vec![
+                PathBuf::from("pack/vendor/start/bar"),
+                PathBuf::from("pack/vendor/opt/baz"),
+                PathBuf::from("bundle/foo"),
+            ]
+        );
+    }
+
+    #[test]
+    fn detect_plugin_roots_layouts() {
+        let tmp_dir = tempdir().unwrap();
+        for path in [
+            "bundle/foo/plugin/foo.vim",
+            "pack/a/start/bar/plugin/bar.vim",
+            "pack/a/opt/baz/autoload/baz.vim",
+            "pack/b/start/README.md",
+        ] {
+            let path = tmp_dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        let roots = |path: &str| {
+            detect_plugin_roots(tmp_dir.path().join(path))
+                .into_iter()
+                .map(|root| root.strip_prefix(tmp_dir.path()).unwrap().to_owned())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(roots("bundle"), vec![PathBuf::from("bundle/foo")]);
+        assert_eq!(
+            roots("pack"),
+            vec![PathBuf::from("pack/a/start/bar"), "pack/a/opt/baz".into()]
+        );
+        assert_eq!(roots("pack/a"), roots("pack")[..]);
+        assert_eq!(roots("bundle/foo"), vec![PathBuf::from("bundle/foo")]);
+    }
+}
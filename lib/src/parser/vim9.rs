@@ -0,0 +1,406 @@
+//! Line-based extraction for Vim9 script, which the tree-sitter grammar doesn't understand.
+//!
+//! Scanning lines is deliberate: the vim grammar has no Vim9 support to build on, and the few
+//! statements extracted here start at the beginning of a line. The exception is a `def`
+//! signature, whose arg list can continue onto later lines until its closing paren.
+//!
+//! Only script-level `def` functions and `var`/`const`/`final` declarations are recognized, along
+//! with `import` and `export` statements, including those inside blocks like `if`. Docs come from
+//! `#` comment blocks starting with a `##` leader, the Vim9 equivalent of `""`.
+
+use crate::{Span, Vim9Import, VimNode};
+use regex::Regex;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Definitions found in a Vim9 script.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Vim9Definitions {
+    /// Functions and variables with their spans, in source order. Defs span through their
+    /// `enddef`, declarations just their own line.
+    pub nodes: Vec<VimNode>,
+    /// The rows spanned by each `def` … `enddef` block, including nested ones.
+    pub def_rows: Vec<RangeInclusive<usize>>,
+    pub imports: Vec<Vim9Import>,
+    /// Names of exported functions, variables and types, in source order.
+    pub exports: Vec<String>,
+}
+
+impl Vim9Definitions {
+    pub fn contains_row(&self, row: usize) -> bool {
+        self.def_rows.iter().any(|rows| rows.contains(&row))
+    }
+}
+
+/// Whether the code starts with a `vim9script` command, ignoring blank and comment lines.
+pub(crate) fn is_vim9script(code: &str) -> bool {
+    code.lines()
+        .map(str::trim)
+        .find(|line| !(line.is_empty() || line.starts_with('"') || line.starts_with('#')))
+        .is_some_and(|line| line.split_whitespace().next() == Some("vim9script"))
+}
+
+/// Finds script-level definitions, skipping anything nested in a def.
+pub(crate) fn scan_vim9(code: &str) -> Vim9Definitions {
+    static DEF: OnceLock<Regex> = OnceLock::new();
+    static ENDDEF: OnceLock<Regex> = OnceLock::new();
+    static DECLARATION: OnceLock<Regex> = OnceLock::new();
+    static IMPORT: OnceLock<Regex> = OnceLock::new();
+    static EXPORT_TYPE: OnceLock<Regex> = OnceLock::new();
+    let def = DEF.get_or_init(|| Regex::new(r"^(export\s+)?def(!)?\s+([\w:#.<>]+)\s*\(").unwrap());
+    let enddef = ENDDEF.get_or_init(|| Regex::new(r"^enddef\b").unwrap());
+    let declaration = DECLARATION.get_or_init(|| {
+        Regex::new(r"^(export\s+)?(?:var|const|final)\s+([\w:#]+)(?:\s*:[^=]*)?\s*=\s*(.+)$")
+            .unwrap()
+    });
+    let import = IMPORT.get_or_init(|| {
+        Regex::new(r#"^import\s+(autoload\s+)?(?:'([^']+)'|"([^"]+)")(?:\s+as\s+(\w+))?"#).unwrap()
+    });
+    let export_type = EXPORT_TYPE.get_or_init(|| {
+        Regex::new(r"^export\s+(?:abstract\s+)?(?:class|interface|enum|type)\s+(\w+)").unwrap()
+    });
+
+    let mut definitions = Vim9Definitions::default();
+    // Start rows of the def blocks enclosing the current line, with the index of the node for
+    // script-level ones.
+    let mut open_defs: Vec<(usize, Option<usize>)> = vec![];
+    // Arg text of a script-level def signature continuing onto later lines, with its node index
+    // and paren depth.
+    let mut open_signature: Option<(usize, String, usize)> = None;
+    let mut doc_lines: Option<Vec<&str>> = None;
+    let mut line_start = 0;
+    for (row, full_line) in code.split_inclusive('\n').enumerate() {
+        let content = full_line.trim_end_matches(['\n', '\r']);
+        let line = content.trim();
+        let line_span = span_of_line(row, line_start, content);
+        line_start += full_line.len();
+        if let Some((index, mut args, mut depth)) = open_signature.take() {
+            match find_closing_paren(line, &mut depth) {
+                Some(end) => {
+                    args.push_str(&line[..end]);
+                    set_args(&mut definitions.nodes[index], parse_args(&args));
+                }
+                None => {
+                    args.push_str(line);
+                    args.push('\n');
+                    open_signature = Some((index, args, depth));
+                }
+            }
+            continue;
+        }
+        if let Some(leader_content) = line.strip_prefix("##") {
+            doc_lines = Some(
+                Some(leader_content.trim_start())
+                    .filter(|l| !l.is_empty())
+                    .into_iter()
+                    .collect(),
+            );
+            continue;
+        }
+        if let (Some(comment), Some(lines)) = (line.strip_prefix('#'), &mut doc_lines) {
+            lines.push(comment.strip_prefix(' ').unwrap_or(comment));
+            continue;
+        }
+        let doc = doc_lines
+            .take()
+            .map(|lines| lines.join("\n").trim_end().to_string());
+        if enddef.is_match(line) {
+            if let Some((start, index)) = open_defs.pop() {
+                definitions.def_rows.push(start..=row);
+                if let Some(span) =
+                    index.and_then(|index| definitions.nodes[index].span_mut().as_mut())
+                {
+                    span.end = line_span.end;
+                    span.byte_range.end = line_span.byte_range.end;
+                }
+            }
+            continue;
+        }
+        if let Some(captures) = def.captures(line) {
+            let mut index = None;
+            if open_defs.is_empty() {
+                let mut modifiers = vec![];
+                if captures.get(1).is_some() {
+                    modifiers.push("export".to_string());
+                    definitions.exports.push(captures[3].to_string());
+                }
+                if captures.get(2).is_some() {
+                    modifiers.push("!".to_string());
+                }
+                let rest = &line[captures.get(0).unwrap().end()..];
+                let mut depth = 1;
+                let args = match find_closing_paren(rest, &mut depth) {
+                    Some(end) => parse_args(&rest[..end]),
+                    None => {
+                        let args = format!("{rest}\n");
+                        open_signature = Some((definitions.nodes.len(), args, depth));
+                        vec![]
+                    }
+                };
+                index = Some(definitions.nodes.len());
+                definitions.nodes.push(VimNode::Function {
+                    name: captures[3].to_string(),
+                    args,
+                    modifiers,
+                    doc,
+                    body: None,
+                    span: Some(line_span),
+                });
+            }
+            open_defs.push((row, index));
+            continue;
+        }
+        if open_defs.is_empty() {
+            if let Some(captures) = declaration.captures(line) {
+                if captures.get(1).is_some() {
+                    definitions.exports.push(captures[2].to_string());
+                }
+                definitions.nodes.push(VimNode::Variable {
+                    name: captures[2].to_string(),
+                    init_value_token: strip_trailing_comment(&captures[3]).to_string(),
+                    doc,
+                    span: Some(line_span),
+                });
+            } else if let Some(captures) = export_type.captures(line) {
+                definitions.exports.push(captures[1].to_string());
+            } else if let Some(captures) = import.captures(line) {
+                let path = captures.get(2).or(captures.get(3)).unwrap().as_str();
+                let name = match captures.get(4) {
+                    Some(name) => name.as_str(),
+                    // Without `as`, the script is named after its file, like util for util.vim.
+                    None => Path::new(path)
+                        .file_stem()
+                        .and_then(|stem| stem.to_str())
+                        .unwrap_or(path),
+                };
+                definitions.imports.push(Vim9Import {
+                    path: path.to_string(),
+                    name: name.to_string(),
+                    autoload: captures.get(1).is_some(),
+                    used_items: vec![],
+                });
+            }
+        }
+    }
+    for import in &mut definitions.imports {
+        import.used_items = find_item_uses(code, &import.name);
+    }
+    definitions
+}
+
+/// Finds the items used through an imported script's name, like `Trim` in `util.Trim(x)`, in
+/// order of first use. Comment lines and imports (whose paths look like `util.vim`) are skipped.
+fn find_item_uses(code: &str, import_name: &str) -> Vec<String> {
+    let item_use = Regex::new(&format!(
+        r"(?:^|[^\w.#:]){}\.(\w+)",
+        regex::escape(import_name)
+    ))
+    .unwrap();
+    let mut items: Vec<String> = vec![];
+    for line in code.lines().filter(|line| {
+        let line = line.trim_start();
+        !(line.starts_with('#') || line.starts_with("import "))
+    }) {
+        for captures in item_use.captures_iter(line) {
+            if !items.iter().any(|item| *item == captures[1]) {
+                items.push(captures[1].to_string());
+            }
+        }
+    }
+    items
+}
+
+/// The span of a line's content, without its indentation or line break.
+fn span_of_line(row: usize, line_start: usize, content: &str) -> Span {
+    let start = content.len() - content.trim_start().len();
+    let end = content.trim_end().len().max(start);
+    Span {
+        start: (row, start),
+        end: (row, end),
+        byte_range: line_start + start..line_start + end,
+    }
+}
+
+/// Finds the paren closing an arg list that's `depth` parens deep at the start of `text`, and
+/// returns its byte offset, or None with `depth` updated if it's not closed within `text`.
+fn find_closing_paren(text: &str, depth: &mut usize) -> Option<usize> {
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' | '[' | '{' => *depth += 1,
+            ')' | ']' | '}' => {
+                *depth -= 1;
+                if *depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn set_args(node: &mut VimNode, parsed_args: Vec<String>) {
+    if let VimNode::Function { args, .. } = node {
+        *args = parsed_args;
+    }
+}
+
+/// Arg names from a def's arg list, dropping types and defaults like `count: number = 1` or
+/// `l: list<number> = [1, 2]`.
+fn parse_args(args: &str) -> Vec<String> {
+    let mut depth = 0;
+    args.split(|c| {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            _ => {}
+        }
+        c == ',' && depth == 0
+    })
+    .filter_map(|arg| {
+        let name = arg.split([':', '=']).next()?.trim();
+        (!name.is_empty()).then(|| name.to_string())
+    })
+    .collect()
+}
+
+/// Strips a ` # comment` after a value, which Vim9 allows at the end of a line.
+fn strip_trailing_comment(value: &str) -> &str {
+    value.split(" #").next().unwrap_or(value).trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn scan_vim9_definitions() {
+        let code = r#"vim9script
+
+## Frobs {x} things.
+# More docs.
+export def Foo(x: number, y = 2, ...rest: list<any>): string
+  var z = x + 1
+  def Inner()
+  enddef
+  return "a"
+enddef
+
+export const MAX: number = 3  # The max.
+var items: list<string>
+export class Frobber
+endclass
+import autoload "foo/util.vim"
+import './other.vim' as o
+echo util.Trim(o.x) .. o.x .. o.Y()
+# o.Z()
+"#;
+        assert!(is_vim9script(code));
+        assert!(!is_vim9script("\" Legacy.\nlet x = 1\n"));
+        assert_eq!(
+            scan_vim9(code),
+            Vim9Definitions {
+                nodes: vec![
+                    VimNode::Function {
+                        name: "Foo".into(),
+                        args: vec!["x".into(), "y".into(), "...rest".into()],
+                        modifiers: vec!["export".into()],
+                        doc: Some("Frobs {x} things.\nMore docs.".into()),
+                        body: None,
+                        span: Some(Span {
+                            start: (4, 0),
+                            end: (9, 6),
+                            byte_range: 46..165,
+                        }),
+                    },
+                    VimNode::Variable {
+                        name: "MAX".into(),
+                        init_value_token: "3".into(),
+                        doc: None,
+                        span: Some(Span {
+                            start: (11, 0),
+                            end: (11, 40),
+                            byte_range: 167..207,
+                        }),
+                    },
+                ],
+                def_rows: vec![6..=7, 4..=9],
+                imports: vec![
+                    Vim9Import {
+                        path: "foo/util.vim".into(),
+                        name: "util".into(),
+                        autoload: true,
+                        used_items: vec!["Trim".into()],
+                    },
+                    Vim9Import {
+                        path: "./other.vim".into(),
+                        name: "o".into(),
+                        autoload: false,
+                        used_items: vec!["x".into(), "Y".into()],
+                    },
+                ],
+                exports: vec!["Foo".into(), "MAX".into(), "Frobber".into()],
+            }
+        );
+    }
+
+    #[test]
+    fn scan_vim9_multiline_signature() {
+        let code = r#"vim9script
+def Foo(
+    x: number,
+    l: list<number> = [1, 2],
+    F: func(number, string): bool = (a, b) => true,
+    ): string
+  return ""
+enddef
+"#;
+        let definitions = scan_vim9(code);
+        assert_eq!(
+            definitions
+                .nodes
+                .iter()
+                .map(|node| match node {
+                    VimNode::Function { args, .. } => args.clone(),
+                    _ => vec![],
+                })
+                .collect::<Vec<_>>(),
+            vec![vec!["x".to_string(), "l".into(), "F".into()]]
+        );
+        assert_eq!(
+            definitions.nodes[0]
+                .span()
+                .map(|span| (span.start, span.end)),
+            Some(((1, 0), (7, 6)))
+        );
+        assert_eq!(definitions.def_rows, vec![1..=7]);
+    }
+
+    #[test]
+    fn scan_vim9_export_def_in_if_block() {
+        let code = r#"vim9script
+if has('nvim')
+  export def Foo(x: number)
+  enddef
+else
+  export def Foo()
+  enddef
+endif
+"#;
+        let definitions = scan_vim9(code);
+        assert_eq!(
+            definitions
+                .nodes
+                .iter()
+                .map(|node| (node.name(), node.span().map(|span| (span.start, span.end))))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some("Foo"), Some(((2, 2), (3, 8)))),
+                (Some("Foo"), Some(((5, 2), (6, 8)))),
+            ]
+        );
+        assert_eq!(definitions.exports, vec!["Foo", "Foo"]);
+    }
+}
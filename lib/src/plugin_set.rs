@@ -0,0 +1,53 @@
+use crate::VimPlugin;
+
+/// A collection of named plugins analyzed together, like everything installed on a runtimepath.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VimPluginSet {
+    plugins: Vec<(String, VimPlugin)>,
+}
+
+impl VimPluginSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a plugin under the given name, keeping insertion order.
+    pub fn add<S: Into<String>>(&mut self, name: S, plugin: VimPlugin) -> &mut Self {
+        self.plugins.push((name.into(), plugin));
+        self
+    }
+
+    /// Gets the first plugin added under the given name.
+    pub fn get(&self, name: &str) -> Option<&VimPlugin> {
+        self.plugins
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, plugin)| plugin)
+    }
+
+    /// Iterates over plugins paired with their names, in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &VimPlugin)> {
+        self.plugins
+            .iter()
+            .map(|(name, plugin)| (name.as_str(), plugin))
+    }
+
+    pub fn len(&self) -> usize {
+        self.plugins.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+}
+
+impl<S: Into<String>> FromIterator<(S, VimPlugin)> for VimPluginSet {
+    fn from_iter<I: IntoIterator<Item = (S, VimPlugin)>>(iter: I) -> Self {
+        Self {
+            plugins: iter
+                .into_iter()
+                .map(|(name, plugin)| (name.into(), plugin))
+                .collect(),
+        }
+    }
+}
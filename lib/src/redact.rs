@@ -0,0 +1,134 @@
+use crate::{VimModule, VimNode, VimPlugin};
+use regex::Regex;
+use unicode_ellipsis::truncate_str;
+
+/// Placeholder for values hidden by [ValueRedaction].
+pub const REDACTED: &str = "<redacted>";
+
+/// Rules for shortening or hiding the value tokens of variables and flags, which can embed huge
+/// dict literals or secrets like API tokens in private configs.
+///
+/// Everything else about the nodes (names, docs, kinds) is kept as is.
+///
+/// ```
+/// use vim_plugin_metadata::{ValueRedaction, VimNode, REDACTED};
+///
+/// let redaction = ValueRedaction::new()
+///     .truncate(20)
+///     .redact_names_matching("(?i)token|password")
+///     .unwrap();
+/// let mut node: VimNode = VimNode::variable("g:foo_api_token", "'hunter2'").into();
+/// redaction.apply_to_node(&mut node);
+/// assert_eq!(node, VimNode::variable("g:foo_api_token", REDACTED).into());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ValueRedaction {
+    max_len: Option<usize>,
+    value_patterns: Vec<Regex>,
+    name_patterns: Vec<Regex>,
+}
+
+impl ValueRedaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Truncates values longer than `max_len` chars, ending them with `…`.
+    pub fn truncate(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Redacts values matching the given regex anywhere in the value.
+    pub fn redact_values_matching(mut self, pattern: &str) -> crate::Result<Self> {
+        self.value_patterns.push(Regex::new(pattern)?);
+        Ok(self)
+    }
+
+    /// Redacts the values of variables and flags whose names match the given regex.
+    pub fn redact_names_matching(mut self, pattern: &str) -> crate::Result<Self> {
+        self.name_patterns.push(Regex::new(pattern)?);
+        Ok(self)
+    }
+
+    /// Applies the rules to a value token, given the name of the variable or flag it belongs to.
+    pub fn apply(&self, name: &str, value: &mut String) {
+        if self.name_patterns.iter().any(|p| p.is_match(name))
+            || self.value_patterns.iter().any(|p| p.is_match(value))
+        {
+            *value = REDACTED.to_string();
+        } else if let Some(max_len) = self.max_len {
+            if value.chars().count() > max_len {
+                *value = truncate_str(value, max_len).into_owned();
+            }
+        }
+    }
+
+    pub fn apply_to_node(&self, node: &mut VimNode) {
+        match node {
+            VimNode::Variable {
+                name,
+                init_value_token,
+                ..
+            } => self.apply(name, init_value_token),
+            VimNode::Flag {
+                name,
+                default_value_token: Some(value),
+                ..
+            } => self.apply(name, value),
+            _ => {}
+        }
+    }
+}
+
+impl VimModule {
+    /// Truncates or redacts the value tokens of this module's variables and flags.
+    pub fn redact_values(&mut self, redaction: &ValueRedaction) {
+        for node in &mut self.nodes {
+            redaction.apply_to_node(node);
+        }
+    }
+}
+
+impl VimPlugin {
+    /// Truncates or redacts the value tokens of variables and flags in all modules.
+    pub fn redact_values(&mut self, redaction: &ValueRedaction) {
+        for module in &mut self.content {
+            module.redact_values(redaction);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn redact_values_truncates_and_redacts() {
+        let mut module = VimModule::new(vec![
+            VimNode::variable("g:foo_mappings", "{'a': 1, 'b': 2, 'c': 3}").into(),
+            VimNode::variable("g:foo_url", "'https://user:pw@example.com'").into(),
+            VimNode::flag("secret").default_value_token("'x'").into(),
+            VimNode::flag("verbose").into(),
+            VimNode::function("foo#Secret").into(),
+        ]);
+        let redaction = ValueRedaction::new()
+            .truncate(10)
+            .redact_values_matching(r"://[^/]*@")
+            .unwrap()
+            .redact_names_matching("secret")
+            .unwrap();
+        module.redact_values(&redaction);
+        assert_eq!(
+            module.nodes,
+            vec![
+                VimNode::variable("g:foo_mappings", "{'a': 1, …").into(),
+                VimNode::variable("g:foo_url", REDACTED).into(),
+                VimNode::flag("secret").default_value_token(REDACTED).into(),
+                VimNode::flag("verbose").into(),
+                VimNode::function("foo#Secret").into(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use crate::parser::read_source;
+use crate::{ModuleLanguage, TextEdit, VimModule, VimParser, VimPluginSet};
+
+/// Computes the edits to rename a function, command or variable everywhere it's defined or
+/// referenced across the plugins in the set.
+///
+/// Sources are re-read from each plugin's [crate::VimPlugin::root], so plugins without a root
+/// are skipped. Returns no edits if no plugin defines the symbol. Script-local functions are
+/// also renamed where they're referenced through `<SID>`. Script-local names are only renamed in
+/// modules that define them, since the same `s:` name in another script is unrelated.
+///
+/// Note: renaming an autoload function into a different namespace also requires moving it to
+/// the matching autoload file, which isn't handled here.
+pub fn rename_symbol(
+    plugins: &VimPluginSet,
+    old_name: &str,
+    new_name: &str,
+) -> crate::Result<Vec<TextEdit>> {
+    let is_defined = plugins
+        .iter()
+        .any(|(_, plugin)| plugin.symbols().any(|s| s.name() == old_name));
+    if !is_defined {
+        return Ok(vec![]);
+    }
+    let script_local = old_name.starts_with("s:") || old_name.starts_with("<SID>");
+    let defines = |module: &VimModule| {
+        module
+            .nodes
+            .iter()
+            .any(|node| node.name() == Some(old_name))
+    };
+    let parser = VimParser::new()?;
+    let mut edits = vec![];
+    for (_, plugin) in plugins.iter() {
+        let Some(root) = &plugin.root else {
+            continue;
+        };
+        for module in &plugin.content {
+            let Some(path) = module
+                .path
+                .as_ref()
+                .filter(|_| module.language != ModuleLanguage::Lua)
+                .filter(|_| !script_local || defines(module))
+            else {
+                continue;
+            };
+            let file = root.join(path);
+            let code = read_source(&file)?;
+            for reference in parser.find_references_str(&code, old_name)? {
+                let new_text = match (reference.text == old_name, new_name.strip_prefix("s:")) {
+                    // Keep `<SID>` references in `<SID>` form.
+                    (false, Some(script_local)) => format!("<SID>{script_local}"),
+                    _ => new_name.to_string(),
+                };
+                edits.push(TextEdit {
+                    file: Some(file.clone()),
+                    range: reference.span,
+                    new_text,
+                });
+            }
+        }
+    }
+    Ok(edits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    #[test]
+    fn rename_symbol_across_files() {
+        let tmp_dir = tempdir().unwrap();
+        let files = [
+            (
+                "autoload/foo.vim",
+                "function! foo#Bar() abort\nendfunction\n\nfunction! foo#BarBaz() abort\nendfunction\n",
+            ),
+            (
+                "plugin/foo.vim",
+                "command Foo call foo#Bar()\nnnoremap x :call foo#Bar()<CR>\nlet s:F = function('foo#Bar')\n",
+            ),
+        ];
+        for (path, code) in files {
+            let path = tmp_dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, code).unwrap();
+        }
+        let plugin = VimParser::new()
+            .unwrap()
+            .parse_plugin_dir(tmp_dir.path())
+            .unwrap();
+        let plugins: VimPluginSet = [("foo", plugin)].into_iter().collect();
+        let edits = rename_symbol(&plugins, "foo#Bar", "foo#Qux").unwrap();
+        assert_eq!(edits.len(), 4);
+        for (path, _) in files {
+            let file = tmp_dir.path().join(path);
+            let mut code = fs::read_to_string(&file).unwrap();
+            let mut file_edits: Vec<_> = edits
+                .iter()
+                .filter(|e| e.file.as_deref() == Some(file.as_path()))
+                .collect();
+            file_edits.sort_by_key(|e| std::cmp::Reverse(e.range.byte_range.start));
+            for edit in file_edits {
+                code.replace_range(edit.range.byte_range.clone(), &edit.new_text);
+            }
+            fs::write(&file, code).unwrap();
+        }
+        let read = |path: &str| fs::read_to_string(tmp_dir.path().join(Path::new(path))).unwrap();
+        assert_eq!(
+            read("autoload/foo.vim"),
+            "function! foo#Qux() abort\nendfunction\n\nfunction! foo#BarBaz() abort\nendfunction\n"
+        );
+        assert_eq!(
+            read("plugin/foo.vim"),
+            "command Foo call foo#Qux()\nnnoremap x :call foo#Qux()<CR>\nlet s:F = function('foo#Qux')\n"
+        );
+        assert_eq!(rename_symbol(&plugins, "foo#Missing", "x").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn rename_symbol_script_local_stays_in_defining_files() {
+        let tmp_dir = tempdir().unwrap();
+        let helper = "function! s:Helper() abort\nendfunction\n\n\
+                      nnoremap x :call <SID>Helper()<CR>\n";
+        let files = [
+            ("plugin/a.vim", helper),
+            ("plugin/b.vim", helper),
+            // Calls an s:Helper it doesn't define, so it's not the same function.
+            ("plugin/c.vim", "call s:Helper()\n"),
+        ];
+        for (path, code) in files {
+            let path = tmp_dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, code).unwrap();
+        }
+        let plugin = VimParser::new()
+            .unwrap()
+            .parse_plugin_dir(tmp_dir.path())
+            .unwrap();
+        let plugins: VimPluginSet = [("foo", plugin)].into_iter().collect();
+        let edits = rename_symbol(&plugins, "s:Helper", "s:Renamed").unwrap();
+        let edited: Vec<_> = edits
+            .iter()
+            .map(|e| {
+                let file = e.file.as_deref().unwrap();
+                (
+                    file.strip_prefix(tmp_dir.path()).unwrap(),
+                    e.new_text.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            edited,
+            vec![
+                (Path::new("plugin/a.vim"), "s:Renamed"),
+                (Path::new("plugin/a.vim"), "<SID>Renamed"),
+                (Path::new("plugin/b.vim"), "s:Renamed"),
+                (Path::new("plugin/b.vim"), "<SID>Renamed"),
+            ]
+        );
+    }
+}
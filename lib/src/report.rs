@@ -0,0 +1,248 @@
+use crate::{ModuleStats, PluginDependency, VimNodeKind, VimPlugin};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+/// Version of the [Report] format, bumped whenever fields are removed or change meaning so
+/// consumers can tell which reports they understand. Added fields don't change it.
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+/// An analysis [VimPlugin::report] can include.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReportSection {
+    /// Doc coverage of the public API (see [VimPlugin::module_doc_coverage]).
+    Coverage,
+    /// Conflicting definitions, currently duplicate help tags (see
+    /// [VimPlugin::duplicate_help_tags]).
+    Conflicts,
+    /// See [VimPlugin::dependencies].
+    Dependencies,
+    /// Module, definition and line counts.
+    Stats,
+}
+
+impl ReportSection {
+    pub const ALL: [ReportSection; 4] = [
+        ReportSection::Coverage,
+        ReportSection::Conflicts,
+        ReportSection::Dependencies,
+        ReportSection::Stats,
+    ];
+}
+
+/// The results of several analyses of one plugin bundled into a single serializable document,
+/// for CI artifacts and dashboards. Sections that weren't requested are None.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    /// The [REPORT_SCHEMA_VERSION] the report was written with.
+    pub schema_version: u32,
+    pub plugin: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<CoverageReport>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conflicts: Option<ConflictsReport>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dependencies: Option<Vec<PluginDependency>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<StatsReport>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CoverageReport {
+    pub documented: usize,
+    pub total: usize,
+    /// Coverage per module, for modules with any public API.
+    pub modules: Vec<ModuleCoverageReport>,
+    /// Names of the undocumented public functions, commands and flags, in module order.
+    pub undocumented: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModuleCoverageReport {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    pub documented: usize,
+    pub total: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConflictsReport {
+    pub duplicate_help_tags: Vec<HelpTagConflict>,
+}
+
+/// A help tag produced by more than one documented symbol.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HelpTagConflict {
+    pub tag: String,
+    /// The names of the symbols producing the tag, with the paths of their modules.
+    pub definitions: Vec<(Option<PathBuf>, String)>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct StatsReport {
+    pub module_count: usize,
+    /// Number of nodes of each kind across all modules.
+    pub node_counts: BTreeMap<VimNodeKind, usize>,
+    /// Line counts summed across all modules.
+    pub lines: ModuleStats,
+    pub warning_count: usize,
+    /// Number of modules that failed to parse (see [VimPlugin::errors]).
+    pub error_count: usize,
+}
+
+impl VimPlugin {
+    /// Runs the selected analyses and bundles their results into a [Report], using
+    /// `plugin_name` to name the plugin and generate help tags.
+    pub fn report(&self, plugin_name: &str, sections: &[ReportSection]) -> Report {
+        let includes = |section| sections.contains(&section);
+        Report {
+            schema_version: REPORT_SCHEMA_VERSION,
+            plugin: plugin_name.to_string(),
+            coverage: includes(ReportSection::Coverage).then(|| self.coverage_report()),
+            conflicts: includes(ReportSection::Conflicts).then(|| ConflictsReport {
+                duplicate_help_tags: self
+                    .duplicate_help_tags(plugin_name)
+                    .into_iter()
+                    .map(|duplicate| HelpTagConflict {
+                        tag: duplicate.tag,
+                        definitions: duplicate
+                            .definitions
+                            .iter()
+                            .map(|d| (d.symbol.module.path.clone(), d.symbol.name().to_string()))
+                            .collect(),
+                    })
+                    .collect(),
+            }),
+            dependencies: includes(ReportSection::Dependencies).then(|| self.dependencies()),
+            stats: includes(ReportSection::Stats).then(|| self.stats_report()),
+        }
+    }
+
+    fn coverage_report(&self) -> CoverageReport {
+        let modules: Vec<ModuleCoverageReport> = self
+            .module_doc_coverage()
+            .into_iter()
+            .filter(|coverage| coverage.total > 0)
+            .map(|coverage| ModuleCoverageReport {
+                path: coverage.module.path.clone(),
+                documented: coverage.documented,
+                total: coverage.total,
+            })
+            .collect();
+        CoverageReport {
+            documented: modules.iter().map(|m| m.documented).sum(),
+            total: modules.iter().map(|m| m.total).sum(),
+            modules,
+            undocumented: self
+                .undocumented_symbols()
+                .iter()
+                .map(|symbol| symbol.name().to_string())
+                .collect(),
+        }
+    }
+
+    fn stats_report(&self) -> StatsReport {
+        let mut stats = StatsReport {
+            module_count: self.content.len(),
+            error_count: self.errors.len(),
+            ..Default::default()
+        };
+        for module in &self.content {
+            for node in &module.nodes {
+                *stats.node_counts.entry(node.kind()).or_default() += 1;
+            }
+            stats.lines += module.stats;
+            stats.warning_count += module.warnings.len();
+        }
+        stats
+    }
+}
+
+impl Report {
+    /// Serializes the report as compact JSON.
+    pub fn write_to<W: Write>(&self, writer: W) -> crate::Result<()> {
+        serde_json::to_writer(writer, self)?;
+        Ok(())
+    }
+
+    /// Loads a report previously written with [Report::write_to]. Check
+    /// [Report::schema_version] before relying on the contents of reports from other versions.
+    pub fn read_from<R: Read>(reader: R) -> crate::Result<Self> {
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{VimModule, VimNode};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn report_sections_round_trip() {
+        let plugin = VimPlugin::new(vec![
+            VimModule::new(vec![
+                VimNode::command("Foo").doc("Does foo.").into(),
+                VimNode::function("foo#Foo").into(),
+            ])
+            .with_path("plugin/foo.vim"),
+            VimModule::new(vec![
+                VimNode::function("s:Helper").into(),
+                VimNode::function("foo#Bar").doc("Does bar.").into(),
+                VimNode::function("foo#Bar").doc("Redefines bar.").into(),
+            ])
+            .with_path("autoload/foo.vim"),
+        ]);
+        let report = plugin.report("foo", &[ReportSection::Coverage, ReportSection::Conflicts]);
+        assert_eq!(
+            report,
+            Report {
+                schema_version: REPORT_SCHEMA_VERSION,
+                plugin: "foo".into(),
+                coverage: Some(CoverageReport {
+                    documented: 3,
+                    total: 4,
+                    modules: vec![
+                        ModuleCoverageReport {
+                            path: Some("plugin/foo.vim".into()),
+                            documented: 1,
+                            total: 2,
+                        },
+                        ModuleCoverageReport {
+                            path: Some("autoload/foo.vim".into()),
+                            documented: 2,
+                            total: 2,
+                        },
+                    ],
+                    undocumented: vec!["foo#Foo".into()],
+                }),
+                conflicts: Some(ConflictsReport {
+                    duplicate_help_tags: vec![HelpTagConflict {
+                        tag: "foo#Bar()".into(),
+                        definitions: vec![
+                            (Some("autoload/foo.vim".into()), "foo#Bar".into()),
+                            (Some("autoload/foo.vim".into()), "foo#Bar".into()),
+                        ],
+                    }],
+                }),
+                dependencies: None,
+                stats: None,
+            }
+        );
+
+        let report = plugin.report("foo", &ReportSection::ALL);
+        assert_eq!(report.dependencies, Some(vec![]));
+        let stats = report.stats.as_ref().unwrap();
+        assert_eq!(
+            (
+                stats.module_count,
+                stats.node_counts.get(&VimNodeKind::Function)
+            ),
+            (2, Some(&4))
+        );
+        let mut json = vec![];
+        report.write_to(&mut json).unwrap();
+        assert_eq!(Report::read_from(json.as_slice()).unwrap(), report);
+    }
+}
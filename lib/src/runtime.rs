@@ -0,0 +1,261 @@
+use crate::filetypes::FILETYPE_SECTIONS;
+use crate::{Section, SectionKind, Symbol, VimModule, VimPlugin};
+use std::path::{Path, PathBuf};
+
+/// A virtual view over several plugins (or config dirs) in runtimepath order, resolving which
+/// files and definitions take effect the way vim does.
+///
+/// Vim sources every matching file for most sections (like all plugin/ files from every entry),
+/// but only the first match for autoload/, colors/ and compiler/ files, so files at the same
+/// path in later entries are shadowed. Among sourced files, later definitions of the same
+/// function or command override earlier ones.
+///
+/// Filetype-scoped sections (ftplugin/, indent/ and syntax/) are only sourced for buffers of
+/// their filetype, so they're left out unless a filetype is given, as with
+/// [RuntimeView::resolve_for].
+#[derive(Clone, Debug)]
+pub struct RuntimeView<'a> {
+    roots: Vec<&'a VimPlugin>,
+}
+
+/// A module in a [RuntimeView] along with the index of the root it came from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RuntimeModule<'a> {
+    pub root: usize,
+    pub module: &'a VimModule,
+}
+
+/// A module that never takes effect because an earlier root provides the same path.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShadowedModule<'a> {
+    pub shadowed: RuntimeModule<'a>,
+    pub winner: RuntimeModule<'a>,
+}
+
+/// A symbol definition in a [RuntimeView] along with the index of the root it came from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RuntimeSymbol<'a> {
+    pub root: usize,
+    pub symbol: Symbol<'a>,
+}
+
+impl<'a> RuntimeView<'a> {
+    /// Creates a view over the given plugins, in runtimepath order (earliest first).
+    pub fn new<I: IntoIterator<Item = &'a VimPlugin>>(roots: I) -> Self {
+        Self {
+            roots: roots.into_iter().collect(),
+        }
+    }
+
+    /// All modules that take effect globally, in the order vim would source them: normal files
+    /// from every root first, then after/ files from every root.
+    pub fn effective_modules(&self) -> Vec<RuntimeModule<'a>> {
+        self.effective_modules_for(None)
+    }
+
+    /// Like [RuntimeView::effective_modules], followed by the modules vim sources for a buffer
+    /// of `filetype`, if given: each filetype-scoped section's normal files, then its after/
+    /// files, with ftplugin/ before indent/ before syntax/.
+    pub fn effective_modules_for(&self, filetype: Option<&str>) -> Vec<RuntimeModule<'a>> {
+        let mut modules = vec![];
+        let scoped_kinds = filetype.map_or(&[][..], |_| &FILETYPE_SECTIONS[..]);
+        for kind in [None].into_iter().chain(scoped_kinds.iter().map(Some)) {
+            for after in [false, true] {
+                for module in self.all_modules() {
+                    let Some(section) = module.module.section() else {
+                        continue;
+                    };
+                    let included = match kind {
+                        None => !FILETYPE_SECTIONS.contains(&section.kind),
+                        Some(kind) => {
+                            section.kind == *kind && module.module.filetype().as_deref() == filetype
+                        }
+                    };
+                    if included && section.after == after && self.shadowing_module(module).is_none()
+                    {
+                        modules.push(module);
+                    }
+                }
+            }
+        }
+        modules
+    }
+
+    /// All modules hidden by a module at the same path in an earlier root.
+    pub fn shadowed_modules(&self) -> Vec<ShadowedModule<'a>> {
+        self.all_modules()
+            .filter_map(|shadowed| {
+                self.shadowing_module(shadowed)
+                    .map(|winner| ShadowedModule { shadowed, winner })
+            })
+            .collect()
+    }
+
+    /// Finds the definition of the named symbol that actually takes effect globally, if any.
+    pub fn resolve(&self, name: &str) -> Option<RuntimeSymbol<'a>> {
+        self.resolve_for(name, None)
+    }
+
+    /// Finds the definition of the named symbol that takes effect in a buffer of `filetype`,
+    /// including definitions from its filetype-scoped modules (see
+    /// [RuntimeView::effective_modules_for]).
+    pub fn resolve_for(&self, name: &str, filetype: Option<&str>) -> Option<RuntimeSymbol<'a>> {
+        if let Some(autoload_path) = autoload_path_for(name) {
+            // Autoload functions are only ever loaded from the first file providing their path.
+            let module = self
+                .all_modules()
+                .find(|m| m.module.path.as_deref() == Some(autoload_path.as_path()))?;
+            return self.definitions_in(module, name).last();
+        }
+        // Otherwise the last definition sourced wins, as with `function!` or `command!`.
+        self.effective_modules_for(filetype)
+            .into_iter()
+            .flat_map(|module| self.definitions_in(module, name))
+            .last()
+    }
+
+    fn all_modules(&self) -> impl Iterator<Item = RuntimeModule<'a>> + '_ {
+        self.roots.iter().enumerate().flat_map(|(root, plugin)| {
+            plugin
+                .content
+                .iter()
+                .map(move |module| RuntimeModule { root, module })
+        })
+    }
+
+    fn shadowing_module(&self, module: RuntimeModule<'a>) -> Option<RuntimeModule<'a>> {
+        let path = module.module.path.as_deref()?;
+        if !is_first_match_only(module.module.section()?) {
+            return None;
+        }
+        self.all_modules()
+            .take_while(|m| m.root < module.root)
+            .find(|m| m.module.path.as_deref() == Some(path))
+    }
+
+    fn definitions_in<'s>(
+        &'s self,
+        module: RuntimeModule<'a>,
+        name: &'s str,
+    ) -> impl Iterator<Item = RuntimeSymbol<'a>> + 's {
+        module
+            .module
+            .nodes
+            .iter()
+            .filter(move |node| node.name() == Some(name))
+            .map(move |node| RuntimeSymbol {
+                root: module.root,
+                symbol: Symbol {
+                    module: module.module,
+                    node,
+                },
+            })
+    }
+}
+
+fn is_first_match_only(section: Section) -> bool {
+    matches!(
+        section.kind,
+        SectionKind::Autoload | SectionKind::Colors | SectionKind::Compiler
+    )
+}
+
+/// Maps an autoload function name like `foo#bar#Baz` to its file path `autoload/foo/bar.vim`.
+pub(crate) fn autoload_path_for(name: &str) -> Option<PathBuf> {
+    let (namespace, _) = name.rsplit_once('#')?;
+    let mut path = Path::new("autoload").to_path_buf();
+    path.extend(namespace.split('#'));
+    path.set_extension("vim");
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VimNode;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn resolve_autoload_first_root_wins() {
+        let user_config = VimPlugin::new(vec![VimModule::new(vec![VimNode::function("foo#Bar")
+            .doc("Override")
+            .into()])
+        .with_path("autoload/foo.vim")]);
+        let plugin = VimPlugin::new(vec![VimModule::new(vec![VimNode::function("foo#Bar")
+            .doc("Original")
+            .into()])
+        .with_path("autoload/foo.vim")]);
+        let view = RuntimeView::new([&user_config, &plugin]);
+        let resolved = view.resolve("foo#Bar").unwrap();
+        assert_eq!(resolved.root, 0);
+        assert_eq!(resolved.symbol.node.get_doc(), Some("Override"));
+        assert_eq!(
+            view.shadowed_modules()
+                .iter()
+                .map(|s| (s.shadowed.root, s.winner.root))
+                .collect::<Vec<_>>(),
+            vec![(1, 0)]
+        );
+    }
+
+    #[test]
+    fn resolve_last_sourced_wins() {
+        let first = VimPlugin::new(vec![
+            VimModule::new(vec![VimNode::command("Foo").doc("After").into()])
+                .with_path("after/plugin/foo.vim"),
+            VimModule::new(vec![VimNode::command("Foo").doc("First").into()])
+                .with_path("plugin/foo.vim"),
+        ]);
+        let second = VimPlugin::new(vec![VimModule::new(vec![VimNode::command("Foo")
+            .doc("Second")
+            .into()])
+        .with_path("plugin/foo.vim")]);
+        let view = RuntimeView::new([&first, &second]);
+        assert_eq!(
+            view.effective_modules()
+                .iter()
+                .map(|m| (m.root, m.module.path.clone().unwrap()))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, PathBuf::from("plugin/foo.vim")),
+                (1, PathBuf::from("plugin/foo.vim")),
+                (0, PathBuf::from("after/plugin/foo.vim")),
+            ]
+        );
+        assert_eq!(
+            view.resolve("Foo").and_then(|s| s.symbol.node.get_doc()),
+            Some("After")
+        );
+    }
+
+    #[test]
+    fn resolve_filetype_scoped_definitions() {
+        let plugin = VimPlugin::new(vec![
+            VimModule::new(vec![VimNode::command("Foo").doc("Python").into()])
+                .with_path("ftplugin/python.vim"),
+            VimModule::new(vec![VimNode::command("Foo").doc("Global").into()])
+                .with_path("plugin/foo.vim"),
+            VimModule::new(vec![VimNode::command("Foo").doc("Syntax").into()])
+                .with_path("syntax/vim.vim"),
+        ]);
+        let view = RuntimeView::new([&plugin]);
+        let doc = |filetype| {
+            view.resolve_for("Foo", filetype)
+                .and_then(|s| s.symbol.node.get_doc())
+        };
+        assert_eq!(
+            view.resolve("Foo").and_then(|s| s.symbol.node.get_doc()),
+            Some("Global")
+        );
+        assert_eq!(doc(Some("python")), Some("Python"));
+        assert_eq!(doc(Some("vim")), Some("Syntax"));
+        assert_eq!(doc(Some("lua")), Some("Global"));
+        assert_eq!(
+            view.effective_modules()
+                .iter()
+                .map(|m| m.module.path.clone().unwrap())
+                .collect::<Vec<_>>(),
+            vec![PathBuf::from("plugin/foo.vim")]
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{Symbol, VimModule, VimNode, VimPlugin};
+use regex::Regex;
+use std::cmp::Reverse;
+use std::ops::Range;
+
+/// A single match of a [VimPlugin::search_docs] query inside some doc.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DocMatch<'a> {
+    pub module: &'a VimModule,
+    /// The node whose doc matched, or None if the match is in the module doc.
+    pub node: Option<&'a VimNode>,
+    /// The full line of the doc containing the match.
+    pub line: &'a str,
+    /// Byte range of the match within `line`.
+    pub range: Range<usize>,
+}
+
+impl DocMatch<'_> {
+    /// Renders `line` as a snippet with the matched text wrapped in `open` and `close` markers.
+    pub fn highlighted(&self, open: &str, close: &str) -> String {
+        format!(
+            "{}{open}{}{close}{}",
+            &self.line[..self.range.start],
+            &self.line[self.range.clone()],
+            &self.line[self.range.end..],
+        )
+    }
+}
+
+/// A symbol matching a [VimPlugin::find_symbol_fuzzy] query.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FuzzyMatch<'a> {
+    pub symbol: Symbol<'a>,
+    /// Relative match quality. Higher is better.
+    pub score: i64,
+    /// Char indices within the symbol name of each matched query char.
+    pub positions: Vec<usize>,
+}
+
+impl VimPlugin {
+    /// Finds symbols whose names contain all chars of `query` in order (case-insensitively),
+    /// best matches first.
+    ///
+    /// Matches at word boundaries like the start of autoload segments or camelCase humps score
+    /// higher, so `fbbaz` matches `foo#bar#Baz` better than `foobarbaz`.
+    pub fn find_symbol_fuzzy(&self, query: &str) -> Vec<FuzzyMatch<'_>> {
+        let mut matches: Vec<_> = self
+            .symbols()
+            .filter_map(|symbol| {
+                fuzzy_score(query, symbol.name()).map(|(score, positions)| FuzzyMatch {
+                    symbol,
+                    score,
+                    positions,
+                })
+            })
+            .collect();
+        // Stable sort keeps definition order for ties.
+        matches.sort_by_key(|m| Reverse(m.score));
+        matches
+    }
+
+    /// Searches all module and node docs for the given regex pattern.
+    ///
+    /// Plain substrings work as patterns as long as they don't contain regex metacharacters
+    /// (which can be escaped with `regex::escape`). Matches never span multiple lines.
+    pub fn search_docs(&self, pattern: &str) -> crate::Result<Vec<DocMatch<'_>>> {
+        let re = Regex::new(pattern)?;
+        let mut matches = vec![];
+        for module in &self.content {
+            let docs = module
+                .doc
+                .as_deref()
+                .map(|doc| (None, doc))
+                .into_iter()
+                .chain(
+                    module
+                        .nodes
+                        .iter()
+                        .filter_map(|node| node.get_doc().map(|doc| (Some(node), doc))),
+                );
+            for (node, doc) in docs {
+                for line in doc.lines() {
+                    matches.extend(re.find_iter(line).map(|m| DocMatch {
+                        module,
+                        node,
+                        line,
+                        range: m.range(),
+                    }));
+                }
+            }
+        }
+        Ok(matches)
+    }
+}
+
+fn fuzzy_score(query: &str, name: &str) -> Option<(i64, Vec<usize>)> {
+    let name_chars: Vec<char> = name.chars().collect();
+    let mut positions = Vec::with_capacity(query.len());
+    let mut score = 0;
+    let mut next_idx = 0;
+    for query_char in query.chars() {
+        let idx = (next_idx..name_chars.len()).find(|&i| chars_match(query_char, name_chars[i]))?;
+        // Prefer a later match at a word boundary over an earlier one mid-word.
+        let idx = (idx..name_chars.len())
+            .filter(|&i| chars_match(query_char, name_chars[i]))
+            .find(|&i| is_word_start(&name_chars, i))
+            .filter(|&boundary_idx| {
+                // Only skip ahead if the rest of the query can still match afterwards.
+                idx == boundary_idx || {
+                    let rest = query.chars().skip(positions.len() + 1);
+                    is_subsequence(rest, &name_chars[boundary_idx + 1..])
+                }
+            })
+            .unwrap_or(idx);
+        score += 1;
+        if is_word_start(&name_chars, idx) {
+            score += 8;
+        }
+        if positions.last().is_some_and(|&last| last + 1 == idx) {
+            score += 4;
+        }
+        if query_char == name_chars[idx] {
+            score += 1;
+        }
+        positions.push(idx);
+        next_idx = idx + 1;
+    }
+    // Penalize unmatched chars so shorter names rank above longer ones.
+    score -= (name_chars.len() - positions.len()) as i64;
+    Some((score, positions))
+}
+
+fn chars_match(query_char: char, name_char: char) -> bool {
+    query_char.to_lowercase().eq(name_char.to_lowercase())
+}
+
+fn is_word_start(chars: &[char], idx: usize) -> bool {
+    match idx.checked_sub(1).map(|prev| chars[prev]) {
+        None => true,
+        Some('#' | ':' | '_' | '.') => true,
+        Some(prev) => prev.is_lowercase() && chars[idx].is_uppercase(),
+    }
+}
+
+fn is_subsequence<I: Iterator<Item = char>>(mut needle: I, haystack: &[char]) -> bool {
+    let mut haystack = haystack.iter();
+    needle.all(|c| haystack.any(|&h| chars_match(c, h)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn search_docs_substring() {
+        let plugin = VimPlugin::new(vec![VimModule::new(vec![
+            VimNode::function("foo#Format")
+                .doc("Formats the buffer.\nUses the formatter.")
+                .into(),
+            VimNode::command("Unrelated")
+                .doc("Does other stuff.")
+                .into(),
+        ])
+        .with_doc("Formatting plugin.")]);
+        let matches = plugin.search_docs("Format").unwrap();
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| (m.node.and_then(VimNode::name), m.highlighted("[", "]")))
+                .collect::<Vec<_>>(),
+            vec![
+                (None, "[Format]ting plugin.".to_string()),
+                (Some("foo#Format"), "[Format]s the buffer.".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn search_docs_regex() {
+        let plugin = VimPlugin::new(vec![VimModule::new(vec![VimNode::flag("someflag")
+            .doc("Set to 0 or 1.")
+            .into()])]);
+        let matches = plugin.search_docs(r"\d").unwrap();
+        assert_eq!(
+            matches.iter().map(|m| m.range.clone()).collect::<Vec<_>>(),
+            vec![7..8, 12..13]
+        );
+    }
+
+    #[test]
+    fn find_symbol_fuzzy_autoload_segments() {
+        let plugin = VimPlugin::new(vec![VimModule::new(vec![
+            VimNode::function("foobarbaz").into(),
+            VimNode::function("foo#bar#Baz").into(),
+            VimNode::function("foo#Quux").into(),
+        ])]);
+        let matches = plugin.find_symbol_fuzzy("fbbaz");
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| (m.symbol.name(), m.positions.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("foo#bar#Baz", vec![0, 4, 8, 9, 10]),
+                ("foobarbaz", vec![0, 3, 6, 7, 8]),
+            ]
+        );
+    }
+
+    #[test]
+    fn find_symbol_fuzzy_no_match() {
+        let plugin = VimPlugin::new(vec![VimModule::new(vec![
+            VimNode::command("SomeCommand").into()
+        ])]);
+        assert_eq!(plugin.find_symbol_fuzzy("xyz"), vec![]);
+    }
+
+    #[test]
+    fn search_docs_invalid_pattern() {
+        let plugin = VimPlugin::default();
+        assert!(matches!(
+            plugin.search_docs("("),
+            Err(crate::Error::InvalidPattern(_))
+        ));
+    }
+}
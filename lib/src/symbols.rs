@@ -0,0 +1,229 @@
+use crate::{VimModule, VimNode, VimPlugin};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// A named node defined somewhere in a plugin, along with the module defining it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Symbol<'a> {
+    pub module: &'a VimModule,
+    pub node: &'a VimNode,
+}
+
+impl<'a> Symbol<'a> {
+    pub fn name(&self) -> &'a str {
+        self.node
+            .name()
+            .expect("symbols are only created for named nodes")
+    }
+}
+
+/// A script-local function wired up to a user command via `<SID>`.
+///
+/// These functions are effectively public API (users invoke them through the command) even
+/// though they can't be addressed by name from outside their script.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SidCommandHandler<'a> {
+    pub command: Symbol<'a>,
+    /// The handler function name normalized to `s:` form, like `s:DoThing`.
+    pub function_name: String,
+    /// The function's definition, if found in the same module as the command.
+    pub definition: Option<Symbol<'a>>,
+}
+
+impl VimPlugin {
+    /// Iterates over all named nodes in the plugin (see [VimNode::name]), in module order.
+    ///
+    /// That's functions, commands, variables and flags, along with augroups, highlight groups
+    /// and signs. Autocmds, mappings and options aren't symbols.
+    pub fn symbols(&self) -> impl Iterator<Item = Symbol<'_>> {
+        self.content.iter().flat_map(|module| {
+            module
+                .nodes
+                .iter()
+                .filter(|node| node.name().is_some())
+                .map(move |node| Symbol { module, node })
+        })
+    }
+
+    /// Iterates over the plugin's functions, in module order.
+    pub fn functions(&self) -> impl Iterator<Item = Symbol<'_>> {
+        self.symbols()
+            .filter(|s| matches!(s.node, VimNode::Function { .. }))
+    }
+
+    /// Iterates over the plugin's user commands, in module order.
+    pub fn commands(&self) -> impl Iterator<Item = Symbol<'_>> {
+        self.symbols()
+            .filter(|s| matches!(s.node, VimNode::Command { .. }))
+    }
+
+    /// Iterates over the plugin's flags, in module order.
+    pub fn flags(&self) -> impl Iterator<Item = Symbol<'_>> {
+        self.symbols()
+            .filter(|s| matches!(s.node, VimNode::Flag { .. }))
+    }
+
+    /// Finds the first definition of the function named `name`, like `foo#bar#Baz`.
+    ///
+    /// Script-local functions match either spelling, so `s:Foo` finds a `<SID>Foo` definition
+    /// and vice versa. Since each script can define its own, this returns the first one.
+    pub fn find_function(&self, name: &str) -> Option<Symbol<'_>> {
+        let s_name = match name.get(..5) {
+            Some(sid) if sid.eq_ignore_ascii_case("<SID>") => format!("s:{}", &name[5..]),
+            _ => name.to_string(),
+        };
+        self.functions().find(|s| {
+            s.name() == name
+                || (s_name.starts_with("s:") && is_same_script_local(s.name(), &s_name))
+        })
+    }
+
+    /// Finds the first definition of the user command named `name`, with or without a leading
+    /// `:`.
+    pub fn find_command(&self, name: &str) -> Option<Symbol<'_>> {
+        let name = name.strip_prefix(':').unwrap_or(name);
+        self.commands().find(|s| s.name() == name)
+    }
+
+    /// Finds script-local functions that commands call through `<SID>Name` (or `s:Name`) in
+    /// their replacement text or use as their `-complete=custom` completion function.
+    pub fn sid_command_handlers(&self) -> Vec<SidCommandHandler<'_>> {
+        static SID_REF: OnceLock<Regex> = OnceLock::new();
+        let sid_ref =
+            SID_REF.get_or_init(|| Regex::new(r"(?i:<SID>|\bs:)([A-Za-z_][A-Za-z0-9_]*)").unwrap());
+        let mut handlers = vec![];
+        for command in self.symbols() {
+            let VimNode::Command { repl, .. } = command.node else {
+                continue;
+            };
+            let completion_function = command.node.completion_function();
+            let texts = repl
+                .iter()
+                .map(String::as_str)
+                .chain(completion_function.as_deref());
+            for captures in texts.flat_map(|text| sid_ref.captures_iter(text)) {
+                let function_name = format!("s:{}", &captures[1]);
+                if handlers.iter().any(|h: &SidCommandHandler| {
+                    h.command == command && h.function_name == function_name
+                }) {
+                    continue;
+                }
+                let definition = command
+                    .module
+                    .nodes
+                    .iter()
+                    .find(|node| match node {
+                        VimNode::Function { name, .. } => {
+                            is_same_script_local(name, &function_name)
+                        }
+                        _ => false,
+                    })
+                    .map(|node| Symbol {
+                        module: command.module,
+                        node,
+                    });
+                handlers.push(SidCommandHandler {
+                    command,
+                    function_name,
+                    definition,
+                });
+            }
+        }
+        handlers
+    }
+}
+
+/// Compares script-local function names, where `<SID>Foo` and `s:Foo` are equivalent.
+pub(crate) fn is_same_script_local(defined_name: &str, s_name: &str) -> bool {
+    let defined_name = match defined_name.get(..5) {
+        Some(sid) if sid.eq_ignore_ascii_case("<SID>") => &defined_name[5..],
+        _ => defined_name.strip_prefix("s:").unwrap_or(""),
+    };
+    s_name.strip_prefix("s:") == Some(defined_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn find_symbols_by_kind() {
+        let plugin = VimPlugin::new(vec![
+            VimModule::new(vec![
+                VimNode::command("Frob").into(),
+                VimNode::function("<SID>Helper").into(),
+                VimNode::flag("verbose").into(),
+            ])
+            .with_path("plugin/foo.vim"),
+            VimModule::new(vec![VimNode::function("foo#bar#Baz").into()])
+                .with_path("autoload/foo/bar.vim"),
+        ]);
+        assert_eq!(
+            plugin.functions().map(|s| s.name()).collect::<Vec<_>>(),
+            vec!["<SID>Helper", "foo#bar#Baz"]
+        );
+        assert_eq!(
+            plugin.commands().map(|s| s.name()).collect::<Vec<_>>(),
+            vec!["Frob"]
+        );
+        assert_eq!(
+            plugin.flags().map(|s| s.name()).collect::<Vec<_>>(),
+            vec!["verbose"]
+        );
+        let found = plugin.find_function("foo#bar#Baz").unwrap();
+        assert_eq!(
+            found.module.path.as_deref(),
+            Some(std::path::Path::new("autoload/foo/bar.vim"))
+        );
+        assert_eq!(
+            plugin.find_function("s:Helper").map(|s| s.name()),
+            Some("<SID>Helper")
+        );
+        assert_eq!(plugin.find_function("foo#bar#Missing"), None);
+        assert_eq!(plugin.find_command(":Frob").map(|s| s.name()), Some("Frob"));
+        let plugin = VimPlugin::new(vec![VimModule::new(vec![
+            VimNode::function("abcdé").into(),
+            VimNode::function("<SID>foo").into(),
+        ])]);
+        assert_eq!(
+            plugin.find_function("s:foo").map(|s| s.name()),
+            Some("<SID>foo")
+        );
+    }
+
+    #[test]
+    fn sid_command_handlers() {
+        let plugin = VimPlugin::new(vec![VimModule::new(vec![
+            VimNode::function("s:DoThing").into(),
+            VimNode::command("DoThing")
+                .repl("call <SID>DoThing(<q-args>)")
+                .into(),
+            VimNode::command("Other")
+                .repl("call s:Missing() | call foo#Public()")
+                .into(),
+            VimNode::function("s:Complete").into(),
+            VimNode::command("Pick")
+                .modifiers(["-complete=customlist,<SID>Complete"])
+                .repl("call foo#Pick(<q-args>)")
+                .into(),
+        ])
+        .with_path("plugin/foo.vim")]);
+        let handlers = plugin.sid_command_handlers();
+        assert_eq!(
+            handlers
+                .iter()
+                .map(|h| (
+                    h.command.name(),
+                    h.function_name.as_str(),
+                    h.definition.map(|d| d.name())
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("DoThing", "s:DoThing", Some("s:DoThing")),
+                ("Other", "s:Missing", None),
+                ("Pick", "s:Complete", Some("s:Complete")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! Internal derive macros for vim-plugin-metadata.
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Field, Fields, Ident};
+
+/// Exports an enum's variants and fields as a `macro_rules!` macro, so other crates can generate
+/// mirrored types (like the python bindings' classes) from the same definition.
+///
+/// For an enum `VimNode`, this defines `vim_node_variants!(callback, PREFIX…)`, which expands to
+/// `callback! { PREFIX… VimNode { Variant { field: Type, … }, … } }`. Doc comments on variants
+/// and fields are passed along; other attributes like serde's aren't.
+///
+/// Fields marked `#[export_variants(skip)]` are left out, so mirrored types can't convert back
+/// and matches on the original enum need a `..` rest pattern.
+#[proc_macro_derive(ExportVariants, attributes(export_variants))]
+pub fn export_variants(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let Data::Enum(data) = &input.data else {
+        return syn::Error::new_spanned(&input.ident, "ExportVariants only supports enums")
+            .to_compile_error()
+            .into();
+    };
+    let name = &input.ident;
+    let macro_name = format_ident!("{}_variants", snake_case(&name.to_string()));
+    let mut variants = vec![];
+    for variant in &data.variants {
+        let Fields::Named(fields) = &variant.fields else {
+            return syn::Error::new_spanned(variant, "ExportVariants only supports named fields")
+                .to_compile_error()
+                .into();
+        };
+        let docs = doc_attributes(&variant.attrs);
+        let ident = &variant.ident;
+        let fields = fields
+            .named
+            .iter()
+            .filter(|field| !is_skipped(field))
+            .map(|field| {
+                let docs = doc_attributes(&field.attrs);
+                let ident = &field.ident;
+                let ty = &field.ty;
+                quote! { #(#docs)* #ident: #ty }
+            });
+        variants.push(quote! { #(#docs)* #ident { #(#fields),* } });
+    }
+    quote! {
+        #[doc(hidden)]
+        #[macro_export]
+        macro_rules! #macro_name {
+            ($callback:ident $(, $($prefix:tt)*)?) => {
+                $callback! { $($($prefix)*)? #name { #(#variants),* } }
+            };
+        }
+    }
+    .into()
+}
+
+/// Whether a field is marked `#[export_variants(skip)]`.
+fn is_skipped(field: &Field) -> bool {
+    field.attrs.iter().any(|attr| {
+        attr.path().is_ident("export_variants")
+            && attr.parse_args::<Ident>().is_ok_and(|arg| arg == "skip")
+    })
+}
+
+fn doc_attributes(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("doc"))
+}
+
+/// Converts a type name like `VimNode` to `vim_node`.
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            snake.push('_');
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+    snake
+}
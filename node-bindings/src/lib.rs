@@ -0,0 +1,158 @@
+//! A library to parse and analyze your vim plugins, from Node.js.
+//!
+//! The main use case is to instantiate a VimParser and hand it the code of a module or a whole
+//! plugin to parse. Results are plain JS objects in the same shape as the rust crate's JSON
+//! serialization.
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// An entire vim plugin with all the metadata parsed from its files.
+#[napi]
+pub struct VimPlugin {
+    rust_plugin: vim_plugin_metadata::VimPlugin,
+}
+
+#[napi]
+impl VimPlugin {
+    /// The plugin's modules, as plain objects.
+    #[napi(getter)]
+    pub fn content(&self) -> Result<Value> {
+        to_js(&self.rust_plugin.content)
+    }
+
+    /// The whole plugin as a plain object, also used by `JSON.stringify`.
+    #[napi(js_name = "toJSON")]
+    pub fn to_json(&self) -> Result<Value> {
+        to_js(&self.rust_plugin)
+    }
+
+    /// Runs all lint rules over the plugin with their default severities.
+    #[napi]
+    pub fn lint(&self) -> Result<Value> {
+        to_js(&vim_plugin_metadata::lint::lint_plugin(&self.rust_plugin))
+    }
+
+    /// The fraction of the plugin's public symbols that have docs, or null if it has none.
+    #[napi]
+    pub fn doc_coverage(&self) -> Option<f64> {
+        self.rust_plugin.doc_coverage()
+    }
+}
+
+/// Differences in the symbols two versions of a plugin define, from diffPlugins.
+#[derive(serde::Serialize)]
+struct PluginDiff {
+    /// Symbols only in the new plugin.
+    added: Vec<vim_plugin_metadata::VimNode>,
+    /// Symbols only in the old plugin.
+    removed: Vec<vim_plugin_metadata::VimNode>,
+    /// Symbols in both that differ, as [old, new] pairs.
+    changed: Vec<(vim_plugin_metadata::VimNode, vim_plugin_metadata::VimNode)>,
+}
+
+impl From<vim_plugin_metadata::PluginDiff> for PluginDiff {
+    fn from(diff: vim_plugin_metadata::PluginDiff) -> Self {
+        Self {
+            added: diff.added,
+            removed: diff.removed,
+            changed: diff.changed,
+        }
+    }
+}
+
+/// Compares the symbols defined by an old and new version of a plugin.
+#[napi]
+pub fn diff_plugins(old: &VimPlugin, new: &VimPlugin) -> Result<Value> {
+    let diff: PluginDiff =
+        vim_plugin_metadata::diff_plugins(&old.rust_plugin, &new.rust_plugin).into();
+    to_js(&diff)
+}
+
+/// The main entry point for parsing plugins.
+#[napi]
+pub struct VimParser {
+    rust_parser: vim_plugin_metadata::VimParser,
+}
+
+#[napi]
+impl VimParser {
+    #[napi(constructor)]
+    pub fn new() -> Result<Self> {
+        let rust_parser = vim_plugin_metadata::VimParser::new().map_err(to_js_err)?;
+        Ok(Self { rust_parser })
+    }
+
+    /// Parses and returns metadata for a single module (a.k.a. file) of vimscript code.
+    #[napi]
+    pub fn parse_module_str(&self, code: String) -> Result<Value> {
+        let module = self
+            .rust_parser
+            .parse_module_str(&code)
+            .map_err(to_js_err)?;
+        to_js(&module)
+    }
+
+    /// Parses and returns metadata for the module (a.k.a. file) at the given path.
+    #[napi]
+    pub fn parse_module_file(&self, path: String) -> Result<Value> {
+        let module = self
+            .rust_parser
+            .parse_module_file(PathBuf::from(path))
+            .map_err(to_js_err)?;
+        to_js(&module)
+    }
+
+    /// Parses all supported metadata from a plugin directory, like parse_plugin_dir in the rust
+    /// crate.
+    #[napi]
+    pub fn parse_plugin_dir(&self, path: String) -> Result<VimPlugin> {
+        let rust_plugin = self
+            .rust_parser
+            .parse_plugin_dir(&PathBuf::from(path))
+            .map_err(to_js_err)?;
+        Ok(VimPlugin { rust_plugin })
+    }
+
+    /// Parses all supported metadata from a plugin given as an object mapping paths relative to
+    /// the plugin root (like "autoload/foo.vim") to their code.
+    ///
+    /// Useful for plugins that aren't on disk, like ones fetched from a registry. Modules come
+    /// out ordered by path, and paths outside the usual plugin subdirectories are parsed like any
+    /// other.
+    #[napi]
+    pub fn parse_plugin_files(&self, files: BTreeMap<String, String>) -> Result<VimPlugin> {
+        let content = files
+            .iter()
+            .map(|(path, code)| {
+                let language =
+                    vim_plugin_metadata::ModuleLanguage::detect(Some(Path::new(path)), code);
+                self.rust_parser
+                    .parse_module_str_as(code, language)
+                    .map(|module| module.with_path(path))
+                    .map_err(|err| {
+                        to_js_err(vim_plugin_metadata::Error::InFile {
+                            path: path.into(),
+                            position: None,
+                            source: Box::new(err),
+                        })
+                    })
+            })
+            .collect::<Result<_>>()?;
+        Ok(VimPlugin {
+            rust_plugin: vim_plugin_metadata::VimPlugin::new(content),
+        })
+    }
+}
+
+fn to_js_err(err: vim_plugin_metadata::Error) -> Error {
+    Error::from_reason(format!("{err}"))
+}
+
+/// Converts a value to a plain JS object in the same shape as its JSON serialization.
+fn to_js<T: serde::Serialize + ?Sized>(value: &T) -> Result<Value> {
+    serde_json::to_value(value).map_err(|err| Error::from_reason(format!("{err}")))
+}
@@ -0,0 +1,47 @@
+const assert = require("node:assert");
+const test = require("node:test");
+const { VimParser, diffPlugins } = require("..");
+
+test("parseModuleStr returns plain objects", () => {
+  const module = new VimParser().parseModuleStr(
+    '""\n" Does something cool.\nfunc MyFunc() abort\nendfunc\n',
+  );
+  assert.deepStrictEqual(module.nodes, [
+    {
+      kind: "Function",
+      name: "MyFunc",
+      args: [],
+      modifiers: ["abort"],
+      doc: "Does something cool.",
+    },
+  ]);
+});
+
+test("parsePluginFiles orders modules by path", () => {
+  const plugin = new VimParser().parsePluginFiles({
+    "plugin/foo.vim": "command Foo call foo#Bar()\n",
+    "autoload/foo.vim": "func foo#Bar()\nendfunc\n",
+  });
+  assert.deepStrictEqual(
+    plugin.content.map((module) => module.path),
+    ["autoload/foo.vim", "plugin/foo.vim"],
+  );
+  assert.deepStrictEqual(JSON.parse(JSON.stringify(plugin)).content, plugin.content);
+});
+
+test("diffPlugins reports added and removed symbols", () => {
+  const parser = new VimParser();
+  const old = parser.parsePluginFiles({ "plugin/foo.vim": "command Foo echo 1\n" });
+  const updated = parser.parsePluginFiles({ "plugin/foo.vim": "command Bar echo 1\n" });
+  const diff = diffPlugins(old, updated);
+  assert.deepStrictEqual(diff.added.map((node) => node.name), ["Bar"]);
+  assert.deepStrictEqual(diff.removed.map((node) => node.name), ["Foo"]);
+  assert.deepStrictEqual(diff.changed, []);
+});
+
+test("errors surface as exceptions", () => {
+  assert.throws(
+    () => new VimParser().parseModuleFile("/nonexistent/foo.vim"),
+    /nonexistent\/foo.vim: I\/O error/,
+  );
+});
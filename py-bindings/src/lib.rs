@@ -1,155 +1,284 @@
 use pyo3::prelude::*;
 use std::path::PathBuf;
 
+/// Defines a python enum class mirroring a rust enum, with a `__repr__` and a `From` conversion
+/// from the rust type.
+///
+/// Invoked with the variants and fields exported from the rust enum's definition, like
+/// `vim_plugin_metadata::vim_node_variants!(vim_node_classes)`, so the two can't drift apart.
+///
+/// Fields that are None are left out of the repr. Extra methods for the class can be passed in an
+/// `@impl { … }` block after its doc comment.
+macro_rules! vim_node_classes {
+    (
+        $(#[$meta:meta])*
+        $(@impl { $($methods:tt)* })?
+        $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident {
+                    $($(#[$field_meta:meta])* $field:ident: $ty:ty),* $(,)?
+                }
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[pyclass(eq)]
+        #[derive(Clone, Debug, PartialEq)]
+        pub enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant { $($(#[$field_meta])* $field: $ty),* },
+            )*
+        }
+
+        #[pymethods]
+        impl $name {
+            pub fn __repr__(&self) -> String {
+                match self {
+                    $(
+                        Self::$variant { $($field),* } => {
+                            let args: Vec<String> = [
+                                $(PyRepr::py_repr($field)
+                                    .map(|repr| format!("{}={repr}", stringify!($field))),)*
+                            ]
+                            .into_iter()
+                            .flatten()
+                            .collect();
+                            format!("{}({})", stringify!($variant), args.join(", "))
+                        }
+                    )*
+                }
+            }
+
+            $($($methods)*)?
+        }
+
+        impl From<vim_plugin_metadata::$name> for $name {
+            fn from(value: vim_plugin_metadata::$name) -> Self {
+                match value {
+                    $(
+                        // Fields the rust crate doesn't export, like spans, are left behind.
+                        vim_plugin_metadata::$name::$variant { $($field,)* .. } => {
+                            Self::$variant { $($field),* }
+                        }
+                    )*
+                    // The enum is non_exhaustive, but the variants above come from its own
+                    // definition.
+                    _ => unreachable!("unexported {} variant", stringify!($name)),
+                }
+            }
+        }
+    };
+}
+
 /// A library to parse and analyze your vim plugins.
 ///
 /// The main use case is to instantiate a VimParser, configure it, and point
 /// it to a plugin dir or file to parse.
 #[pymodule(name = "vim_plugin_metadata")]
+// False positive from pyo3's generated wrappers for PyResult-returning methods.
+#[allow(clippy::useless_conversion)]
 mod py_vim_plugin_metadata {
     use super::*;
-    use pyo3::exceptions::{PyException, PyIOError};
+    use pyo3::exceptions::{PyException, PyIndexError, PyKeyError};
     use vim_plugin_metadata;
 
-    /// A representation of a single high-level grammar token of vim syntax,
-    /// such as a comment or function.
-    #[pyclass]
+    vim_plugin_metadata::vim_node_variants!(
+        vim_node_classes,
+        /// A representation of a single high-level grammar token of vim syntax,
+        /// such as a comment or function.
+        @impl {
+            /// Parsed modifiers if this is a Function.
+            #[getter]
+            pub fn get_function_modifiers(&self) -> Option<FunctionModifiers> {
+                match self {
+                    Self::Function { modifiers, .. } => Some(
+                        vim_plugin_metadata::FunctionModifiers::parse(modifiers).into(),
+                    ),
+                    _ => None,
+                }
+            }
+
+            /// Parsed attributes if this is a Command.
+            #[getter]
+            pub fn get_command_attributes(&self) -> Option<CommandAttributes> {
+                match self {
+                    Self::Command { modifiers, .. } => Some(
+                        vim_plugin_metadata::CommandAttributes::parse(modifiers).into(),
+                    ),
+                    _ => None,
+                }
+            }
+
+            /// Parsed arguments if this is a Mapping.
+            #[getter]
+            pub fn get_map_arguments(&self) -> Option<MapArguments> {
+                match self {
+                    Self::Mapping { arguments, .. } => {
+                        Some(vim_plugin_metadata::MapArguments::parse(arguments).into())
+                    }
+                    _ => None,
+                }
+            }
+        }
+    );
+
+    // Classes defined through macros aren't registered automatically.
+    #[pymodule_init]
+    fn init(m: &Bound<'_, PyModule>) -> PyResult<()> {
+        m.add_class::<VimNode>()
+    }
+
+    /// Parsed form of Function modifiers like "!", "range" and "abort".
+    #[pyclass(eq, get_all)]
     #[derive(Clone, Debug, PartialEq)]
-    pub enum VimNode {
-        StandaloneDocComment {
-            doc: String,
-        },
-        Function {
-            name: String,
-            args: Vec<String>,
-            modifiers: Vec<String>,
-            doc: Option<String>,
-        },
-        Command {
-            name: String,
-            modifiers: Vec<String>,
-            doc: Option<String>,
-        },
-        Variable {
-            name: String,
-            init_value_token: String,
-            doc: Option<String>,
-        },
-        /// A defined "Flag" like the mechanism used in google/vim-maktaba.
-        Flag {
-            name: String,
-            default_value_token: Option<String>,
-            doc: Option<String>,
-        },
+    pub struct FunctionModifiers {
+        pub bang: bool,
+        pub range: bool,
+        pub dict: bool,
+        pub abort: bool,
+        pub closure: bool,
+        /// Any modifiers not recognized above, in their raw form.
+        pub unknown: Vec<String>,
     }
 
     #[pymethods]
-    impl VimNode {
+    impl FunctionModifiers {
         pub fn __repr__(&self) -> String {
-            match &self {
-                Self::StandaloneDocComment { doc } => {
-                    format!("StandaloneDocComment(doc={doc:?})")
-                }
-                Self::Function {
-                    name,
-                    args,
-                    modifiers,
-                    doc,
-                } => {
-                    let mut args_str =
-                        format!("name={name:?}, args={args:?}, modifiers={modifiers:?}");
-                    if let Some(doc) = doc {
-                        args_str.push_str(format!(", doc={doc:?}").as_str());
-                    }
-                    format!("Function({args_str})")
-                }
-                Self::Command {
-                    name,
-                    modifiers,
-                    doc,
-                } => {
-                    let mut args_str = format!("name={name:?}, modifiers={modifiers:?}");
-                    if let Some(doc) = doc {
-                        args_str.push_str(format!(", doc={doc:?}").as_str());
-                    }
-                    format!("Command({args_str})")
-                }
-                Self::Variable {
-                    name,
-                    init_value_token,
-                    doc,
-                } => {
-                    let mut args_str =
-                        format!("name={name:?}, init_value_token={init_value_token:?}");
-                    if let Some(doc) = doc {
-                        args_str.push_str(format!(", doc={doc:?}").as_str());
-                    }
-                    format!("Flag({args_str})")
-                }
-                Self::Flag {
-                    name,
-                    default_value_token,
-                    doc,
-                } => {
-                    let mut args_str = format!("name={name:?}");
-                    if let Some(default_value_token) = default_value_token {
-                        args_str.push_str(
-                            format!(", default_value_token={default_value_token:?}").as_str(),
-                        );
-                    }
-                    if let Some(doc) = doc {
-                        args_str.push_str(format!(", doc={doc:?}").as_str());
-                    }
-                    format!("Flag({args_str})")
-                }
+            struct_repr(
+                "FunctionModifiers",
+                [
+                    ("bang", self.bang.py_repr()),
+                    ("range", self.range.py_repr()),
+                    ("dict", self.dict.py_repr()),
+                    ("abort", self.abort.py_repr()),
+                    ("closure", self.closure.py_repr()),
+                    ("unknown", self.unknown.py_repr()),
+                ],
+            )
+        }
+    }
+
+    impl From<vim_plugin_metadata::FunctionModifiers> for FunctionModifiers {
+        fn from(modifiers: vim_plugin_metadata::FunctionModifiers) -> Self {
+            Self {
+                bang: modifiers.bang,
+                range: modifiers.range,
+                dict: modifiers.dict,
+                abort: modifiers.abort,
+                closure: modifiers.closure,
+                unknown: modifiers.unknown,
             }
         }
     }
 
-    impl From<vim_plugin_metadata::VimNode> for VimNode {
-        fn from(n: vim_plugin_metadata::VimNode) -> Self {
-            match n {
-                vim_plugin_metadata::VimNode::StandaloneDocComment { doc } => {
-                    Self::StandaloneDocComment { doc }
-                }
-                vim_plugin_metadata::VimNode::Function {
-                    name,
-                    args,
-                    modifiers,
-                    doc,
-                } => Self::Function {
-                    name,
-                    args,
-                    modifiers,
-                    doc,
-                },
-                vim_plugin_metadata::VimNode::Command {
-                    name,
-                    modifiers,
-                    doc,
-                } => Self::Command {
-                    name,
-                    modifiers,
-                    doc,
-                },
-                vim_plugin_metadata::VimNode::Flag {
-                    name,
-                    default_value_token,
-                    doc,
-                } => Self::Flag {
-                    name,
-                    default_value_token,
-                    doc,
-                },
-                vim_plugin_metadata::VimNode::Variable {
-                    name,
-                    init_value_token,
-                    doc,
-                } => Self::Variable {
-                    name,
-                    init_value_token,
-                    doc,
-                },
+    /// Parsed form of Command attributes like "-nargs=*" and "-bang".
+    ///
+    /// Attributes that take an optional value ("-range", "-count") are recorded with an empty
+    /// string when given without one.
+    #[pyclass(eq, get_all)]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct CommandAttributes {
+        pub nargs: Option<String>,
+        pub complete: Option<String>,
+        pub range: Option<String>,
+        pub count: Option<String>,
+        pub addr: Option<String>,
+        pub bang: bool,
+        pub bar: bool,
+        pub register: bool,
+        pub buffer: bool,
+        pub keepscript: bool,
+        /// Any attributes not recognized above, in their raw form.
+        pub unknown: Vec<String>,
+    }
+
+    #[pymethods]
+    impl CommandAttributes {
+        pub fn __repr__(&self) -> String {
+            struct_repr(
+                "CommandAttributes",
+                [
+                    ("nargs", self.nargs.py_repr()),
+                    ("complete", self.complete.py_repr()),
+                    ("range", self.range.py_repr()),
+                    ("count", self.count.py_repr()),
+                    ("addr", self.addr.py_repr()),
+                    ("bang", self.bang.py_repr()),
+                    ("bar", self.bar.py_repr()),
+                    ("register", self.register.py_repr()),
+                    ("buffer", self.buffer.py_repr()),
+                    ("keepscript", self.keepscript.py_repr()),
+                    ("unknown", self.unknown.py_repr()),
+                ],
+            )
+        }
+    }
+
+    impl From<vim_plugin_metadata::CommandAttributes> for CommandAttributes {
+        fn from(attributes: vim_plugin_metadata::CommandAttributes) -> Self {
+            Self {
+                nargs: attributes.nargs,
+                complete: attributes.complete,
+                range: attributes.range,
+                count: attributes.count,
+                addr: attributes.addr,
+                bang: attributes.bang,
+                bar: attributes.bar,
+                register: attributes.register,
+                buffer: attributes.buffer,
+                keepscript: attributes.keepscript,
+                unknown: attributes.unknown,
+            }
+        }
+    }
+
+    /// Parsed form of Mapping arguments like "<silent>" and "<buffer>".
+    #[pyclass(eq, get_all)]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct MapArguments {
+        pub buffer: bool,
+        pub nowait: bool,
+        pub silent: bool,
+        pub special: bool,
+        pub script: bool,
+        pub expr: bool,
+        pub unique: bool,
+        /// Any arguments not recognized above, in their raw form.
+        pub unknown: Vec<String>,
+    }
+
+    #[pymethods]
+    impl MapArguments {
+        pub fn __repr__(&self) -> String {
+            struct_repr(
+                "MapArguments",
+                [
+                    ("buffer", self.buffer.py_repr()),
+                    ("nowait", self.nowait.py_repr()),
+                    ("silent", self.silent.py_repr()),
+                    ("special", self.special.py_repr()),
+                    ("script", self.script.py_repr()),
+                    ("expr", self.expr.py_repr()),
+                    ("unique", self.unique.py_repr()),
+                    ("unknown", self.unknown.py_repr()),
+                ],
+            )
+        }
+    }
+
+    impl From<vim_plugin_metadata::MapArguments> for MapArguments {
+        fn from(arguments: vim_plugin_metadata::MapArguments) -> Self {
+            Self {
+                buffer: arguments.buffer,
+                nowait: arguments.nowait,
+                silent: arguments.silent,
+                special: arguments.special,
+                script: arguments.script,
+                expr: arguments.expr,
+                unique: arguments.unique,
+                unknown: arguments.unknown,
             }
         }
     }
@@ -217,6 +346,8 @@ mod py_vim_plugin_metadata {
     pub struct VimPlugin {
         #[pyo3(get)]
         pub content: Vec<VimModule>,
+        /// The original plugin, kept for analyses that need more than the converted content.
+        rust_plugin: vim_plugin_metadata::VimPlugin,
     }
 
     #[pymethods]
@@ -231,6 +362,19 @@ mod py_vim_plugin_metadata {
                     .join(", ")
             )
         }
+
+        /// Runs all lint rules over the plugin with their default severities.
+        pub fn lint(&self) -> Vec<Finding> {
+            vim_plugin_metadata::lint::lint_plugin(&self.rust_plugin)
+                .into_iter()
+                .map(Finding::from)
+                .collect()
+        }
+
+        /// The fraction of the plugin's public symbols that have docs, or None if it has none.
+        pub fn doc_coverage(&self) -> Option<f64> {
+            self.rust_plugin.doc_coverage()
+        }
     }
 
     impl From<vim_plugin_metadata::VimPlugin> for VimPlugin {
@@ -238,13 +382,250 @@ mod py_vim_plugin_metadata {
             Self {
                 content: plugin
                     .content
-                    .into_iter()
+                    .iter()
+                    .cloned()
                     .map(|section| section.into())
                     .collect(),
+                rust_plugin: plugin,
+            }
+        }
+    }
+
+    /// A plugin whose modules are parsed lazily as they're accessed, opened with Plugin.open.
+    ///
+    /// Handy for exploring huge runtime trees interactively without waiting for a full scan.
+    #[pyclass]
+    pub struct Plugin {
+        root: PathBuf,
+        paths: Vec<PathBuf>,
+        /// Results for the modules in `paths` parsed so far, by index.
+        modules: Vec<Option<PyResult<VimModule>>>,
+        lazy_modules: vim_plugin_metadata::PluginModules,
+    }
+
+    #[pymethods]
+    impl Plugin {
+        /// Lists the modules of the plugin at the given path without parsing any of them.
+        #[staticmethod]
+        pub fn open(path: PathBuf) -> PyResult<Self> {
+            let lazy_modules = vim_plugin_metadata::VimParser::new()
+                .and_then(|parser| parser.iter_plugin_modules(&path))
+                .map_err(to_py_err)?;
+            let paths: Vec<PathBuf> = lazy_modules.remaining_paths().map(PathBuf::from).collect();
+            Ok(Self {
+                root: path,
+                modules: paths.iter().map(|_| None).collect(),
+                paths,
+                lazy_modules,
+            })
+        }
+
+        #[getter]
+        pub fn get_root(&self) -> PathBuf {
+            self.root.clone()
+        }
+
+        /// Paths of all the plugin's modules, without parsing them.
+        ///
+        /// Note: returned as PurePaths relative to the plugin root dir.
+        #[getter]
+        pub fn get_paths(&self) -> PyResult<Vec<PyObject>> {
+            self.paths.iter().cloned().map(as_py_purepath).collect()
+        }
+
+        /// All of the plugin's modules, parsing any that haven't been yet.
+        #[getter]
+        pub fn get_content(&mut self) -> PyResult<Vec<VimModule>> {
+            (0..self.paths.len()).map(|i| self.parsed(i)).collect()
+        }
+
+        /// The module at the given path relative to the plugin root, parsing it if it hasn't been
+        /// yet.
+        pub fn module(&mut self, path: PathBuf) -> PyResult<VimModule> {
+            let index = self
+                .paths
+                .iter()
+                .position(|p| *p == path)
+                .ok_or_else(|| PyKeyError::new_err(format!("No module at {path:?}")))?;
+            self.parsed(index)
+        }
+
+        pub fn __len__(&self) -> usize {
+            self.paths.len()
+        }
+
+        pub fn __getitem__(&mut self, index: usize) -> PyResult<VimModule> {
+            if index >= self.paths.len() {
+                return Err(PyIndexError::new_err("module index out of range"));
+            }
+            self.parsed(index)
+        }
+
+        pub fn __repr__(&self) -> String {
+            format!(
+                "Plugin(root={:?}, parsed={}/{})",
+                self.root.to_string_lossy(),
+                self.modules
+                    .iter()
+                    .filter(|module| module.is_some())
+                    .count(),
+                self.paths.len()
+            )
+        }
+    }
+
+    impl Plugin {
+        /// Gets the module at `index`, parsing it if it hasn't been yet.
+        fn parsed(&mut self, index: usize) -> PyResult<VimModule> {
+            let lazy_modules = &self.lazy_modules;
+            let path = &self.paths[index];
+            let result = self.modules[index].get_or_insert_with(|| {
+                lazy_modules
+                    .parse_module(path)
+                    .map(VimModule::from)
+                    .map_err(to_py_err)
+            });
+            match result {
+                Ok(module) => Ok(module.clone()),
+                Err(err) => Err(Python::with_gil(|py| err.clone_ref(py))),
+            }
+        }
+    }
+
+    /// Differences in the symbols two versions of a plugin define, from diff_plugins.
+    #[pyclass]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct PluginDiff {
+        /// Symbols only in the new plugin.
+        #[pyo3(get)]
+        pub added: Vec<VimNode>,
+        /// Symbols only in the old plugin.
+        #[pyo3(get)]
+        pub removed: Vec<VimNode>,
+        /// Symbols in both that differ, as (old, new) pairs.
+        #[pyo3(get)]
+        pub changed: Vec<(VimNode, VimNode)>,
+    }
+
+    #[pymethods]
+    impl PluginDiff {
+        pub fn __repr__(&self) -> String {
+            let reprs = |nodes: &[VimNode]| {
+                nodes
+                    .iter()
+                    .map(VimNode::__repr__)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            let changed = self
+                .changed
+                .iter()
+                .map(|(old, new)| format!("({}, {})", old.__repr__(), new.__repr__()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "PluginDiff(added=[{}], removed=[{}], changed=[{changed}])",
+                reprs(&self.added),
+                reprs(&self.removed),
+            )
+        }
+    }
+
+    impl From<vim_plugin_metadata::PluginDiff> for PluginDiff {
+        fn from(diff: vim_plugin_metadata::PluginDiff) -> Self {
+            Self {
+                added: diff.added.into_iter().map(VimNode::from).collect(),
+                removed: diff.removed.into_iter().map(VimNode::from).collect(),
+                changed: diff
+                    .changed
+                    .into_iter()
+                    .map(|(old, new)| (old.into(), new.into()))
+                    .collect(),
             }
         }
     }
 
+    /// Compares the symbols defined by an old and new version of a plugin.
+    #[pyfunction]
+    pub fn diff_plugins(old: &VimPlugin, new: &VimPlugin) -> PluginDiff {
+        vim_plugin_metadata::diff_plugins(&old.rust_plugin, &new.rust_plugin).into()
+    }
+
+    /// A problem reported by a lint rule or other analysis.
+    #[pyclass]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Finding {
+        finding: vim_plugin_metadata::lint::Finding,
+    }
+
+    #[pymethods]
+    impl Finding {
+        #[getter]
+        pub fn get_rule_id(&self) -> &str {
+            &self.finding.rule_id
+        }
+
+        /// One of "info", "warning" or "error".
+        #[getter]
+        pub fn get_severity(&self) -> &'static str {
+            match self.finding.severity {
+                vim_plugin_metadata::lint::Severity::Info => "info",
+                vim_plugin_metadata::lint::Severity::Warning => "warning",
+                vim_plugin_metadata::lint::Severity::Error => "error",
+            }
+        }
+
+        #[getter]
+        pub fn get_message(&self) -> &str {
+            &self.finding.message
+        }
+
+        /// Note: returned as a PurePath relative to the plugin root dir.
+        #[getter]
+        pub fn get_file(&self) -> PyResult<Option<PyObject>> {
+            self.finding.file.clone().map(as_py_purepath).transpose()
+        }
+
+        /// The 0-based ((start_row, start_col), (end_row, end_col)) of the finding, if known.
+        #[getter]
+        pub fn get_range(&self) -> Option<((usize, usize), (usize, usize))> {
+            self.finding
+                .range
+                .as_ref()
+                .map(|span| (span.start, span.end))
+        }
+
+        #[getter]
+        pub fn get_suggestion(&self) -> Option<&str> {
+            self.finding.suggestion.as_deref()
+        }
+
+        /// Serializes the finding in the same JSON format used by the rust crate.
+        pub fn to_json(&self) -> PyResult<String> {
+            serde_json::to_string(&self.finding)
+                .map_err(|err| PyException::new_err(format!("{err}")))
+        }
+
+        pub fn __repr__(&self) -> String {
+            let mut args_str = format!(
+                "rule_id={:?}, severity={:?}, message={:?}",
+                self.finding.rule_id,
+                self.get_severity(),
+                self.finding.message
+            );
+            if let Some(file) = &self.finding.file {
+                args_str.push_str(format!(", file={:?}", file.to_string_lossy()).as_str());
+            }
+            format!("Finding({args_str})")
+        }
+    }
+
+    impl From<vim_plugin_metadata::lint::Finding> for Finding {
+        fn from(finding: vim_plugin_metadata::lint::Finding) -> Self {
+            Self { finding }
+        }
+    }
+
     /// The main entry point for parsing plugins.
     #[pyclass]
     #[derive(Default)]
@@ -262,21 +643,16 @@ mod py_vim_plugin_metadata {
         }
 
         /// Parses all supported metadata from a single plugin at the given path.
-        pub fn parse_plugin_dir(&mut self, path: PathBuf) -> PyResult<VimPlugin> {
+        pub fn parse_plugin_dir(&self, path: PathBuf) -> PyResult<VimPlugin> {
             let plugin = self
                 .rust_parser
                 .parse_plugin_dir(&path)
-                .map_err(|err| match err {
-                    vim_plugin_metadata::Error::IOError(io_error) => {
-                        PyIOError::new_err(format!("{io_error}"))
-                    }
-                    _ => PyException::new_err(format!("{err}")),
-                })?;
+                .map_err(to_py_err)?;
             Ok(plugin.into())
         }
 
         /// Parses and returns metadata for a single module (a.k.a. file) of vimscript code.
-        pub fn parse_module_file(&mut self, path: PathBuf) -> PyResult<VimModule> {
+        pub fn parse_module_file(&self, path: PathBuf) -> PyResult<VimModule> {
             let module = self
                 .rust_parser
                 .parse_module_file(&path)
@@ -285,7 +661,7 @@ mod py_vim_plugin_metadata {
         }
 
         /// Parses and returns metadata for a single module (a.k.a. file) of vimscript code.
-        pub fn parse_module_str(&mut self, code: &str) -> PyResult<VimModule> {
+        pub fn parse_module_str(&self, code: &str) -> PyResult<VimModule> {
             let module = self
                 .rust_parser
                 .parse_module_str(code)
@@ -295,9 +671,57 @@ mod py_vim_plugin_metadata {
     }
 }
 
+/// Converts a rust error into a python IOError for I/O failures or a generic Exception otherwise.
+fn to_py_err(err: vim_plugin_metadata::Error) -> PyErr {
+    match err.inner() {
+        vim_plugin_metadata::Error::IOError(_) => {
+            pyo3::exceptions::PyIOError::new_err(format!("{err}"))
+        }
+        _ => pyo3::exceptions::PyException::new_err(format!("{err}")),
+    }
+}
+
 fn as_py_purepath(path: PathBuf) -> PyResult<PyObject> {
     Python::with_gil(|py| {
         let pathlib = PyModule::import_bound(py, "pathlib")?;
         Ok(pathlib.getattr("PurePath")?.call1((path,))?.unbind())
     })
 }
+
+/// Formats a python-style repr like `Name(field=value, …)`, leaving out fields without a value.
+fn struct_repr<const N: usize>(name: &str, fields: [(&str, Option<String>); N]) -> String {
+    let args: Vec<String> = fields
+        .into_iter()
+        .filter_map(|(field, repr)| Some(format!("{field}={}", repr?)))
+        .collect();
+    format!("{name}({})", args.join(", "))
+}
+
+/// Formats a field value for a python-style repr, or None to leave it out.
+trait PyRepr {
+    fn py_repr(&self) -> Option<String>;
+}
+
+impl PyRepr for String {
+    fn py_repr(&self) -> Option<String> {
+        Some(format!("{self:?}"))
+    }
+}
+
+impl PyRepr for bool {
+    fn py_repr(&self) -> Option<String> {
+        Some(if *self { "True" } else { "False" }.to_string())
+    }
+}
+
+impl PyRepr for Vec<String> {
+    fn py_repr(&self) -> Option<String> {
+        Some(format!("{self:?}"))
+    }
+}
+
+impl<T: PyRepr> PyRepr for Option<T> {
+    fn py_repr(&self) -> Option<String> {
+        self.as_ref().and_then(T::py_repr)
+    }
+}
@@ -1,7 +1,7 @@
 from abc import ABC
 from dataclasses import dataclass
 import os
-from typing import List, Optional, Union
+from typing import List, Literal, Optional, Tuple, Union
 
 class VimParser:
     def __init__(self): ...
@@ -9,7 +9,26 @@ class VimParser:
     def parse_module_file(self, path: Union[str, os.PathLike]) -> VimModule: ...
     def parse_module_str(self, code: str) -> VimModule: ...
 
+class Plugin:
+    @staticmethod
+    def open(path: Union[str, os.PathLike]) -> Plugin: ...
+    @property
+    def root(self) -> str: ...
+    @property
+    def paths(self) -> List[os.PathLike]: ...
+    @property
+    def content(self) -> List[VimModule]: ...
+    def module(self, path: Union[str, os.PathLike]) -> VimModule: ...
+    def __len__(self) -> int: ...
+    def __getitem__(self, index: int) -> VimModule: ...
+
 class VimNode(ABC):
+    @property
+    def function_modifiers(self) -> Optional[FunctionModifiers]: ...
+    @property
+    def command_attributes(self) -> Optional[CommandAttributes]: ...
+    @property
+    def map_arguments(self) -> Optional[MapArguments]: ...
     @dataclass
     class StandaloneDocComment(VimNode):
         doc: str
@@ -23,6 +42,7 @@ class VimNode(ABC):
     class Command(VimNode):
         name: str
         modifiers: List[str]
+        repl: Optional[str]
         doc: Optional[str]
     @dataclass
     class Variable(VimNode):
@@ -34,10 +54,106 @@ class VimNode(ABC):
         name: str
         default_value_token: Optional[str]
         doc: Optional[str]
+    @dataclass
+    class Augroup(VimNode):
+        name: str
+        doc: Optional[str]
+    @dataclass
+    class Autocmd(VimNode):
+        group: Optional[str]
+        events: List[str]
+        patterns: List[str]
+        command: str
+        doc: Optional[str]
+    @dataclass
+    class Mapping(VimNode):
+        mode: str
+        noremap: bool
+        lhs: str
+        rhs: str
+        arguments: List[str]
+        doc: Optional[str]
+    @dataclass
+    class Highlight(VimNode):
+        name: str
+        default: bool
+        link: Optional[str]
+        attributes: List[str]
+        doc: Optional[str]
+    @dataclass
+    class Sign(VimNode):
+        name: str
+        attributes: List[str]
+        doc: Optional[str]
+    @dataclass
+    class SetOption(VimNode):
+        name: str
+        item: str
+        value: Optional[str]
+        scope: str
+        doc: Optional[str]
+
+class FunctionModifiers:
+    bang: bool
+    range: bool
+    dict: bool
+    abort: bool
+    closure: bool
+    unknown: List[str]
+
+class CommandAttributes:
+    nargs: Optional[str]
+    complete: Optional[str]
+    range: Optional[str]
+    count: Optional[str]
+    addr: Optional[str]
+    bang: bool
+    bar: bool
+    register: bool
+    buffer: bool
+    keepscript: bool
+    unknown: List[str]
+
+class MapArguments:
+    buffer: bool
+    nowait: bool
+    silent: bool
+    special: bool
+    script: bool
+    expr: bool
+    unique: bool
+    unknown: List[str]
 
 class VimPlugin:
     @property
     def content(self) -> List[VimModule]: ...
+    def lint(self) -> List[Finding]: ...
+    def doc_coverage(self) -> Optional[float]: ...
+
+class PluginDiff:
+    @property
+    def added(self) -> List[VimNode]: ...
+    @property
+    def removed(self) -> List[VimNode]: ...
+    @property
+    def changed(self) -> List[Tuple[VimNode, VimNode]]: ...
+
+def diff_plugins(old: VimPlugin, new: VimPlugin) -> PluginDiff: ...
+
+class Finding:
+    @property
+    def rule_id(self) -> str: ...
+    @property
+    def severity(self) -> Literal["info", "warning", "error"]: ...
+    @property
+    def message(self) -> str: ...
+    @property
+    def file(self) -> Optional[os.PathLike]: ...
+    @property
+    def range(self) -> Optional[Tuple[Tuple[int, int], Tuple[int, int]]]: ...
+    @property
+    def suggestion(self) -> Optional[str]: ...
+    def to_json(self) -> str: ...
 
 class VimModule:
     @property